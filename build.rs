@@ -1,5 +1,8 @@
 fn main() {
-    slint_build::compile("ui/app_window.slint").unwrap();
+    // UI translations: lang/<language>/LC_MESSAGES/quick-findr.po, built into
+    // the binary.
+    let config = slint_build::CompilerConfiguration::new().with_bundled_translations("lang");
+    slint_build::compile_with_config("ui/app_window.slint", config).unwrap();
 
     // Windows icon configuration (optional)
     #[cfg(target_os = "windows")]
@@ -0,0 +1,50 @@
+// Accent folding for diacritic-insensitive search: "café" -> "cafe".
+// Compatibility decomposition (NFKD) followed by removal of combining marks.
+
+use std::borrow::Cow;
+use std::ops::Range;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+pub fn fold(text: &str) -> Cow<'_, str> {
+    if text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(text.nfkd().filter(|c| !is_combining_mark(*c)).collect())
+}
+
+// Folded text plus, for each of its bytes, the byte range of the original
+// character it comes from (to map match ranges back onto the original).
+pub fn fold_with_offsets(text: &str) -> (String, Vec<Range<usize>>) {
+    let mut folded = String::with_capacity(text.len());
+    let mut origins = Vec::with_capacity(text.len());
+    for (start, c) in text.char_indices() {
+        let origin = start..start + c.len_utf8();
+        for folded_char in c.nfkd().filter(|c| !is_combining_mark(*c)) {
+            folded.push(folded_char);
+            origins.extend(std::iter::repeat_n(origin.clone(), folded_char.len_utf8()));
+        }
+    }
+    (folded, origins)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold() {
+        assert_eq!(fold("café_menu.txt"), "cafe_menu.txt");
+        assert_eq!(fold("Ångström Œuvre naïve"), "Angstrom Œuvre naive");
+        assert!(matches!(fold("plain"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_fold_with_offsets() {
+        let (folded, origins) = fold_with_offsets("le café");
+        assert_eq!(folded, "le cafe");
+        // The folded "e" comes from the two bytes of "é".
+        assert_eq!(origins[6], 6..8);
+        assert_eq!(origins.len(), folded.len());
+    }
+}
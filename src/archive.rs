@@ -0,0 +1,76 @@
+// Entries of zip-based archives (.zip, .jar, .war, .ear), searched like the
+// files of a folder when the "Archives" option is on. An entry is reported as
+// "archive.zip!/inner/path"; nested archives are not opened.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+const EXTENSIONS: &[&str] = &["zip", "jar", "war", "ear"];
+
+// Between the archive path and the path of an entry inside it.
+pub const SEPARATOR: &str = "!/";
+
+pub fn is_archive(extension: &str) -> bool {
+    EXTENSIONS
+        .iter()
+        .any(|archive| extension.eq_ignore_ascii_case(archive))
+}
+
+pub fn entry_path(archive: &str, inner: &str) -> String {
+    format!("{}{}{}", archive, SEPARATOR, inner)
+}
+
+// "C:\docs\a.zip!/x/y.txt" -> ("C:\docs\a.zip", "x/y.txt"); None for a path
+// that does not point inside an archive.
+pub fn split_entry_path(path: &str) -> Option<(&str, &str)> {
+    path.match_indices(SEPARATOR).find_map(|(at, _)| {
+        let archive = &path[..at];
+        let extension = Path::new(archive).extension()?.to_str()?;
+        is_archive(extension).then(|| (archive, &path[at + SEPARATOR.len()..]))
+    })
+}
+
+pub fn is_entry_path(path: &str) -> bool {
+    split_entry_path(path).is_some()
+}
+
+// Calls `visit` with the inner path, uncompressed size and reader of every file
+// entry. Entries that cannot be read (encrypted, unsupported compression) are
+// skipped; an unreadable archive is an error.
+pub fn for_each_file(
+    path: &Path,
+    mut visit: impl FnMut(&str, u64, &mut dyn Read),
+) -> zip::result::ZipResult<()> {
+    let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+    for i in 0..archive.len() {
+        let Ok(mut entry) = archive.by_index(i) else {
+            continue;
+        };
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        visit(&name, entry.size(), &mut entry);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_entry_path() {
+        assert_eq!(
+            split_entry_path(r"C:\docs\lib.JAR!/com/app/Main.class"),
+            Some((r"C:\docs\lib.JAR", "com/app/Main.class"))
+        );
+        assert_eq!(
+            split_entry_path("/tmp/a!/b.zip!/c.txt"),
+            Some(("/tmp/a!/b.zip", "c.txt"))
+        );
+        assert_eq!(split_entry_path("/tmp/wow!/notes.txt"), None);
+        assert!(!is_entry_path("/tmp/archive.zip"));
+    }
+}
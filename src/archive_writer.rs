@@ -0,0 +1,219 @@
+// Zip archives of checked results ("Ajouter au zip"). Files keep their path
+// relative to the search root; files outside it are stored under their name.
+// A checked folder brings the files below it (empty folders are left out).
+
+use chrono::{DateTime, Datelike, Local, Timelike};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+#[derive(Debug, Default)]
+pub struct ZipReport {
+    pub added: usize,
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+// Name of a file in the archive: its path below `root`, with `/` separators.
+pub fn entry_name(root: &Path, path: &Path) -> String {
+    let relative = path
+        .strip_prefix(root)
+        .ok()
+        .filter(|relative| !relative.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new(path.file_name().unwrap_or_default()));
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// The checked files, and the files below the checked folders (in name order).
+// A file checked twice (itself and through its folder) is listed once.
+pub fn collect_files(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            push_tree(path, &mut files);
+        } else {
+            files.push(path.clone());
+        }
+    }
+    let mut seen = HashSet::new();
+    files.retain(|file| seen.insert(file.clone()));
+    files
+}
+
+// Symlinked folders are not followed (they may loop).
+fn push_tree(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut entries: Vec<_> = entries.flatten().collect();
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(kind) if kind.is_dir() => push_tree(&path, files),
+            Ok(_) if !path.is_dir() => files.push(path),
+            _ => {}
+        }
+    }
+}
+
+// Writes `files` to a new zip at `destination`, calling `progress` with the
+// number of files done so far. Files that cannot be read are reported and left
+// out. When `cancelled` returns true the partial archive is removed and
+// `Interrupted` is returned.
+pub fn write_zip(
+    destination: &Path,
+    root: &Path,
+    files: &[PathBuf],
+    mut progress: impl FnMut(usize),
+    cancelled: impl Fn() -> bool,
+) -> io::Result<ZipReport> {
+    let result = write_entries(destination, root, files, &mut progress, &cancelled);
+    if result.is_err() {
+        let _ = fs::remove_file(destination);
+    }
+    result
+}
+
+fn write_entries(
+    destination: &Path,
+    root: &Path,
+    files: &[PathBuf],
+    progress: &mut impl FnMut(usize),
+    cancelled: &impl Fn() -> bool,
+) -> io::Result<ZipReport> {
+    let mut writer = ZipWriter::new(File::create(destination)?);
+    let mut report = ZipReport::default();
+    // Entry names already used, compared like the Windows file system does.
+    let mut names = HashSet::new();
+
+    for (index, path) in files.iter().enumerate() {
+        if cancelled() {
+            return Err(io::ErrorKind::Interrupted.into());
+        }
+        let name = entry_name(root, path);
+        let added = if path == destination {
+            Err("the archive itself".to_string())
+        } else if !names.insert(name.to_lowercase()) {
+            Err("another file has this path in the archive".to_string())
+        } else {
+            add_file(&mut writer, path, &name).map_err(|err| err.to_string())
+        };
+        match added {
+            Ok(()) => report.added += 1,
+            Err(reason) => report.failed.push((path.clone(), reason)),
+        }
+        progress(index + 1);
+    }
+
+    writer.finish()?;
+    Ok(report)
+}
+
+fn add_file(writer: &mut ZipWriter<File>, path: &Path, name: &str) -> io::Result<()> {
+    let mut file = File::open(path)?;
+    let metadata = file.metadata()?;
+    let mut options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(metadata.len() >= u64::from(u32::MAX));
+    if let Some(time) = metadata.modified().ok().and_then(zip_time) {
+        options = options.last_modified_time(time);
+    }
+
+    writer.start_file(name, options)?;
+    if let Err(err) = io::copy(&mut file, writer) {
+        // Drop the truncated entry.
+        let _ = writer.abort_file();
+        return Err(err);
+    }
+    Ok(())
+}
+
+// Zip times are local, from 1980 to 2107.
+fn zip_time(time: SystemTime) -> Option<zip::DateTime> {
+    let time: DateTime<Local> = time.into();
+    zip::DateTime::from_date_and_time(
+        u16::try_from(time.year()).ok()?,
+        time.month() as u8,
+        time.day() as u8,
+        time.hour() as u8,
+        time.minute() as u8,
+        time.second() as u8,
+    )
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_root;
+    use std::io::Read;
+
+    #[test]
+    fn test_entry_name() {
+        let root = Path::new("/data/project");
+        assert_eq!(
+            entry_name(root, Path::new("/data/project/src/main.rs")),
+            "src/main.rs"
+        );
+        assert_eq!(
+            entry_name(root, Path::new("/elsewhere/notes.txt")),
+            "notes.txt"
+        );
+    }
+
+    #[test]
+    fn test_write_zip_keeps_relative_paths() {
+        let root = temp_root("zip-write");
+        fs::create_dir_all(root.join("docs/old")).unwrap();
+        fs::write(root.join("readme.txt"), "hello").unwrap();
+        fs::write(root.join("docs/a.txt"), "A").unwrap();
+        fs::write(root.join("docs/old/b.txt"), "B").unwrap();
+        let destination = std::env::temp_dir().join("quick-findr-zip-write.zip");
+
+        let files = collect_files(&[
+            root.join("readme.txt"),
+            root.join("docs"),
+            root.join("docs/a.txt"),
+            root.join("missing.txt"),
+        ]);
+        let mut done = 0;
+        let report = write_zip(&destination, &root, &files, |n| done = n, || false).unwrap();
+        assert_eq!(report.added, 3);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(done, 4);
+
+        let mut archive = zip::ZipArchive::new(File::open(&destination).unwrap()).unwrap();
+        let mut names: Vec<&str> = archive.file_names().collect();
+        names.sort();
+        assert_eq!(names, vec!["docs/a.txt", "docs/old/b.txt", "readme.txt"]);
+        let mut text = String::new();
+        archive
+            .by_name("docs/old/b.txt")
+            .unwrap()
+            .read_to_string(&mut text)
+            .unwrap();
+        assert_eq!(text, "B");
+        let _ = fs::remove_file(&destination);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_cancel_removes_the_archive() {
+        let root = temp_root("zip-cancel");
+        fs::write(root.join("a.txt"), "A").unwrap();
+        let destination = std::env::temp_dir().join("quick-findr-zip-cancel.zip");
+
+        let result = write_zip(&destination, &root, &[root.join("a.txt")], |_| {}, || true);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::Interrupted);
+        assert!(!destination.exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
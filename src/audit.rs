@@ -0,0 +1,339 @@
+use crate::compare::csv_field;
+use crate::hashing::to_hex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// One line of the audit log (JSON Lines). Each entry hashes its content together
+// with the previous entry's hash, so editing or removing a past entry breaks
+// the chain from that point on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: u64,
+    pub user: String,
+    pub action: String,
+    pub scope: String,
+    pub details: String,
+    pub result_count: usize,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AuditEntry {
+    fn compute_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        for field in [
+            self.timestamp.to_string().as_str(),
+            &self.user,
+            &self.action,
+            &self.scope,
+            &self.details,
+            self.result_count.to_string().as_str(),
+            &self.prev_hash,
+        ] {
+            hasher.update(field.as_bytes());
+            hasher.update([0u8]);
+        }
+        to_hex(&hasher.finalize())
+    }
+}
+
+// Last link of the chain, also kept in a file next to the log ("audit.head"):
+// entries cut from the end of the log leave a valid chain, but not this count.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainHead {
+    // Lines written to the log.
+    pub count: usize,
+    pub hash: String,
+}
+
+// What the application reports; time, user and hashes are filled in by `append`.
+pub struct AuditEvent {
+    pub action: &'static str,
+    pub scope: String,
+    pub details: String,
+    pub result_count: usize,
+}
+
+pub fn log_path() -> PathBuf {
+    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("quick-findr");
+    path.push("audit.log");
+    path
+}
+
+fn current_user() -> String {
+    std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or_default()
+}
+
+fn head_path(log: &Path) -> PathBuf {
+    log.with_extension("head")
+}
+
+// The head file of the log (None for a log written before it existed).
+pub fn read_head(log: &Path) -> io::Result<Option<ChainHead>> {
+    match fs::read_to_string(head_path(log)) {
+        Ok(text) => serde_json::from_str(&text)
+            .map(Some)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+// Appends to the log at `path`. The head of the chain is kept between entries,
+// so the log itself is only read once, for a log without a head file.
+pub struct AuditLog {
+    path: PathBuf,
+    head: Option<ChainHead>,
+}
+
+impl AuditLog {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, head: None }
+    }
+
+    pub fn append(&mut self, event: AuditEvent) -> io::Result<AuditEntry> {
+        let head = match &self.head {
+            Some(head) => head.clone(),
+            None => match read_head(&self.path)? {
+                Some(head) => head,
+                None => {
+                    let content = read_entries(&self.path)?;
+                    ChainHead {
+                        count: content.line_count(),
+                        hash: content
+                            .entries
+                            .last()
+                            .map(|entry| entry.hash.clone())
+                            .unwrap_or_default(),
+                    }
+                }
+            },
+        };
+
+        let entry = new_entry(event, head.hash);
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+
+        let head = ChainHead {
+            count: head.count + 1,
+            hash: entry.hash.clone(),
+        };
+        let written = fs::write(head_path(&self.path), serde_json::to_string(&head)?);
+        self.head = Some(head);
+        written.map(|()| entry)
+    }
+}
+
+fn new_entry(event: AuditEvent, prev_hash: String) -> AuditEntry {
+    let mut entry = AuditEntry {
+        timestamp: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        user: current_user(),
+        action: event.action.to_string(),
+        scope: event.scope,
+        details: event.details,
+        result_count: event.result_count,
+        prev_hash,
+        hash: String::new(),
+    };
+    entry.hash = entry.compute_hash();
+    entry
+}
+
+#[derive(Debug, Default)]
+pub struct LogContent {
+    pub entries: Vec<AuditEntry>,
+    // 1-based numbers of the lines that are not entries (left out of `entries`).
+    pub bad_lines: Vec<usize>,
+}
+
+impl LogContent {
+    pub fn line_count(&self) -> usize {
+        self.entries.len() + self.bad_lines.len()
+    }
+
+    // Whether the log ends where its head file says it does.
+    pub fn matches_head(&self, head: &ChainHead) -> bool {
+        self.line_count() == head.count
+            && self
+                .entries
+                .last()
+                .is_some_and(|entry| entry.hash == head.hash)
+    }
+}
+
+// Reads the whole log (empty when it does not exist yet).
+pub fn read_entries(path: &Path) -> io::Result<LogContent> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(LogContent::default()),
+        Err(err) => return Err(err),
+    };
+
+    let mut content = LogContent::default();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(entry) => content.entries.push(entry),
+            Err(_) => content.bad_lines.push(index + 1),
+        }
+    }
+    Ok(content)
+}
+
+// Index of the first entry whose hash or link to its predecessor does not check out.
+pub fn verify_chain(entries: &[AuditEntry]) -> Option<usize> {
+    let mut prev_hash = "";
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.prev_hash != prev_hash || entry.hash != entry.compute_hash() {
+            return Some(index);
+        }
+        prev_hash = &entry.hash;
+    }
+    None
+}
+
+// Writes the log as CSV (RFC 4180 quoting); times are Unix seconds.
+pub fn write_csv<W: Write>(entries: &[AuditEntry], writer: &mut W) -> io::Result<()> {
+    writeln!(
+        writer,
+        "timestamp,user,action,scope,details,result_count,hash"
+    )?;
+    for entry in entries {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{}",
+            entry.timestamp,
+            csv_field(&entry.user),
+            csv_field(&entry.action),
+            csv_field(&entry.scope),
+            csv_field(&entry.details),
+            entry.result_count,
+            entry.hash
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_root;
+
+    fn temp_log(name: &str) -> PathBuf {
+        temp_root(&format!("audit-{}", name)).join("audit.log")
+    }
+
+    fn event(action: &'static str, count: usize) -> AuditEvent {
+        AuditEvent {
+            action,
+            scope: "/data".to_string(),
+            details: "query=\"invoice\"".to_string(),
+            result_count: count,
+        }
+    }
+
+    #[test]
+    fn test_append_builds_a_valid_chain() {
+        let log = temp_log("chain");
+        AuditLog::new(log.clone())
+            .append(event("search", 3))
+            .unwrap();
+        // A new session picks the chain up from the head file.
+        AuditLog::new(log.clone())
+            .append(event("export", 3))
+            .unwrap();
+
+        let content = read_entries(&log).unwrap();
+        let entries = &content.entries;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].prev_hash, entries[0].hash);
+        assert_eq!(verify_chain(entries), None);
+        assert!(content.matches_head(&read_head(&log).unwrap().unwrap()));
+        let _ = fs::remove_dir_all(log.parent().unwrap());
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let log = temp_log("tamper");
+        let mut audit_log = AuditLog::new(log.clone());
+        audit_log.append(event("search", 3)).unwrap();
+        audit_log.append(event("delete", 1)).unwrap();
+        audit_log.append(event("search", 8)).unwrap();
+
+        let mut entries = read_entries(&log).unwrap().entries;
+        entries[1].result_count = 0;
+        assert_eq!(verify_chain(&entries), Some(1));
+
+        let mut entries = read_entries(&log).unwrap().entries;
+        entries.remove(1);
+        assert_eq!(verify_chain(&entries), Some(1));
+
+        // Cutting the last entry keeps the chain valid, not the head.
+        let text = fs::read_to_string(&log).unwrap();
+        let kept: Vec<&str> = text.lines().take(2).collect();
+        fs::write(&log, kept.join("\n") + "\n").unwrap();
+        let content = read_entries(&log).unwrap();
+        assert_eq!(verify_chain(&content.entries), None);
+        assert!(!content.matches_head(&read_head(&log).unwrap().unwrap()));
+        let _ = fs::remove_dir_all(log.parent().unwrap());
+    }
+
+    #[test]
+    fn test_bad_line_is_reported() {
+        let log = temp_log("bad-line");
+        let mut audit_log = AuditLog::new(log.clone());
+        audit_log.append(event("search", 3)).unwrap();
+        let mut file = OpenOptions::new().append(true).open(&log).unwrap();
+        writeln!(file, "{{not json").unwrap();
+        // Without the head file, the log is read back to continue the chain.
+        fs::remove_file(head_path(&log)).unwrap();
+        AuditLog::new(log.clone())
+            .append(event("export", 3))
+            .unwrap();
+
+        let content = read_entries(&log).unwrap();
+        assert_eq!(content.entries.len(), 2);
+        assert_eq!(content.bad_lines, vec![2]);
+        assert_eq!(read_head(&log).unwrap().unwrap().count, 3);
+        let _ = fs::remove_dir_all(log.parent().unwrap());
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let entry = AuditEntry {
+            timestamp: 10,
+            user: "bob".to_string(),
+            action: "search".to_string(),
+            scope: "C:\\data".to_string(),
+            details: "query=\"a,b\"".to_string(),
+            result_count: 2,
+            prev_hash: String::new(),
+            hash: "abc".to_string(),
+        };
+
+        let mut out = Vec::new();
+        write_csv(&[entry], &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "timestamp,user,action,scope,details,result_count,hash\n10,bob,search,C:\\data,\"query=\"\"a,b\"\"\",2,abc\n"
+        );
+    }
+}
@@ -0,0 +1,159 @@
+// Colors of the UI the user can change in the settings: the accent, and the
+// background of the window, of its bars and of its dialogs (for brand colors,
+// or a pure black theme on OLED screens), and the badge color of each
+// extension.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub type Rgb = (u8, u8, u8);
+
+// As typed in the settings ("#RRGGBB"); empty keeps the theme color.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomColors {
+    pub accent: String,
+    pub background: String,
+    pub surface: String,
+    pub panel: String,
+}
+
+// Colors applied to the UI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub accent: Rgb,
+    // Window background.
+    pub background: Rgb,
+    // Header, footer and detail pane.
+    pub surface: Rgb,
+    // Dialogs and menus.
+    pub panel: Rgb,
+}
+
+impl Palette {
+    pub fn default_for(dark: bool) -> Self {
+        if dark {
+            Self {
+                accent: (0x00, 0x78, 0xd4),
+                background: (0x1e, 0x1e, 0x1e),
+                surface: (0x25, 0x25, 0x25),
+                panel: (0x2d, 0x2d, 0x2d),
+            }
+        } else {
+            Self {
+                accent: (0x00, 0x78, 0xd4),
+                background: (0xf9, 0xf9, 0xf9),
+                surface: (0xff, 0xff, 0xff),
+                panel: (0xff, 0xff, 0xff),
+            }
+        }
+    }
+
+    // The theme colors with the valid custom ones in their place.
+    pub fn resolve(custom: &CustomColors, dark: bool) -> Self {
+        let default = Self::default_for(dark);
+        let pick = |text: &str, default: Rgb| parse_hex(text).unwrap_or(default);
+        Self {
+            accent: pick(&custom.accent, default.accent),
+            background: pick(&custom.background, default.background),
+            surface: pick(&custom.surface, default.surface),
+            panel: pick(&custom.panel, default.panel),
+        }
+    }
+}
+
+// Badge of the extensions missing from the map.
+pub const DEFAULT_ICON_COLOR: Rgb = (128, 128, 128);
+
+// Badge colors by extension until the settings file says otherwise (its
+// [icon_colors] table, where extensions can be added).
+pub fn default_icon_colors() -> BTreeMap<String, String> {
+    let groups: [(&[&str], &str); 12] = [
+        (&["rs"], "#dea584"),                          // Rust
+        (&["js", "ts", "jsx", "tsx"], "#f1e05a"),      // JS/TS
+        (&["html", "css", "scss"], "#e34c26"),         // Web
+        (&["json", "toml", "yaml", "yml"], "#854cc7"), // Config
+        (&["md", "txt"], "#007acc"),                   // Docs
+        (&["pdf"], "#b40f0f"),                         // PDF
+        (&["zip", "tar", "gz"], "#ffc800"),            // Archive
+        (&["png", "jpg", "jpeg", "svg"], "#64c864"),   // Images
+        (&["java", "kt"], "#b46432"),                  // JVM
+        (&["py"], "#3572a5"),                          // Python
+        (&["c", "cpp", "h"], "#555555"),               // C/C++
+        (&["exe", "dll", "bat", "ps1"], "#0078d4"),    // System
+    ];
+    groups
+        .iter()
+        .flat_map(|(extensions, color)| {
+            extensions
+                .iter()
+                .map(|extension| (extension.to_string(), color.to_string()))
+        })
+        .collect()
+}
+
+// Badge color of `extension`; map keys may be in any case, with a dot or not.
+pub fn icon_color(colors: &BTreeMap<String, String>, extension: &str) -> Rgb {
+    colors
+        .iter()
+        .find(|(key, _)| key.trim_start_matches('.').eq_ignore_ascii_case(extension))
+        .and_then(|(_, color)| parse_hex(color))
+        .unwrap_or(DEFAULT_ICON_COLOR)
+}
+
+// "#0078D4" or "0078d4".
+pub fn parse_hex(text: &str) -> Option<Rgb> {
+    let hex = text.trim();
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!(parse_hex("#0078D4"), Some((0x00, 0x78, 0xd4)));
+        assert_eq!(parse_hex(" ff8000 "), Some((0xff, 0x80, 0x00)));
+        assert_eq!(parse_hex("#fff"), None);
+        assert_eq!(parse_hex("#12345g"), None);
+        assert_eq!(parse_hex(""), None);
+    }
+
+    #[test]
+    fn test_icon_color() {
+        let mut colors = default_icon_colors();
+        assert_eq!(icon_color(&colors, "RS"), (222, 165, 132));
+        assert_eq!(icon_color(&colors, "tsx"), (241, 224, 90));
+        assert_eq!(icon_color(&colors, "vue"), DEFAULT_ICON_COLOR);
+
+        colors.insert(".Vue".to_string(), "#41b883".to_string());
+        colors.insert("rs".to_string(), "oops".to_string());
+        assert_eq!(icon_color(&colors, "vue"), (0x41, 0xb8, 0x83));
+        assert_eq!(icon_color(&colors, "rs"), DEFAULT_ICON_COLOR);
+    }
+
+    #[test]
+    fn test_resolve() {
+        let custom = CustomColors {
+            accent: "#e81123".to_string(),
+            background: "#000000".to_string(),
+            surface: "not a color".to_string(),
+            panel: String::new(),
+        };
+        let palette = Palette::resolve(&custom, true);
+        assert_eq!(palette.accent, (0xe8, 0x11, 0x23));
+        assert_eq!(palette.background, (0, 0, 0));
+        assert_eq!(palette.surface, Palette::default_for(true).surface);
+        assert_eq!(palette.panel, Palette::default_for(true).panel);
+        assert_eq!(
+            Palette::resolve(&CustomColors::default(), false),
+            Palette::default_for(false)
+        );
+    }
+}
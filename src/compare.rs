@@ -0,0 +1,329 @@
+use crate::engine::{update_ui, SearchResult, SearchToken};
+use crate::hashing::{hash_file, HashAlgorithm};
+use crate::i18n::tr;
+use ignore::WalkBuilder;
+use rayon::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    OnlyInA,
+    OnlyInB,
+    Different,
+}
+
+impl DiffKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::OnlyInA => "Only in A",
+            Self::OnlyInB => "Only in B",
+            Self::Different => "Different",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiffEntry {
+    pub relative_path: String,
+    pub kind: DiffKind,
+    pub detail: String,
+    pub path_a: Option<PathBuf>,
+    pub path_b: Option<PathBuf>,
+}
+
+impl DiffEntry {
+    // Row shown in the results list (opens the copy from A when there is one).
+    pub fn to_search_result(&self) -> SearchResult {
+        let path = self.path_a.as_ref().or(self.path_b.as_ref());
+        let file_name = self
+            .relative_path
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let line_match = if self.detail.is_empty() {
+            self.kind.label().to_string()
+        } else {
+            format!("{}: {}", self.kind.label(), self.detail)
+        };
+
+        SearchResult {
+            file_name,
+            relative_path: self.relative_path.clone(),
+            line_match,
+            ..path
+                .map(|path| SearchResult::for_path(path, Path::new("")))
+                .unwrap_or_default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareMethod {
+    // Size, then modification time.
+    SizeAndTime,
+    // Size, then SHA-256 of the content.
+    Hash,
+}
+
+struct FileInfo {
+    path: PathBuf,
+    size: u64,
+    modified: Option<SystemTime>,
+}
+
+// Indexes the files under `root` by their `/`-separated relative path.
+fn list_files(
+    root: &Path,
+    respect_gitignore: bool,
+    token: &SearchToken,
+) -> HashMap<String, FileInfo> {
+    let mut builder = WalkBuilder::new(root);
+    builder.hidden(false).git_ignore(respect_gitignore);
+
+    let mut files = HashMap::new();
+    for entry in builder.build() {
+        if token.is_cancelled() {
+            break;
+        }
+        let Ok(dir_entry) = entry else { continue };
+        let Ok(metadata) = dir_entry.metadata() else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+
+        let relative = dir_entry
+            .path()
+            .strip_prefix(root)
+            .unwrap_or(dir_entry.path())
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        files.insert(
+            relative,
+            FileInfo {
+                path: dir_entry.into_path(),
+                size: metadata.len(),
+                modified: metadata.modified().ok(),
+            },
+        );
+    }
+    files
+}
+
+// Compares two folder trees; identical files are left out of the report.
+pub fn compare_dirs(
+    root_a: &Path,
+    root_b: &Path,
+    method: CompareMethod,
+    respect_gitignore: bool,
+    token: &SearchToken,
+) -> Vec<DiffEntry> {
+    let files_a = list_files(root_a, respect_gitignore, token);
+    let files_b = list_files(root_b, respect_gitignore, token);
+
+    let all_paths: BTreeSet<&String> = files_a.keys().chain(files_b.keys()).collect();
+
+    all_paths
+        .into_par_iter()
+        .filter_map(|relative| {
+            if token.is_cancelled() {
+                return None;
+            }
+
+            let a = files_a.get(relative);
+            let b = files_b.get(relative);
+            let (kind, detail) = match (a, b) {
+                (Some(_), None) => (DiffKind::OnlyInA, String::new()),
+                (None, Some(_)) => (DiffKind::OnlyInB, String::new()),
+                (Some(a), Some(b)) => (DiffKind::Different, file_difference(a, b, method)?),
+                (None, None) => return None,
+            };
+
+            Some(DiffEntry {
+                relative_path: relative.clone(),
+                kind,
+                detail,
+                path_a: a.map(|f| f.path.clone()),
+                path_b: b.map(|f| f.path.clone()),
+            })
+        })
+        .collect()
+}
+
+// Describes how two files differ, or None when they are considered identical.
+fn file_difference(a: &FileInfo, b: &FileInfo, method: CompareMethod) -> Option<String> {
+    if a.size != b.size {
+        return Some(format!("size {} B vs {} B", a.size, b.size));
+    }
+
+    match method {
+        CompareMethod::SizeAndTime => {
+            (a.modified != b.modified).then(|| "modification time differs".to_string())
+        }
+        CompareMethod::Hash => {
+            let hash_a = hash_file(&a.path, HashAlgorithm::Sha256).ok();
+            let hash_b = hash_file(&b.path, HashAlgorithm::Sha256).ok();
+            (hash_a.is_none() || hash_a != hash_b).then(|| "content differs".to_string())
+        }
+    }
+}
+
+// Writes the report as CSV (RFC 4180 quoting).
+pub fn write_csv<W: Write>(entries: &[DiffEntry], writer: &mut W) -> io::Result<()> {
+    writeln!(writer, "status,relative_path,detail,path_a,path_b")?;
+    for entry in entries {
+        let path_a = entry
+            .path_a
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let path_b = entry
+            .path_b
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        writeln!(
+            writer,
+            "{},{},{},{},{}",
+            csv_field(entry.kind.label()),
+            csv_field(&entry.relative_path),
+            csv_field(&entry.detail),
+            csv_field(&path_a),
+            csv_field(&path_b)
+        )?;
+    }
+    Ok(())
+}
+
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// Spawns the folder comparison in a separate thread.
+pub fn spawn_compare(
+    root_a: PathBuf,
+    root_b: PathBuf,
+    method: CompareMethod,
+    respect_gitignore: bool,
+    sender: slint::Weak<crate::AppWindow>,
+    token: SearchToken,
+) {
+    std::thread::spawn(move || {
+        let start_time = Instant::now();
+
+        let mut entries = compare_dirs(&root_a, &root_b, method, respect_gitignore, &token);
+        entries.sort_by(|x, y| x.relative_path.cmp(&y.relative_path));
+
+        let duration = start_time.elapsed().as_millis() as u64;
+        update_ui(&token, &sender, move |window| {
+            let count = entries.len();
+            crate::set_compare_report(window, entries);
+            window
+                .set_status_text(tr!("Completed: {} differences in {}ms", count, duration).into());
+            window.set_active_threads(0);
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_root;
+    use std::fs;
+
+    // Folders "a" and "b" of a comparison, each with a "sub" folder.
+    fn compare_root(name: &str) -> PathBuf {
+        let root = temp_root(&format!("compare-{}", name));
+        fs::create_dir_all(root.join("a/sub")).unwrap();
+        fs::create_dir_all(root.join("b/sub")).unwrap();
+        root
+    }
+
+    fn kinds(entries: &[DiffEntry]) -> Vec<(String, DiffKind)> {
+        let mut kinds: Vec<_> = entries
+            .iter()
+            .map(|e| (e.relative_path.clone(), e.kind))
+            .collect();
+        kinds.sort_by(|x, y| x.0.cmp(&y.0));
+        kinds
+    }
+
+    #[test]
+    fn test_compare_dirs_by_hash() {
+        let root = compare_root("hash");
+        fs::write(root.join("a/same.txt"), "same").unwrap();
+        fs::write(root.join("b/same.txt"), "same").unwrap();
+        fs::write(root.join("a/sub/changed.txt"), "abc").unwrap();
+        fs::write(root.join("b/sub/changed.txt"), "abd").unwrap();
+        fs::write(root.join("a/left.txt"), "x").unwrap();
+        fs::write(root.join("b/right.txt"), "x").unwrap();
+
+        let entries = compare_dirs(
+            &root.join("a"),
+            &root.join("b"),
+            CompareMethod::Hash,
+            false,
+            &SearchToken::default(),
+        );
+
+        assert_eq!(
+            kinds(&entries),
+            vec![
+                ("left.txt".to_string(), DiffKind::OnlyInA),
+                ("right.txt".to_string(), DiffKind::OnlyInB),
+                ("sub/changed.txt".to_string(), DiffKind::Different),
+            ]
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_compare_dirs_size_difference() {
+        let root = compare_root("size");
+        fs::write(root.join("a/file.txt"), "short").unwrap();
+        fs::write(root.join("b/file.txt"), "much longer").unwrap();
+
+        let entries = compare_dirs(
+            &root.join("a"),
+            &root.join("b"),
+            CompareMethod::SizeAndTime,
+            false,
+            &SearchToken::default(),
+        );
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].detail, "size 5 B vs 11 B");
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_write_csv_quotes_fields() {
+        let entries = vec![DiffEntry {
+            relative_path: "dir/a,b.txt".to_string(),
+            kind: DiffKind::OnlyInA,
+            detail: String::new(),
+            path_a: Some(PathBuf::from("/x/dir/a,b.txt")),
+            path_b: None,
+        }];
+
+        let mut out = Vec::new();
+        write_csv(&entries, &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+
+        assert_eq!(
+            csv,
+            "status,relative_path,detail,path_a,path_b\nOnly in A,\"dir/a,b.txt\",,\"/x/dir/a,b.txt\",\n"
+        );
+    }
+}
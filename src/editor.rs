@@ -0,0 +1,86 @@
+// "Open in editor": a command line built from a template such as
+// `code -g {file}:{line}:{column}`. The template is split into arguments
+// first (double quotes group words), then the placeholders are filled, so a
+// path with spaces stays one argument.
+
+use std::process::Command;
+
+pub const DEFAULT_COMMAND: &str = "code -g {file}:{line}:{column}";
+
+// Words of the template; `"..."` is one word, without its quotes.
+fn split_args(template: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quoted = false;
+    for c in template.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.get_or_insert_with(String::new);
+            }
+            c if c.is_whitespace() && !quoted => {
+                args.extend(current.take());
+            }
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    args.extend(current);
+    args
+}
+
+// Program and arguments for a file at a line (1-based; column 0 is the start
+// of the line).
+pub fn command_line(template: &str, file: &str, line: usize, column: usize) -> Vec<String> {
+    split_args(template)
+        .into_iter()
+        .map(|arg| {
+            arg.replace("{file}", file)
+                .replace("{line}", &line.max(1).to_string())
+                .replace("{column}", &column.max(1).to_string())
+        })
+        .collect()
+}
+
+pub fn open(template: &str, file: &str, line: usize, column: usize) -> Result<(), String> {
+    let args = command_line(template, file, line, column);
+    let Some((program, rest)) = args.split_first() else {
+        return Err("no editor command in the settings".to_string());
+    };
+    // Editors are often started by a .cmd script (`code.cmd`), which only the
+    // command interpreter runs.
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(program);
+        command
+    } else {
+        Command::new(program)
+    };
+    command
+        .args(rest)
+        .spawn()
+        .map(drop)
+        .map_err(|err| format!("cannot start {}: {}", program, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_line() {
+        assert_eq!(
+            command_line(DEFAULT_COMMAND, r"C:\My Docs\main.rs", 12, 5),
+            vec!["code", "-g", r"C:\My Docs\main.rs:12:5"]
+        );
+        assert_eq!(
+            command_line(
+                r#""C:\Program Files\Notepad++\notepad++.exe" -n{line} {file}"#,
+                "a.txt",
+                0,
+                0
+            ),
+            vec![r"C:\Program Files\Notepad++\notepad++.exe", "-n1", "a.txt"]
+        );
+        assert!(command_line("   ", "a.txt", 1, 1).is_empty());
+    }
+}
@@ -0,0 +1,412 @@
+// Searchable text of email files: the main headers, one per line, then the
+// text of the body. `.eml` files are parsed as MIME messages (multipart,
+// base64, quoted-printable, encoded headers, charsets); Outlook `.msg` files
+// are compound files whose properties are read as streams.
+
+use base64::alphabet;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::DecodePaddingMode;
+use base64::Engine;
+use encoding_rs::Encoding;
+use std::io::{self, Read};
+use std::path::Path;
+
+// Headers kept in the searchable text, in this order.
+const HEADERS: &[&str] = &["Subject", "From", "To", "Cc", "Date"];
+
+// Lenient base64: line breaks are stripped first, missing padding is accepted.
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+pub fn is_email(extension: &str) -> bool {
+    extension.eq_ignore_ascii_case("eml") || extension.eq_ignore_ascii_case("msg")
+}
+
+pub fn text(path: &Path) -> io::Result<String> {
+    let extension = path.extension().unwrap_or_default();
+    if extension.eq_ignore_ascii_case("msg") {
+        msg_text(path)
+    } else {
+        Ok(eml_text(&std::fs::read(path)?))
+    }
+}
+
+fn eml_text(raw: &[u8]) -> String {
+    let (headers, body) = split_message(raw);
+    let mut text = String::new();
+    for name in HEADERS {
+        if let Some(value) = header(&headers, name) {
+            text.push_str(&format!("{}: {}\n", name, decode_words(value)));
+        }
+    }
+    text.push('\n');
+    part_text(&headers, body, &mut text);
+    text
+}
+
+// Unfolded headers and the body of a message (or of a MIME part).
+fn split_message(raw: &[u8]) -> (Vec<(String, String)>, &[u8]) {
+    // A part without headers starts with the blank line.
+    if let Some(body) = raw.strip_prefix(b"\r\n").or(raw.strip_prefix(b"\n")) {
+        return (Vec::new(), body);
+    }
+    let (head, body) = match find(raw, b"\r\n\r\n") {
+        Some(at) => (&raw[..at], &raw[at + 4..]),
+        None => match find(raw, b"\n\n") {
+            Some(at) => (&raw[..at], &raw[at + 2..]),
+            None => (raw, &raw[raw.len()..]),
+        },
+    };
+
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in String::from_utf8_lossy(head).lines() {
+        if line.starts_with([' ', '\t']) {
+            // Folded continuation of the previous header.
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    (headers, body)
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(header, _)| header.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+// "text/plain; charset=\"utf-8\"" -> ("text/plain", parameter lookup).
+fn content_type(headers: &[(String, String)]) -> (String, Vec<(String, String)>) {
+    let value = header(headers, "Content-Type").unwrap_or("text/plain");
+    let mut fields = value.split(';');
+    let mime = fields.next().unwrap_or_default().trim().to_lowercase();
+    let params = fields
+        .filter_map(|field| {
+            let (name, value) = field.split_once('=')?;
+            let value = value.trim().trim_matches('"');
+            Some((name.trim().to_lowercase(), value.to_string()))
+        })
+        .collect();
+    (mime, params)
+}
+
+fn param<'a>(params: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    params
+        .iter()
+        .find(|(param, _)| param == name)
+        .map(|(_, value)| value.as_str())
+}
+
+// Appends the text of a part: text parts decoded, multiparts walked (the
+// plain text of an alternative only), attached messages parsed in turn.
+fn part_text(headers: &[(String, String)], body: &[u8], text: &mut String) {
+    let (mime, params) = content_type(headers);
+    if mime.starts_with("multipart/") {
+        let Some(boundary) = param(&params, "boundary") else {
+            return;
+        };
+        let parts: Vec<_> = multipart_parts(body, boundary)
+            .into_iter()
+            .map(split_message)
+            .collect();
+        if mime == "multipart/alternative" {
+            let preferred = parts
+                .iter()
+                .find(|(headers, _)| content_type(headers).0 == "text/plain")
+                .or(parts.first());
+            if let Some((headers, body)) = preferred {
+                part_text(headers, body, text);
+            }
+        } else {
+            for (headers, body) in &parts {
+                part_text(headers, body, text);
+            }
+        }
+    } else if mime == "message/rfc822" {
+        text.push_str(&eml_text(&decode_transfer(headers, body)));
+    } else if mime == "text/plain" || mime == "text/html" {
+        let bytes = decode_transfer(headers, body);
+        let charset = param(&params, "charset")
+            .and_then(|label| Encoding::for_label(label.as_bytes()))
+            .unwrap_or(encoding_rs::UTF_8);
+        let (decoded, _, _) = charset.decode(&bytes);
+        if mime == "text/html" {
+            text.push_str(&html_text(&decoded));
+        } else {
+            text.push_str(&decoded);
+        }
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+    }
+}
+
+fn multipart_parts<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let mut parts = Vec::new();
+    let mut start = None;
+    let mut offset = 0;
+    for line in body.split_inclusive(|&b| b == b'\n') {
+        let trimmed = line.trim_ascii_end();
+        if trimmed.starts_with(delimiter.as_bytes()) {
+            if let Some(start) = start {
+                parts.push(&body[start..offset]);
+            }
+            if trimmed.ends_with(b"--") && trimmed.len() == delimiter.len() + 2 {
+                return parts;
+            }
+            start = Some(offset + line.len());
+        }
+        offset += line.len();
+    }
+    if let Some(start) = start {
+        parts.push(&body[start..]);
+    }
+    parts
+}
+
+fn decode_transfer(headers: &[(String, String)], body: &[u8]) -> Vec<u8> {
+    let encoding = header(headers, "Content-Transfer-Encoding").unwrap_or_default();
+    if encoding.eq_ignore_ascii_case("base64") {
+        let cleaned: Vec<u8> = body
+            .iter()
+            .copied()
+            .filter(|b| !b.is_ascii_whitespace())
+            .collect();
+        BASE64.decode(cleaned).unwrap_or_default()
+    } else if encoding.eq_ignore_ascii_case("quoted-printable") {
+        decode_quoted_printable(body, false)
+    } else {
+        body.to_vec()
+    }
+}
+
+// `=XX` escapes and `=` soft line breaks; in headers, `_` stands for a space.
+fn decode_quoted_printable(bytes: &[u8], header: bool) -> Vec<u8> {
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'=' => {
+                let hex = bytes
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                if let Some(byte) = hex {
+                    decoded.push(byte);
+                    i += 3;
+                } else if bytes[i + 1..].starts_with(b"\r\n") {
+                    i += 3;
+                } else if bytes[i + 1..].starts_with(b"\n") {
+                    i += 2;
+                } else {
+                    decoded.push(b'=');
+                    i += 1;
+                }
+            }
+            b'_' if header => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    decoded
+}
+
+// RFC 2047 encoded words ("=?utf-8?B?...?="); the blanks between two of them
+// are dropped.
+fn decode_words(value: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = value;
+    let mut after_word = false;
+    while let Some(start) = rest.find("=?") {
+        let word = rest[start + 2..].splitn(3, '?').collect::<Vec<_>>();
+        let (charset, encoding, tail) = match word[..] {
+            [charset, encoding, tail] => (charset, encoding, tail),
+            _ => break,
+        };
+        let Some(end) = tail.find("?=") else {
+            break;
+        };
+        let between = &rest[..start];
+        if !(after_word && between.trim().is_empty()) {
+            decoded.push_str(between);
+        }
+        let payload = &tail.as_bytes()[..end];
+        let bytes = if encoding.eq_ignore_ascii_case("b") {
+            BASE64.decode(payload).unwrap_or_default()
+        } else {
+            decode_quoted_printable(payload, true)
+        };
+        let charset = Encoding::for_label(charset.as_bytes()).unwrap_or(encoding_rs::UTF_8);
+        decoded.push_str(&charset.decode(&bytes).0);
+        after_word = true;
+        rest = &tail[end + 2..];
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+// Visible text of an HTML body: tags dropped (block tags end a line), script
+// and style contents skipped, common entities decoded.
+fn html_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        let opening = !tag.starts_with('/') && !tag.ends_with('/');
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        rest = &rest[start + end + 1..];
+        if (name == "script" || name == "style") && opening {
+            let close = format!("</{}", name);
+            rest = match rest.to_ascii_lowercase().find(&close) {
+                Some(at) => &rest[at..],
+                None => "",
+            };
+        } else if matches!(
+            name.as_str(),
+            "br" | "p" | "div" | "tr" | "li" | "h1" | "h2" | "h3"
+        ) {
+            text.push('\n');
+        }
+    }
+    text.push_str(rest);
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+// Outlook message: subject, sender, recipients and plain text body, stored as
+// Unicode (001F) or, in older files, ANSI (001E) property streams.
+fn msg_text(path: &Path) -> io::Result<String> {
+    let mut file = cfb::open(path)?;
+    let mut property = |id: &str| -> Option<String> {
+        if let Ok(mut stream) = file.open_stream(format!("/__substg1.0_{}001F", id)) {
+            let mut bytes = Vec::new();
+            stream.read_to_end(&mut bytes).ok()?;
+            return Some(encoding_rs::UTF_16LE.decode(&bytes).0.into_owned());
+        }
+        let mut stream = file.open_stream(format!("/__substg1.0_{}001E", id)).ok()?;
+        let mut bytes = Vec::new();
+        stream.read_to_end(&mut bytes).ok()?;
+        Some(encoding_rs::WINDOWS_1252.decode(&bytes).0.into_owned())
+    };
+
+    let mut text = String::new();
+    let headers = [
+        ("Subject", "0037"),
+        ("From", "0C1A"),
+        ("To", "0E04"),
+        ("Cc", "0E03"),
+    ];
+    for (name, id) in headers {
+        if let Some(value) = property(id).filter(|value| !value.is_empty()) {
+            text.push_str(&format!("{}: {}\n", name, value.trim_end_matches('\0')));
+        }
+    }
+    text.push('\n');
+    if let Some(body) = property("1000") {
+        text.push_str(body.trim_end_matches('\0'));
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    memchr::memmem::find(haystack, needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_eml_text() {
+        let raw = concat!(
+            "From: =?utf-8?Q?Ren=C3=A9e?= <renee@example.com>\r\n",
+            "To: team@example.com\r\n",
+            "Subject: =?utf-8?B?UsOpdW5pb24=?=\r\n",
+            " =?utf-8?Q?_budg=C3=A9taire?=\r\n",
+            "Content-Type: multipart/mixed; boundary=\"outer\"\r\n",
+            "\r\n",
+            "preamble\r\n",
+            "--outer\r\n",
+            "Content-Type: multipart/alternative; boundary=inner\r\n",
+            "\r\n",
+            "--inner\r\n",
+            "Content-Type: text/html\r\n",
+            "\r\n",
+            "<p>Ignored HTML copy</p>\r\n",
+            "--inner\r\n",
+            "Content-Type: text/plain; charset=iso-8859-1\r\n",
+            "Content-Transfer-Encoding: quoted-printable\r\n",
+            "\r\n",
+            "Le budget est valid=E9 pour l'ann=\r\n",
+            "=E9e.\r\n",
+            "--inner--\r\n",
+            "--outer\r\n",
+            "Content-Type: text/html; charset=utf-8\r\n",
+            "Content-Transfer-Encoding: base64\r\n",
+            "\r\n",
+            "PHN0eWxlPnAge308L3N0eWxlPjxwPkFubmV4ZSAmYW1wOyBjaGlmZnJlczwvcD4=\r\n",
+            "--outer--\r\n",
+        );
+
+        let text = eml_text(raw.as_bytes());
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "Subject: Réunion budgétaire");
+        assert_eq!(lines[1], "From: Renée <renee@example.com>");
+        assert_eq!(lines[2], "To: team@example.com");
+        assert!(text.contains("Le budget est validé pour l'année."));
+        assert!(text.contains("Annexe & chiffres"));
+        assert!(!text.contains("Ignored") && !text.contains("p {}"));
+    }
+
+    #[test]
+    fn test_msg_text() {
+        let path = std::env::temp_dir().join("quick-findr-email-test.msg");
+        let mut file = cfb::create(&path).unwrap();
+        let utf16 =
+            |text: &str| -> Vec<u8> { text.encode_utf16().flat_map(u16::to_le_bytes).collect() };
+        file.create_stream("/__substg1.0_0037001F")
+            .unwrap()
+            .write_all(&utf16("Quarterly report"))
+            .unwrap();
+        file.create_stream("/__substg1.0_1000001E")
+            .unwrap()
+            .write_all(b"Numbers attached.\r\n")
+            .unwrap();
+        file.flush().unwrap();
+        drop(file);
+
+        let text = text(&path).unwrap();
+        assert!(text.starts_with("Subject: Quarterly report\n\n"));
+        assert!(text.contains("Numbers attached."));
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,181 @@
+use crate::engine::{publish_results, update_ui, SearchResult, SearchToken};
+use crate::i18n::tr;
+use ignore::WalkBuilder;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+// Lists the topmost folders under `root` whose whole subtree contains no files.
+pub fn find_empty_dirs(root: &Path, token: &SearchToken) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    let mut non_empty: HashSet<PathBuf> = HashSet::new();
+
+    // No filters at all: a folder holding only a hidden or ignored file is not empty.
+    let mut builder = WalkBuilder::new(root);
+    builder.standard_filters(false).follow_links(false);
+
+    for entry in builder.build() {
+        if token.is_cancelled() {
+            return Vec::new();
+        }
+
+        let (path, is_dir) = match entry {
+            Ok(dir_entry) => {
+                if dir_entry.depth() == 0 {
+                    continue;
+                }
+                let is_dir = dir_entry.file_type().is_some_and(|t| t.is_dir());
+                (dir_entry.into_path(), is_dir)
+            }
+            Err(err) => {
+                // Unreadable folder: we cannot prove it is empty, so keep it out of the list.
+                match error_path(&err) {
+                    Some(path) => (path.to_path_buf(), false),
+                    None => continue,
+                }
+            }
+        };
+
+        if is_dir {
+            dirs.push(path);
+        } else {
+            // Files (and symlinks) make every ancestor up to the root non-empty.
+            for ancestor in path.ancestors().skip(1) {
+                if ancestor == root || !non_empty.insert(ancestor.to_path_buf()) {
+                    break;
+                }
+            }
+        }
+    }
+
+    let empty: HashSet<&PathBuf> = dirs.iter().filter(|d| !non_empty.contains(*d)).collect();
+
+    // Keep only the topmost ones: deleting a folder already removes its empty children.
+    let mut topmost: Vec<PathBuf> = empty
+        .iter()
+        .filter(|d| d.parent().is_none_or(|p| !empty.contains(&p.to_path_buf())))
+        .map(|d| (*d).clone())
+        .collect();
+    topmost.sort();
+    topmost
+}
+
+fn error_path(err: &ignore::Error) -> Option<&Path> {
+    match err {
+        ignore::Error::WithPath { path, .. } => Some(path),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+            error_path(err)
+        }
+        _ => None,
+    }
+}
+
+// Deletes an empty folder tree bottom-up with `remove_dir`, so a file created
+// since the scan makes the deletion fail instead of being lost.
+pub fn delete_empty_dir(path: &Path) -> io::Result<()> {
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            delete_empty_dir(&entry.path())?;
+        }
+    }
+    fs::remove_dir(path)
+}
+
+// Spawns the empty folder scan in a separate thread.
+pub fn spawn_empty_folder_scan(
+    root_path: PathBuf,
+    sender: slint::Weak<crate::AppWindow>,
+    token: SearchToken,
+) {
+    std::thread::spawn(move || {
+        let start_time = Instant::now();
+
+        let results: Vec<SearchResult> = find_empty_dirs(&root_path, &token)
+            .into_iter()
+            .map(|path| SearchResult {
+                extension: String::new(),
+                is_dir: true,
+                ..SearchResult::for_path(&path, &root_path)
+            })
+            .collect();
+
+        let total_results_count = publish_results(&token, &sender, results);
+
+        let duration = start_time.elapsed().as_millis() as u64;
+        update_ui(&token, &sender, move |window| {
+            window.set_status_text(
+                tr!(
+                    "Completed: {} empty folders in {}ms",
+                    total_results_count,
+                    duration
+                )
+                .into(),
+            );
+            window.set_active_threads(0);
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_root;
+
+    #[test]
+    fn test_find_empty_dirs_topmost_only() {
+        let root = temp_root("empty-topmost");
+        fs::create_dir_all(root.join("a/b/c")).unwrap();
+        fs::create_dir_all(root.join("full/empty")).unwrap();
+        fs::write(root.join("full/file.txt"), "x").unwrap();
+
+        let found = find_empty_dirs(&root, &SearchToken::default());
+
+        assert_eq!(found, vec![root.join("a"), root.join("full/empty")]);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_hidden_file_is_not_empty() {
+        let root = temp_root("empty-hidden");
+        fs::create_dir_all(root.join("dotted")).unwrap();
+        fs::write(root.join("dotted/.keep"), "").unwrap();
+
+        assert!(find_empty_dirs(&root, &SearchToken::default()).is_empty());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_cancelled_scan_returns_nothing() {
+        let root = temp_root("empty-cancelled");
+        fs::create_dir_all(root.join("empty")).unwrap();
+
+        let token = SearchToken::default();
+        token.cancel();
+        assert!(find_empty_dirs(&root, &token).is_empty());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_delete_empty_dir() {
+        let root = temp_root("empty-delete");
+        fs::create_dir_all(root.join("a/b/c")).unwrap();
+
+        delete_empty_dir(&root.join("a")).unwrap();
+        assert!(!root.join("a").exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_delete_refuses_non_empty_dir() {
+        let root = temp_root("empty-refuse");
+        fs::create_dir_all(root.join("a/b")).unwrap();
+        fs::write(root.join("a/b/late.txt"), "x").unwrap();
+
+        assert!(delete_empty_dir(&root.join("a")).is_err());
+        assert!(root.join("a/b/late.txt").exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -1,33 +1,526 @@
-use ignore::WalkBuilder;
+use crate::accents;
+use crate::archive;
+use crate::email;
+use crate::file_id::{self, FileId};
+use crate::filetype;
+use crate::fuzzy;
+use crate::git;
+use crate::i18n::tr;
+use crate::index;
+use crate::indexer::{self, ContentCandidates};
+use crate::mft;
+use crate::network;
+use crate::ocr;
+use crate::query_parser::{self, Expr, Qualifiers};
+use crate::signature;
+use aho_corasick::AhoCorasick;
+use chrono::{DateTime, Datelike, Days, Local};
+use encoding_rs_io::DecodeReaderBytesBuilder;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::overrides::{Override, OverrideBuilder};
+use ignore::{DirEntry, WalkBuilder};
+use memmap2::Mmap;
 use rayon::prelude::*;
 use regex::RegexBuilder;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime};
 
 // Result sent to UI thread (main.rs) via invoke_from_event_loop.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct SearchResult {
     pub file_name: String,
     pub file_path: String,
     pub relative_path: String,
     pub extension: String,
     pub line_match: String, // Empty if match on filename
+    // Every matching line of a content match (empty for a name match).
+    pub matches: Vec<LineMatch>,
+    // Byte ranges of the query in `file_name` (empty for a content match).
+    pub name_ranges: Vec<Range<usize>>,
+    pub is_dir: bool,
+    pub modified: Option<SystemTime>,
+    // Size in bytes (None for folders, and when unknown).
+    pub size: Option<u64>,
+    // Fuzzy relevance of the file name (0 outside fuzzy mode).
+    pub score: i64,
+    pub match_kind: MatchKind,
+    // Uncommitted state of a file of a git repository (None when unchanged).
+    pub git_status: Option<git::FileStatus>,
+    // Other paths of the same file (hardlinks, junctions), listed once.
+    pub also_at: Vec<String>,
+}
+
+impl SearchResult {
+    // Name match on the file `path` found under `root`; the fields that depend
+    // on the search (excerpt, ranges, size...) are left empty.
+    pub fn for_path(path: &Path, root: &Path) -> Self {
+        Self {
+            file_name: path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            file_path: path.to_string_lossy().to_string(),
+            relative_path: path
+                .strip_prefix(root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .to_string(),
+            extension: path
+                .extension()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            ..Self::default()
+        }
+    }
+}
+
+// What matched the query; name matches are listed before content matches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    // The file (or folder) name, or the relative path in full-path mode.
+    #[default]
+    Name,
+    Content,
+}
+
+// One matching line of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineMatch {
+    pub line_number: usize,
+    // 1-based character column of the first match in the untrimmed line.
+    pub column: usize,
+    // Trimmed line.
+    pub text: String,
+    // Byte ranges of the matched substrings in `text`.
+    pub ranges: Vec<Range<usize>>,
+}
+
+// Cancellation handle of one scan. Every scan gets its own token: starting a new
+// scan cancels the previous one, whose workers then stop and whose pending UI
+// updates are dropped. A paused scan keeps its workers parked at their next
+// entry until it is resumed (or cancelled).
+#[derive(Debug, Clone, Default)]
+pub struct SearchToken {
+    cancelled: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
+    // Wakes the parked workers on resume and cancel.
+    wake: Arc<(Mutex<()>, Condvar)>,
+}
+
+impl SearchToken {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.wake_workers();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+        self.wake_workers();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    // Blocks a worker while the scan is paused.
+    pub fn wait_while_paused(&self) {
+        if !self.is_paused() {
+            return;
+        }
+        let (lock, condvar) = &*self.wake;
+        let mut guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+        while self.is_paused() && !self.is_cancelled() {
+            guard = condvar.wait(guard).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    // Taking the lock first: a worker between its check and its wait is not missed.
+    fn wake_workers(&self) {
+        let (lock, condvar) = &*self.wake;
+        let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+        condvar.notify_all();
+    }
+}
+
+// Order of the results before they are paged to the UI. In every order, name
+// matches come before content matches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResultOrder {
+    // Walk order (whatever the parallel scan produced first).
+    #[default]
+    Unsorted,
+    // Most recently modified first; files without a date go last.
+    RecentFirst,
+    // Best fuzzy score first.
+    Relevance,
+    // Fewest folders between the searched folder and the file first, then by
+    // name.
+    ShallowFirst,
+}
+
+// Quick "modified in the current period" filters (local calendar).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimeFilter {
+    #[default]
+    Any,
+    Today,
+    ThisWeek,
+    ThisMonth,
+}
+
+impl TimeFilter {
+    // Name used by the UI chips ("any", "today", "week", "month").
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "today" => Self::Today,
+            "week" => Self::ThisWeek,
+            "month" => Self::ThisMonth,
+            _ => Self::Any,
+        }
+    }
+
+    // Start of the period (local midnight; weeks start on Monday).
+    pub fn since(self, now: DateTime<Local>) -> Option<SystemTime> {
+        let today = now.date_naive();
+        let start = match self {
+            Self::Any => return None,
+            Self::Today => today,
+            Self::ThisWeek => today - Days::new(u64::from(today.weekday().num_days_from_monday())),
+            Self::ThisMonth => today.with_day(1)?,
+        };
+        let midnight = start
+            .and_hms_opt(0, 0, 0)?
+            .and_local_timezone(Local)
+            .earliest()?;
+        Some(midnight.into())
+    }
+}
+
+// Modified-date range of the options panel; `until` is exclusive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateRange {
+    pub from: Option<SystemTime>,
+    pub until: Option<SystemTime>,
+}
+
+impl DateRange {
+    // The last `days` days, up to now.
+    pub fn last_days(days: u64, now: DateTime<Local>) -> Self {
+        Self {
+            from: Some((now - chrono::Duration::days(days as i64)).into()),
+            until: None,
+        }
+    }
+
+    // Local dates "YYYY-MM-DD", both inclusive; either may be empty.
+    pub fn parse(from: &str, to: &str) -> Result<Self, String> {
+        let midnight = |text: &str, days_after: u64| -> Result<Option<SystemTime>, String> {
+            let text = text.trim();
+            if text.is_empty() {
+                return Ok(None);
+            }
+            let invalid = || format!("invalid date \"{}\" (expected YYYY-MM-DD)", text);
+            let date =
+                chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d").map_err(|_| invalid())?;
+            let time = (date + Days::new(days_after))
+                .and_hms_opt(0, 0, 0)
+                .and_then(|t| t.and_local_timezone(Local).earliest())
+                .ok_or_else(invalid)?;
+            Ok(Some(time.into()))
+        };
+
+        let range = Self {
+            from: midnight(from, 0)?,
+            until: midnight(to, 1)?,
+        };
+        match (range.from, range.until) {
+            (Some(from), Some(until)) if from >= until => {
+                Err("the start date is after the end date".to_string())
+            }
+            _ => Ok(range),
+        }
+    }
+}
+
+// Search options beyond the query itself.
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    pub order: ResultOrder,
+    // fzf-style subsequence matching on file names (ignored in regex mode).
+    pub fuzzy: bool,
+    // Matches must be whole words ("test" does not match "latest").
+    pub whole_word: bool,
+    // Diacritic-insensitive matching ("cafe" matches "café").
+    pub ignore_accents: bool,
+    // Only files modified at or after this time.
+    pub modified_since: Option<SystemTime>,
+    // Only files modified before this time.
+    pub modified_until: Option<SystemTime>,
+    // Comma-separated globs on the relative path ("src/**/*.rs, *.toml"); empty: any file.
+    pub include_globs: String,
+    // Comma-separated paths to skip, gitignore style ("**/build/**, dist/").
+    pub exclude_globs: String,
+    pub size_filter: SizeFilter,
+    // The walk stops once this many results are found.
+    pub max_results: Option<usize>,
+    // Content search skips files larger than this (bytes).
+    pub max_content_size: Option<u64>,
+    // Content search reads at most this many lines per file.
+    pub max_content_lines: Option<usize>,
+    // The ignored folders of the settings are walked too, for this search.
+    pub walk_ignored_dirs: bool,
+    // Files deeper than this many levels below the folder are skipped (1: its
+    // own files only).
+    pub max_depth: Option<usize>,
+    // The walk follows symlinks and junctions (see `FollowedLinks`).
+    pub follow_links: bool,
+    // Worker threads of the walk and of the matching (None: one per CPU).
+    pub threads: Option<usize>,
+    // Background scan: half the threads, at low CPU and I/O priority, pausing
+    // between batches of entries.
+    pub low_priority: bool,
+    // Only the files known to git are searched (see git.rs), instead of the
+    // files a walk lists.
+    pub tracked_only: bool,
+    // Only the files git lists as changed since this point are searched: "N"
+    // for the last N commits, or a branch (see `git::changed_files`).
+    pub changed_since: Option<String>,
+    // The files of this git revision (branch, tag or commit) are searched, as
+    // git reads them, instead of the working tree (see `search_revision`).
+    pub revision: Option<String>,
+    // Hidden files and folders (dot names, hidden attribute) are searched too.
+    pub include_hidden: bool,
+    // Queries match the relative path ("models/user") instead of the file name.
+    pub match_path: bool,
+    // Also searches the entries of zip-based archives (see archive.rs).
+    pub search_archives: bool,
+    // Content search reads images and scanned PDFs through OCR (see ocr.rs).
+    pub ocr: bool,
+    // Content search also reads cloud files that are not on disk (OneDrive
+    // placeholders), downloading them; otherwise they only match by name.
+    pub read_cloud_files: bool,
+    // Re-run of a live search: the results replace the list once the scan is
+    // complete instead of streaming into a cleared one.
+    pub refresh: bool,
+}
+
+// File size bounds in bytes, both inclusive ("<1KB", ">10MB", ">=1GB <=2GB").
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SizeFilter {
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+}
+
+impl SizeFilter {
+    // Space- or comma-separated bounds; units B, KB, MB, GB, TB (1024-based).
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut filter = Self::default();
+        for token in text
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|t| !t.is_empty())
+        {
+            let invalid = || format!("invalid size \"{}\"", token);
+            let (operator, amount) = ["<=", ">=", "<", ">"]
+                .iter()
+                .find_map(|op| token.strip_prefix(op).map(|rest| (*op, rest)))
+                .ok_or_else(invalid)?;
+            let bytes = parse_size(amount).ok_or_else(invalid)?;
+            match operator {
+                ">=" => filter.min = Some(bytes),
+                ">" => filter.min = Some(bytes.saturating_add(1)),
+                "<=" => filter.max = Some(bytes),
+                _ => filter.max = Some(bytes.saturating_sub(1)),
+            }
+        }
+        Ok(filter)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.is_none() && self.max.is_none()
+    }
+
+    pub fn contains(&self, size: u64) -> bool {
+        self.min.is_none_or(|min| size >= min) && self.max.is_none_or(|max| size <= max)
+    }
+}
+
+// Limit typed in megabytes ("10", "0.5"); None when empty.
+pub fn parse_megabytes(text: &str) -> Result<Option<u64>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    match text.replace(',', ".").parse::<f64>() {
+        Ok(mb) if mb > 0.0 => Ok(Some((mb * (1u64 << 20) as f64) as u64)),
+        _ => Err(format!("\"{}\" is not a positive number of MB", text)),
+    }
+}
+
+// Depth limit of the walk ("2": the folder and its subfolders); None when empty.
+pub fn parse_depth(text: &str) -> Result<Option<usize>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    match text.parse::<usize>() {
+        Ok(depth) if depth > 0 => Ok(Some(depth)),
+        _ => Err(format!("\"{}\" is not a positive whole number", text)),
+    }
+}
+
+// "10MB", "1.5 GB", "512" (bytes).
+fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let multiplier: u64 = match unit.trim().to_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" => 1 << 10,
+        "M" | "MB" => 1 << 20,
+        "G" | "GB" => 1 << 30,
+        "T" | "TB" => 1 << 40,
+        _ => return None,
+    };
+    Some((number * multiplier as f64) as u64)
 }
 
 pub struct SearchContext {
     pub query: String,
     pub query_lower: String,
     pub regex: Option<regex::Regex>,
+    // Boolean form of the query when it uses AND / OR / NOT / -term (not in regex mode).
+    pub expression: Option<Expr>,
+    // Case-insensitive matcher of an ASCII query: no per-line lowercasing.
+    literal: Option<AhoCorasick>,
+    // Locates the matched substrings of a line (the query itself outside regex mode).
+    highlighter: Option<regex::Regex>,
     pub case_sensitive: bool,
     pub use_regex: bool,
     pub search_content: bool,
     pub root_path: PathBuf,
     pub exclude_extensions: Vec<String>,
     pub respect_gitignore: bool,
+    // Compiled `SearchOptions::include_globs` (see `with_include_globs`).
+    pub include_globs: Option<GlobSet>,
+    // Extensions of the selected language (empty: any file).
+    pub language_extensions: &'static [&'static str],
+    // `ext:`, `size:`, `path:`, `modified:`, `type:` and `signed:` tokens of the query.
+    pub qualifiers: Qualifiers,
+    pub options: SearchOptions,
+    // Files left out of (or cut short in) the content search.
+    pub content_stats: ContentStats,
+    // Counters of the running scan, shown in the status bar.
+    pub progress: ScanProgress,
+    // Paths the scan could not read.
+    pub access_errors: AccessErrors,
+    // Full-text index of the folder, when it has one (see `indexer`).
+    pub content_candidates: Option<ContentCandidates>,
+}
+
+// Counters filled by the workers during a content search.
+#[derive(Debug, Default)]
+pub struct ContentStats {
+    // Files over `SearchOptions::max_content_size`, not read.
+    pub skipped_large: AtomicUsize,
+    // Files with lines past `SearchOptions::max_content_lines`, left unread.
+    pub truncated: AtomicUsize,
+    // Indexed files without the query, not read.
+    pub ruled_out: AtomicUsize,
+    // Cloud files that are not on disk, not read (see `is_cloud_placeholder`).
+    pub cloud_only: AtomicUsize,
+}
+
+// Counters filled by the workers while a scan runs.
+#[derive(Debug, Default)]
+pub struct ScanProgress {
+    // Files and folders walked (or listed).
+    pub walked: AtomicUsize,
+    // Files whose content was read.
+    pub searched: AtomicUsize,
+}
+
+impl ScanProgress {
+    // Status bar text of a running scan ("Scanning...", "Paused...") with
+    // `matches` results so far, after `elapsed`; the rate counts walked entries.
+    fn status(
+        &self,
+        label: &str,
+        matches: usize,
+        search_content: bool,
+        elapsed: Duration,
+    ) -> String {
+        let walked = self.walked.load(Ordering::Relaxed);
+        let mut status = tr!("{} {} entries", label, walked);
+        if search_content {
+            status.push_str(&tr!(
+                ", {} files searched",
+                self.searched.load(Ordering::Relaxed)
+            ));
+        }
+        status.push_str(&tr!(", {} matches", matches));
+        let seconds = elapsed.as_secs_f64();
+        if seconds > 0.0 {
+            status.push_str(&tr!(
+                " ({}s, {} entries/s)",
+                format!("{:.1}", seconds),
+                (walked as f64 / seconds) as u64
+            ));
+        }
+        status
+    }
+}
+
+// Walk and read errors of a scan (permission denied, vanished or unreadable
+// files), listed in the "skipped" panel once the scan is over.
+#[derive(Debug, Default)]
+pub struct AccessErrors {
+    count: AtomicUsize,
+    // The first `MAX_LISTED_ERRORS` messages.
+    listed: Mutex<Vec<String>>,
+}
+
+// Access errors listed in the panel (all of them are counted).
+const MAX_LISTED_ERRORS: usize = 1000;
+
+impl AccessErrors {
+    pub fn record(&self, message: String) {
+        if self.count.fetch_add(1, Ordering::Relaxed) < MAX_LISTED_ERRORS {
+            self.listed
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(message);
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    pub fn take_listed(&self) -> Vec<String> {
+        std::mem::take(&mut *self.listed.lock().unwrap_or_else(|e| e.into_inner()))
+    }
 }
 
 impl SearchContext {
@@ -40,8 +533,10 @@ impl SearchContext {
         root_path: PathBuf,
         exclude_extensions: String,
         respect_gitignore: bool,
-        _language_filter: Option<String>,
+        language_filter: Option<String>,
     ) -> Option<Self> {
+        let (query, qualifiers) = query_parser::parse(&query, Local::now());
+
         // Wildcards (*, ?) enable regex mode for convenience.
         let has_wildcards = query.contains('*') || query.contains('?');
         let should_use_regex = use_regex || has_wildcards;
@@ -69,6 +564,36 @@ impl SearchContext {
             None
         };
 
+        let expression = if should_use_regex {
+            None
+        } else {
+            query_parser::parse_expression(&query)
+        };
+
+        let highlighter = match (&regex, &expression) {
+            (Some(re), _) => Some(re.clone()),
+            (None, Some(expression)) => {
+                let terms: Vec<String> = expression
+                    .positive_terms()
+                    .into_iter()
+                    .map(regex::escape)
+                    .collect();
+                if terms.is_empty() {
+                    None
+                } else {
+                    RegexBuilder::new(&terms.join("|"))
+                        .case_insensitive(!case_sensitive)
+                        .build()
+                        .ok()
+                }
+            }
+            (None, None) if query.is_empty() => None,
+            (None, None) => RegexBuilder::new(&regex::escape(&query))
+                .case_insensitive(!case_sensitive)
+                .build()
+                .ok(),
+        };
+
         let exclude_list: Vec<String> = exclude_extensions
             .split(',')
             .map(|s| {
@@ -88,18 +613,241 @@ impl SearchContext {
         Some(Self {
             query: query.clone(),
             query_lower: query.to_lowercase(),
+            literal: literal_matcher(&query, case_sensitive || should_use_regex),
             regex,
+            expression,
+            highlighter,
             case_sensitive,
             use_regex: should_use_regex,
             search_content,
             root_path,
             exclude_extensions: exclude_list,
             respect_gitignore,
+            include_globs: None,
+            language_extensions: language_filter
+                .as_deref()
+                .and_then(language_extensions)
+                .unwrap_or_default(),
+            qualifiers,
+            options: SearchOptions::default(),
+            content_stats: ContentStats::default(),
+            progress: ScanProgress::default(),
+            access_errors: AccessErrors::default(),
+            content_candidates: None,
         })
     }
 
+    pub fn with_options(mut self, options: SearchOptions) -> Self {
+        if options.ignore_accents {
+            // Texts are folded before matching, so the query is folded too.
+            self.query = accents::fold(&self.query).into_owned();
+            self.query_lower = self.query.to_lowercase();
+            if self.literal.is_some() {
+                self.literal = literal_matcher(&self.query, false);
+            }
+            if self.expression.is_some() {
+                self.expression = query_parser::parse_expression(&self.query);
+            }
+            self.regex = self.regex.take().map(|re| self.folded_regex(re));
+            self.highlighter = self.highlighter.take().map(|re| self.folded_regex(re));
+        }
+        if options.whole_word {
+            self.regex = self.regex.take().map(|re| self.word_bounded(re));
+            self.highlighter = self.highlighter.take().map(|re| self.word_bounded(re));
+        }
+        self.options = options;
+        self
+    }
+
+    fn folded_regex(&self, re: regex::Regex) -> regex::Regex {
+        RegexBuilder::new(&accents::fold(re.as_str()))
+            .case_insensitive(!self.case_sensitive)
+            .build()
+            .unwrap_or(re)
+    }
+
+    // Wraps a pattern in `\b` boundaries; anchored patterns (wildcards) already
+    // span the whole name and are kept as they are.
+    fn word_bounded(&self, re: regex::Regex) -> regex::Regex {
+        let pattern = re.as_str();
+        if pattern.starts_with('^') && pattern.ends_with('$') {
+            return re;
+        }
+        RegexBuilder::new(&format!(r"\b(?:{})\b", pattern))
+            .case_insensitive(!self.case_sensitive)
+            .build()
+            .unwrap_or(re)
+    }
+
+    // Only files whose relative path matches one of the comma-separated globs.
+    pub fn with_include_globs(mut self, patterns: &str) -> Result<Self, globset::Error> {
+        self.include_globs = build_globset(patterns)?;
+        Ok(self)
+    }
+
+    // Checks the size filters; the size is only fetched when a filter is set.
+    pub fn size_allowed(&self, size: impl FnOnce() -> Option<u64>) -> bool {
+        let (filter, typed) = (self.options.size_filter, self.qualifiers.size);
+        if filter.is_empty() && typed.is_empty() {
+            return true;
+        }
+        size().is_some_and(|size| filter.contains(size) && typed.contains(size))
+    }
+
+    // Whether the content of a file may be read: a cloud placeholder is only
+    // read (and downloaded) on request.
+    fn may_read(&self, path: &Path) -> bool {
+        if self.options.read_cloud_files || !is_cloud_placeholder(path) {
+            return true;
+        }
+        self.content_stats
+            .cloud_only
+            .fetch_add(1, Ordering::Relaxed);
+        false
+    }
+
+    // Counts a walked (or listed) entry; a low-priority scan sleeps after each
+    // batch.
+    fn walked_entry(&self) {
+        let walked = self.progress.walked.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.options.low_priority && walked.is_multiple_of(LOW_PRIORITY_BATCH) {
+            std::thread::sleep(LOW_PRIORITY_PAUSE);
+        }
+    }
+
+    // Modification-time bounds of the options and of the `modified:` tokens combined.
+    pub fn modified_bounds(&self) -> (Option<SystemTime>, Option<SystemTime>) {
+        let typed = self.qualifiers.modified;
+        let since = match (self.options.modified_since, typed.from) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        let until = match (self.options.modified_until, typed.until) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        (since, until)
+    }
+
+    // Extension and path filters, shared by files and archive entries: excluded
+    // extensions, `ext:` and `path:` tokens, include globs and language filter.
+    fn name_allowed(&self, ext_lower: &str, relative: &Path) -> bool {
+        let excluded = self
+            .exclude_extensions
+            .iter()
+            .any(|excluded| ext_lower == excluded.strip_prefix('.').unwrap_or(excluded));
+        if excluded {
+            return false;
+        }
+        if !self.qualifiers.extensions.is_empty()
+            && !self
+                .qualifiers
+                .extensions
+                .iter()
+                .any(|ext| ext == ext_lower)
+        {
+            return false;
+        }
+        if !self.qualifiers.path_allowed(&relative.to_string_lossy()) {
+            return false;
+        }
+        if let Some(include) = &self.include_globs {
+            if !include.is_match(relative) {
+                return false;
+            }
+        }
+        self.language_extensions.is_empty() || self.language_extensions.contains(&ext_lower)
+    }
+
+    // Folder names are matched by plain queries only: file filters (type,
+    // extension, size, language) mean the user is after files.
+    fn matches_folders(&self) -> bool {
+        !self.query.is_empty()
+            && self.qualifiers.kinds.is_empty()
+            && self.qualifiers.signed.is_none()
+            && self.qualifiers.extensions.is_empty()
+            && self.qualifiers.size.is_empty()
+            && self.options.size_filter.is_empty()
+            && self.language_extensions.is_empty()
+    }
+
+    // Text matched against the query: the relative path in full-path mode;
+    // otherwise the file name, without its extension for wildcard queries
+    // (Eclipse style).
+    fn match_target(&self, file_name: &str, relative: &Path) -> String {
+        if self.options.match_path {
+            return slash_path(relative);
+        }
+        if self.query.contains('*') || self.query.contains('?') {
+            if let Some(stem) = Path::new(file_name).file_stem() {
+                return stem.to_string_lossy().to_string();
+            }
+        }
+        file_name.to_string()
+    }
+
+    // Matches a file name; in fuzzy mode returns its relevance score.
+    pub fn name_score(&self, name: &str) -> Option<i64> {
+        if self.query.is_empty() {
+            // Qualifiers only ("ext:pdf"): every remaining file matches.
+            return Some(0);
+        }
+        if self.fuzzy_names() {
+            fuzzy::score(&self.query, &self.folded(name), self.case_sensitive)
+        } else {
+            (self.is_match(name) || self.initials_name_match(name)).then_some(0)
+        }
+    }
+
+    // Names are matched as fzf-style subsequences.
+    fn fuzzy_names(&self) -> bool {
+        self.options.fuzzy
+            && !self.options.whole_word
+            && !self.use_regex
+            && self.expression.is_none()
+    }
+
+    // Byte ranges of the query in a matched name: the matched characters in
+    // fuzzy mode (unless accents are folded away), else as in `match_ranges`.
+    pub fn name_ranges(&self, name: &str) -> Vec<Range<usize>> {
+        if self.fuzzy_names() && (!self.options.ignore_accents || name.is_ascii()) {
+            return fuzzy::positions(&self.query, name, self.case_sensitive).unwrap_or_default();
+        }
+        self.match_ranges(name)
+    }
+
+    // Byte ranges of the matched substrings (empty for CamelCase matches).
+    pub fn match_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let Some(re) = &self.highlighter else {
+            return Vec::new();
+        };
+        if self.options.ignore_accents && !text.is_ascii() {
+            // Found in the folded text, mapped back onto the original characters.
+            let (folded, origins) = accents::fold_with_offsets(text);
+            return re
+                .find_iter(&folded)
+                .filter(|m| !m.is_empty())
+                .map(|m| origins[m.start()].start..origins[m.end() - 1].end)
+                .collect();
+        }
+        re.find_iter(text)
+            .filter(|m| !m.is_empty())
+            .map(|m| m.range())
+            .collect()
+    }
+
+    // Text as it is matched: accent-folded when accents are ignored.
+    fn folded<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if self.options.ignore_accents {
+            accents::fold(text)
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+
     // Checks if the given string matches the query.
     pub fn is_match(&self, text: &str) -> bool {
+        let text = &*self.folded(text);
         if self.use_regex {
             if let Some(re) = &self.regex {
                 return re.is_match(text);
@@ -107,372 +855,2235 @@ impl SearchContext {
             return false;
         }
 
+        if let Some(expression) = &self.expression {
+            let text_lower = text.to_lowercase();
+            return expression.eval(&|term| {
+                self.camelcase_term_match(term, text)
+                    || if self.case_sensitive {
+                        self.contains(text, term)
+                    } else {
+                        self.contains(&text_lower, &term.to_lowercase())
+                    }
+            });
+        }
+
         // CamelCase matching: if query is uppercase/digits (e.g., "UC"), match uppercase letters in name.
-        if self.is_camelcase_query() {
-            if self.camelcase_match(text) {
-                return true;
-            }
+        if self.camelcase_term_match(&self.query, text) {
+            return true;
         }
 
         // Substring matching.
+        if let Some(literal) = &self.literal {
+            return literal
+                .find_iter(text)
+                .any(|m| !self.options.whole_word || at_word_boundaries(text, m.range()));
+        }
         if self.case_sensitive {
-            text.contains(&self.query)
+            self.contains(text, &self.query)
         } else {
-            text.to_lowercase().contains(&self.query_lower)
+            self.contains(&text.to_lowercase(), &self.query_lower)
+        }
+    }
+
+    // Byte regex that every matching line contains, used to skip ahead in large
+    // buffers. None when a line can match without it (CamelCase, boolean
+    // queries, accent folding, non-ASCII regex) or without any pattern.
+    fn content_prefilter(&self) -> Option<regex::bytes::Regex> {
+        if self.options.ignore_accents || self.expression.is_some() {
+            return None;
+        }
+        if self.use_regex {
+            // A non-ASCII pattern matches the decoded text, not the Windows-1252 bytes.
+            let re = self.regex.as_ref().filter(|re| re.as_str().is_ascii())?;
+            // `^` and `$` anchor lines, as in the line reader.
+            return regex::bytes::RegexBuilder::new(re.as_str())
+                .case_insensitive(!self.case_sensitive)
+                .multi_line(true)
+                .crlf(true)
+                .build()
+                .ok();
         }
+        // ASCII literal: byte-wise (also finds it in Windows-1252 lines).
+        let escaped = regex::escape(self.content_literal()?);
+        let pattern = if self.options.whole_word {
+            format!(r"\b{}\b", escaped)
+        } else {
+            escaped
+        };
+        regex::bytes::RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .unicode(false)
+            .build()
+            .ok()
     }
 
-    // Checks if the query is a CamelCase pattern (e.g., "UC", "UCS", "U2C")
-    fn is_camelcase_query(&self) -> bool {
-        // CamelCase pattern: at least 2 characters, all uppercase or digits
-        self.query.len() >= 2
-            && self
-                .query
-                .chars()
-                .all(|c| c.is_uppercase() || c.is_numeric())
+    // The query as an ASCII literal that every matching line contains.
+    pub fn content_literal(&self) -> Option<&str> {
+        let plain = !self.use_regex && !self.options.ignore_accents && self.expression.is_none();
+        (plain && !self.query.is_empty() && self.query.is_ascii() && !self.camelcase_possible())
+            .then_some(self.query.as_str())
     }
 
-    // CamelCase matching: "UC" matches "UserController", "U2C" matches "User2Controller"
-    fn camelcase_match(&self, text: &str) -> bool {
-        let query_chars: Vec<char> = self.query.chars().collect();
-        let mut query_idx = 0;
+    fn camelcase_possible(&self) -> bool {
+        !self.options.whole_word && is_camelcase_query(&self.query)
+    }
 
-        for ch in text.chars() {
-            if query_idx >= query_chars.len() {
-                return true;
-            }
+    // CamelCase abbreviations ("UC") are not words: off in whole-word mode.
+    fn camelcase_term_match(&self, term: &str, text: &str) -> bool {
+        !self.options.whole_word && is_camelcase_query(term) && camelcase_match(term, text)
+    }
 
-            // Match uppercase letters and digits from query with those in text
-            if (ch.is_uppercase() || ch.is_numeric()) && ch == query_chars[query_idx] {
-                query_idx += 1;
-            }
+    // Lowercase word initials ("uc" for "user_controller.rs"), on file names only.
+    fn initials_name_match(&self, name: &str) -> bool {
+        !self.options.whole_word
+            && !self.use_regex
+            && self.expression.is_none()
+            && is_initials_query(&self.query)
+            && initials_match(&self.query, &self.folded(name))
+    }
+
+    // Substring test, at word boundaries in whole-word mode.
+    fn contains(&self, text: &str, term: &str) -> bool {
+        if self.options.whole_word {
+            contains_word(text, term)
+        } else {
+            text.contains(term)
         }
+    }
+}
+
+// Checks if the query is a CamelCase pattern (e.g., "UC", "UCS", "U2C")
+fn is_camelcase_query(query: &str) -> bool {
+    // CamelCase pattern: at least 2 characters, all uppercase or digits
+    query.len() >= 2 && query.chars().all(|c| c.is_uppercase() || c.is_numeric())
+}
 
-        query_idx >= query_chars.len()
+// `term` occurs with no letter, digit or `_` right before or after it.
+fn contains_word(text: &str, term: &str) -> bool {
+    if term.is_empty() {
+        return true;
     }
+    text.match_indices(term)
+        .any(|(start, _)| at_word_boundaries(text, start..start + term.len()))
 }
 
-// Default exclusions (keeps scan fast and avoids build/vendor directories).
-const IGNORED_DIRS: &[&str] = &[
-    "target",
-    ".git",
-    "node_modules",
-    "vendor",
-    ".idea",
-    ".vscode",
-];
+fn at_word_boundaries(text: &str, range: Range<usize>) -> bool {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    !text[..range.start]
+        .chars()
+        .next_back()
+        .is_some_and(is_word_char)
+        && !text[range.end..].chars().next().is_some_and(is_word_char)
+}
 
-// Spawns a search in a separate thread.
-pub fn spawn_search(
-    query: String,
-    root_path: PathBuf,
-    sender: slint::Weak<crate::AppWindow>,
-    is_searching: Arc<AtomicBool>,
-    case_sensitive: bool,
-    use_regex: bool,
-    search_content: bool,
-    respect_gitignore: bool,
-    exclude_extensions: String,
-    language_filter: Option<String>,
-) {
-    let root_path_clone = root_path.clone();
-    std::thread::spawn(move || {
-        let start_time = Instant::now();
+// Aho-Corasick matcher for a case-insensitive ASCII query (None otherwise:
+// case-sensitive search uses `str::contains`, other queries are lowercased).
+fn literal_matcher(query: &str, case_sensitive: bool) -> Option<AhoCorasick> {
+    if case_sensitive || query.is_empty() || !query.is_ascii() {
+        return None;
+    }
+    AhoCorasick::builder()
+        .ascii_case_insensitive(true)
+        .build([query])
+        .ok()
+}
 
-        // Context preparation (regex compilation, exclusion parsing, etc.)
-        let context = match SearchContext::new(
-            query,
-            case_sensitive,
-            use_regex,
-            search_content,
-            root_path_clone.clone(),
-            exclude_extensions,
-            respect_gitignore,
-            language_filter,
-        ) {
-            Some(ctx) => ctx,
-            None => {
-                let _ = slint::invoke_from_event_loop({
-                    let sender_clone = sender.clone();
-                    move || {
-                        if let Some(window) = sender_clone.upgrade() {
-                            window.set_status_text("Error: Invalid regular expression".into());
-                            window.set_active_threads(0);
-                        }
-                    }
-                });
-                return;
-            }
+// Lowercase abbreviation (e.g., "uc", "u2c"): at least 2 lowercase letters or digits.
+fn is_initials_query(query: &str) -> bool {
+    query.chars().count() >= 2 && query.chars().all(|c| c.is_lowercase() || c.is_numeric())
+}
+
+// Word-initials matching: "uc" matches "user_controller.rs", "user-controller"
+// and "UserController". Words start the name, follow `_`, `-` or `.`, or begin
+// with an uppercase letter.
+fn initials_match(query: &str, text: &str) -> bool {
+    let mut remaining = query.chars().peekable();
+    let mut previous: Option<char> = None;
+
+    for ch in text.chars() {
+        let Some(&wanted) = remaining.peek() else {
+            return true;
         };
+        let word_start = match previous {
+            None => true,
+            Some(p) => matches!(p, '_' | '-' | '.') || (ch.is_uppercase() && !p.is_uppercase()),
+        };
+        if word_start && ch.to_lowercase().eq(std::iter::once(wanted)) {
+            remaining.next();
+        }
+        previous = Some(ch);
+    }
 
-        // Pipeline: WalkBuilder -> process_file() -> results -> push UI.
-        let mut builder = WalkBuilder::new(&root_path);
-        builder
-            .hidden(true)
-            .git_ignore(context.respect_gitignore)
-            .threads(num_cpus::get());
+    remaining.peek().is_none()
+}
 
-        // Manual exclusions.
-        for dir in IGNORED_DIRS {
-            builder.add_ignore(format!("**/{}/**", dir));
+// CamelCase matching: "UC" matches "UserController", "U2C" matches "User2Controller"
+fn camelcase_match(query: &str, text: &str) -> bool {
+    let query_chars: Vec<char> = query.chars().collect();
+    let mut query_idx = 0;
+
+    for ch in text.chars() {
+        if query_idx >= query_chars.len() {
+            return true;
         }
 
-        // Pre-filtering by excluded extensions.
-        if !context.exclude_extensions.is_empty() {
-            for ext in context.exclude_extensions.iter() {
-                if !ext.is_empty() {
-                    builder.add_ignore(format!("**/*{}", ext));
-                }
-            }
+        // Match uppercase letters and digits from query with those in text
+        if (ch.is_uppercase() || ch.is_numeric()) && ch == query_chars[query_idx] {
+            query_idx += 1;
         }
+    }
 
-        let all_results: Vec<SearchResult> = builder
-            .build()
-            .par_bridge()
-            .filter_map(|entry| {
-                if !is_searching.load(Ordering::Relaxed) {
+    query_idx >= query_chars.len()
+}
+
+// Compiles comma-separated globs; None when there is no pattern.
+pub fn build_globset(patterns: &str) -> Result<Option<GlobSet>, globset::Error> {
+    let mut builder = GlobSetBuilder::new();
+    let mut count = 0;
+    for pattern in patterns.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        builder.add(GlobBuilder::new(pattern).case_insensitive(true).build()?);
+        count += 1;
+    }
+    if count == 0 {
+        return Ok(None);
+    }
+    builder.build().map(Some)
+}
+
+// Walker exclusions: ignored directories, excluded extensions and exclude globs.
+pub fn build_overrides(
+    root: &Path,
+    ignored_dirs: &[String],
+    exclude_extensions: &[String],
+    exclude_globs: &str,
+) -> Result<Override, ignore::Error> {
+    let mut builder = OverrideBuilder::new(root);
+    builder.case_insensitive(true)?;
+    for dir in ignored_dirs {
+        builder.add(&format!("!{}/", dir))?;
+    }
+    for ext in exclude_extensions {
+        builder.add(&format!("!*{}", ext))?;
+    }
+    for pattern in exclude_globs
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    {
+        builder.add(&format!("!{}", pattern.trim_start_matches('!')))?;
+    }
+    builder.build()
+}
+
+// Whether the exclusions reject an indexed file or one of its folders (the
+// walker does not enter excluded folders; the index lists their files).
+pub(crate) fn overrides_exclude(overrides: &Override, root: &Path, path: &Path) -> bool {
+    path.ancestors()
+        .take_while(|ancestor| *ancestor != root && ancestor.starts_with(root))
+        .enumerate()
+        .any(|(depth, ancestor)| overrides.matched(ancestor, depth > 0).is_ignore())
+}
+
+// Ignore files of the folders of a listed file (.gitignore, .ignore, .rgignore),
+// read on first use, and the custom ignore file of the settings.
+// Simplification: a file ignored by any folder is ignored (no re-inclusion by
+// a deeper `!pattern`).
+#[derive(Default)]
+struct GitignoreRules {
+    by_folder: Mutex<HashMap<PathBuf, Option<Arc<Gitignore>>>>,
+    // Patterns relative to the searched folder.
+    custom: Option<Gitignore>,
+}
+
+impl GitignoreRules {
+    fn with_ignore_file(root: &Path, file: Option<PathBuf>) -> Self {
+        let custom = file.and_then(|file| {
+            let mut builder = GitignoreBuilder::new(root);
+            builder.add(file);
+            builder.build().ok()
+        });
+        Self {
+            custom,
+            ..Self::default()
+        }
+    }
+
+    fn ignores(&self, root: &Path, path: &Path, is_dir: bool) -> bool {
+        let in_folder = path
+            .ancestors()
+            .skip(1)
+            .take_while(|folder| folder.starts_with(root))
+            .any(|folder| {
+                self.rules(folder).is_some_and(|rules| {
+                    rules.matched_path_or_any_parents(path, is_dir).is_ignore()
+                })
+            });
+        in_folder
+            || self
+                .custom
+                .as_ref()
+                .is_some_and(|rules| rules.matched_path_or_any_parents(path, is_dir).is_ignore())
+    }
+
+    fn rules(&self, folder: &Path) -> Option<Arc<Gitignore>> {
+        let mut by_folder = self.by_folder.lock().unwrap_or_else(|e| e.into_inner());
+        by_folder
+            .entry(folder.to_path_buf())
+            .or_insert_with(|| {
+                // Added by increasing precedence: the last matching pattern wins.
+                let files: Vec<PathBuf> = IGNORE_FILENAMES
+                    .iter()
+                    .map(|name| folder.join(name))
+                    .filter(|file| file.is_file())
+                    .collect();
+                if files.is_empty() {
                     return None;
                 }
+                let mut builder = GitignoreBuilder::new(folder);
+                for file in files {
+                    builder.add(file);
+                }
+                builder.build().ok().map(Arc::new)
+            })
+            .clone()
+    }
+}
 
-                match entry {
-                    Ok(dir_entry) => {
-                        let path = dir_entry.path();
+// Git state of the results: the repository of each folder and the status of
+// each repository, found on first use.
+#[derive(Default)]
+struct GitStatuses {
+    repo_of: Mutex<HashMap<PathBuf, Option<PathBuf>>>,
+    by_repo: Mutex<HashMap<PathBuf, Arc<HashMap<PathBuf, git::FileStatus>>>>,
+}
 
-                        if path.is_file() {
-                            process_file(path, &context)
-                        } else {
-                            None
-                        }
-                    }
+impl GitStatuses {
+    fn status(&self, path: &Path) -> Option<git::FileStatus> {
+        let folder = path.parent()?;
+        let repo = self
+            .repo_of
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(folder.to_path_buf())
+            .or_insert_with(|| git::repo_root(folder).map(Path::to_path_buf))
+            .clone()?;
+        let files = self
+            .by_repo
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(repo.clone())
+            // A repository git cannot read shows no status.
+            .or_insert_with(|| Arc::new(git::status(&repo).unwrap_or_default()))
+            .clone();
+        files.get(path).copied()
+    }
+}
+
+// Whether a folder or file between `root` and `path` has a dot name (tracked
+// files are listed with them).
+fn is_hidden_below(root: &Path, path: &Path) -> bool {
+    path.strip_prefix(root).is_ok_and(|relative| {
+        relative
+            .components()
+            .any(|component| component.as_os_str().to_string_lossy().starts_with('.'))
+    })
+}
+
+// Folder links (symlinks, junctions) met by a walk that follows them. A link
+// into the searched folder is skipped: it is a loop back to a parent, or a
+// folder walked anyway. So is a link to a folder already walked through
+// another link. Targets are compared as canonical paths.
+struct FollowedLinks {
+    root: PathBuf,
+    walked: Mutex<HashSet<PathBuf>>,
+}
+
+impl FollowedLinks {
+    fn new(root: &Path) -> Self {
+        Self {
+            root: std::fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf()),
+            walked: Mutex::default(),
+        }
+    }
+
+    fn should_follow(&self, link: &Path) -> bool {
+        let Ok(target) = std::fs::canonicalize(link) else {
+            return false;
+        };
+        if target.starts_with(&self.root) {
+            return false;
+        }
+        let mut walked = self.walked.lock().unwrap_or_else(|e| e.into_inner());
+        if walked.iter().any(|folder| target.starts_with(folder)) {
+            return false;
+        }
+        walked.insert(target)
+    }
+}
+
+// Source extensions of a language quick filter ("rust", "python", "javascript"...).
+pub fn language_extensions(language: &str) -> Option<&'static [&'static str]> {
+    let extensions: &'static [&'static str] = match language.to_lowercase().as_str() {
+        "rust" => &["rs"],
+        "java" => &["java", "kt", "kts", "gradle"],
+        "python" => &["py", "pyw", "pyi"],
+        "javascript" | "typescript" | "web" => &[
+            "js", "jsx", "mjs", "cjs", "ts", "tsx", "html", "htm", "css", "scss", "vue", "svelte",
+        ],
+        "cpp" | "c" => &["c", "cc", "cpp", "cxx", "h", "hh", "hpp", "hxx"],
+        "csharp" => &["cs", "csx"],
+        "go" => &["go"],
+        _ => return None,
+    };
+    Some(extensions)
+}
+
+// Default exclusions (keeps scan fast and avoids build/vendor directories).
+pub const DEFAULT_IGNORED_DIRS: &[&str] = &[
+    "target",
+    ".git",
+    "node_modules",
+    "vendor",
+    ".idea",
+    ".vscode",
+];
+
+// Folders never entered by searches and indexes, from the settings (the
+// defaults until they are loaded).
+static IGNORED_DIRS: RwLock<Option<Vec<String>>> = RwLock::new(None);
+
+pub fn set_ignored_dirs(dirs: Vec<String>) {
+    *IGNORED_DIRS.write().unwrap_or_else(|e| e.into_inner()) = Some(dirs);
+}
+
+pub fn ignored_dirs() -> Vec<String> {
+    IGNORED_DIRS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(|| {
+            DEFAULT_IGNORED_DIRS
+                .iter()
+                .map(|dir| dir.to_string())
+                .collect()
+        })
+}
+
+// Ignore files honored in every folder when ignore files are respected, as
+// ripgrep does; a later one takes precedence over an earlier one.
+pub const IGNORE_FILENAMES: &[&str] = &[".gitignore", ".ignore", ".rgignore"];
+
+// Ignore file from the settings, applied below the ones of the folders
+// (ripgrep's --ignore-file).
+static IGNORE_FILE: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+pub fn set_ignore_file(file: Option<PathBuf>) {
+    *IGNORE_FILE.write().unwrap_or_else(|e| e.into_inner()) = file;
+}
+
+pub fn ignore_file() -> Option<PathBuf> {
+    IGNORE_FILE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+// Makes the walk honor the ignore files (or none of them). An unreadable
+// custom ignore file is an error.
+pub fn apply_ignore_files(builder: &mut WalkBuilder, respect: bool) -> Result<(), ignore::Error> {
+    builder.git_ignore(respect).ignore(respect);
+    if !respect {
+        return Ok(());
+    }
+    builder.add_custom_ignore_filename(".rgignore");
+    match ignore_file().and_then(|file| builder.add_ignore(file)) {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+// Spawns a search in a separate thread.
+pub fn spawn_search(
+    query: String,
+    root_path: PathBuf,
+    sender: slint::Weak<crate::AppWindow>,
+    token: SearchToken,
+    case_sensitive: bool,
+    use_regex: bool,
+    search_content: bool,
+    respect_gitignore: bool,
+    exclude_extensions: String,
+    language_filter: Option<String>,
+    options: SearchOptions,
+) {
+    let root_path_clone = root_path.clone();
+    std::thread::spawn(move || {
+        let start_time = Instant::now();
+
+        // Context preparation (regex compilation, exclusion parsing, etc.)
+        let mut context = match SearchContext::new(
+            query,
+            case_sensitive,
+            use_regex,
+            search_content,
+            root_path_clone.clone(),
+            exclude_extensions,
+            respect_gitignore,
+            language_filter,
+        ) {
+            Some(ctx) => {
+                let include_globs = options.include_globs.clone();
+                match ctx.with_options(options).with_include_globs(&include_globs) {
+                    Ok(ctx) => ctx,
                     Err(err) => {
-                        eprintln!("Access error: {}", err);
-                        None
+                        update_ui(&token, &sender, move |window| {
+                            window.set_status_text(
+                                tr!("Error: Invalid include pattern: {}", err).into(),
+                            );
+                            window.set_active_threads(0);
+                        });
+                        return;
                     }
                 }
+            }
+            None => {
+                update_ui(&token, &sender, |window| {
+                    window.set_status_text(tr!("Error: Invalid regular expression").into());
+                    window.set_active_threads(0);
+                });
+                return;
+            }
+        };
+
+        // Content searches in an indexed favorite skip the files the index rules out.
+        if context.search_content {
+            if let Some(literal) = context.content_literal().map(str::to_string) {
+                context.content_candidates =
+                    indexer::candidates(&indexer::index_dir(), &root_path, &literal);
+            }
+        }
+
+        // Pipeline: WalkBuilder -> file_results() -> results -> push UI. The
+        // walker and the Rayon workers matching what it lists share the thread
+        // count (its own pool, not the global one).
+        let mut threads = context.options.threads.unwrap_or_else(num_cpus::get);
+        let low_priority = context.options.low_priority;
+        if low_priority {
+            threads = (threads / 2).max(1);
+        }
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .start_handler(move |_| {
+                if low_priority {
+                    lower_thread_priority();
+                }
             })
-            .collect();
+            .build();
+        let pool = match pool {
+            Ok(pool) => pool,
+            Err(err) => {
+                update_ui(&token, &sender, move |window| {
+                    window.set_status_text(
+                        tr!("Error: Unable to start the search threads: {}", err).into(),
+                    );
+                    window.set_active_threads(0);
+                });
+                return;
+            }
+        };
+        let mut builder = WalkBuilder::new(&root_path);
+        builder
+            .hidden(!context.options.include_hidden)
+            .follow_links(context.options.follow_links)
+            .max_depth(context.options.max_depth)
+            .threads(threads);
+        if let Err(err) = apply_ignore_files(&mut builder, context.respect_gitignore) {
+            update_ui(&token, &sender, move |window| {
+                window.set_status_text(tr!("Error: Invalid ignore file: {}", err).into());
+                window.set_active_threads(0);
+            });
+            return;
+        }
+        if context.options.follow_links {
+            let links = FollowedLinks::new(&root_path);
+            builder.filter_entry(move |entry| {
+                !entry.path_is_symlink()
+                    || !entry.file_type().is_some_and(|t| t.is_dir())
+                    || links.should_follow(entry.path())
+            });
+        }
 
-        // 3. Send results in pages (pagination)
-        let total_results_count = all_results.len();
-        let page_size = 50;
-        let first_batch: Vec<SearchResult> = all_results.iter().take(page_size).cloned().collect();
-        let remaining: Vec<SearchResult> = all_results.iter().skip(page_size).cloned().collect();
-
-        let _ = slint::invoke_from_event_loop({
-            let sender_clone = sender.clone();
-            let first_batch_clone = first_batch.clone();
-            let remaining_clone = remaining.clone();
-            move || {
-                if let Some(window) = sender_clone.upgrade() {
-                    // Note: These functions are implemented in main.rs
-                    #[cfg(not(test))]
-                    {
-                        crate::add_results_batch_to_ui(&window, first_batch_clone);
-                        crate::set_remaining_results(remaining_clone);
-                        window.set_total_results(total_results_count as i32);
+        // Ignored directories (unless this search walks them), excluded
+        // extensions and exclude globs are skipped by the walker itself
+        // (excluded folders are not even entered).
+        let ignored = if context.options.walk_ignored_dirs {
+            Vec::new()
+        } else {
+            ignored_dirs()
+        };
+        let overrides = match build_overrides(
+            &root_path,
+            &ignored,
+            &context.exclude_extensions,
+            &context.options.exclude_globs,
+        ) {
+            Ok(overrides) => {
+                builder.overrides(overrides.clone());
+                overrides
+            }
+            Err(err) => {
+                update_ui(&token, &sender, move |window| {
+                    window.set_status_text(tr!("Error: Invalid exclude pattern: {}", err).into());
+                    window.set_active_threads(0);
+                });
+                return;
+            }
+        };
+
+        // A tracked-only or changed-since search lists the files of git instead
+        // of walking (changed files are tracked files).
+        let tracked_files = if let Some(since) = &context.options.changed_since {
+            match git::changed_files(&root_path, since) {
+                Ok(files) => Some(files),
+                Err(err) => {
+                    update_ui(&token, &sender, move |window| {
+                        window.set_status_text(
+                            tr!("Error: Unable to list the changed git files: {}", err).into(),
+                        );
+                        window.set_active_threads(0);
+                    });
+                    return;
+                }
+            }
+        } else if context.options.tracked_only {
+            match git::tracked_files(&root_path) {
+                Ok(files) => Some(files),
+                Err(err) => {
+                    update_ui(&token, &sender, move |window| {
+                        window.set_status_text(
+                            tr!("Error: Unable to list the git files: {}", err).into(),
+                        );
+                        window.set_active_threads(0);
+                    });
+                    return;
+                }
+            }
+        } else {
+            None
+        };
+
+        // A search at a git revision lists the files of its tree.
+        let revision_tree = match &context.options.revision {
+            Some(revision) => match git::tree_files(&root_path, revision) {
+                Ok(files) => Some((files, git::commit_time(&root_path, revision).ok())),
+                Err(err) => {
+                    update_ui(&token, &sender, move |window| {
+                        window.set_status_text(
+                            tr!("Error: Unable to read the git revision: {}", err).into(),
+                        );
+                        window.set_active_threads(0);
+                    });
+                    return;
+                }
+            },
+            None => None,
+        };
+
+        // Filename-only searches of a whole NTFS volume list it from the master
+        // file table; elsewhere they read the persistent index of the folder (or
+        // of a parent folder) when there is one, instead of walking the disk.
+        // Both list folders as well as files, for folder-name matches. Neither
+        // lists hidden files nor follows links, and the index leaves out the
+        // ignored folders.
+        let listed = tracked_files.is_none()
+            && revision_tree.is_none()
+            && !context.search_content
+            && !context.options.include_hidden
+            && !context.options.follow_links;
+        let volume_files = if listed && mft::is_volume_root(&root_path) {
+            mft::list_entries(&root_path)
+        } else {
+            None
+        };
+        let file_index = if !listed || volume_files.is_some() || context.options.walk_ignored_dirs {
+            None
+        } else {
+            index::lookup(&index::index_dir(), &root_path, context.respect_gitignore)
+        };
+        let index_built = file_index.as_ref().map(|index| index.built);
+        // Entries of a share are read with a timeout.
+        let network = network::is_network_path(&root_path);
+        let from_mft = volume_files.is_some();
+        // The index applies the ignore files when it is built; the table does not.
+        let gitignore = (from_mft && context.respect_gitignore)
+            .then(|| GitignoreRules::with_ignore_file(&root_path, ignore_file()));
+
+        // Workers send each match through a channel; this thread forwards them to
+        // the UI in batches so results show up while the walk is still running.
+        let (result_tx, result_rx) = mpsc::channel();
+        let mut all_results: Vec<SearchResult> = Vec::new();
+        // Index in `all_results` of each file found, and whether a file was
+        // found again through another path.
+        let mut seen: HashMap<FileId, usize> = HashMap::new();
+        let mut collapsed = false;
+        let max_results = context.options.max_results;
+        // Set by the collector once a unique result past the limit arrives.
+        let limit_reached = AtomicBool::new(false);
+        let git_statuses = GitStatuses::default();
+        std::thread::scope(|scope| {
+            let context = &context;
+            let token = &token;
+            let limit_reached = &limit_reached;
+            let root_path = &root_path;
+            let pool = &pool;
+            let git_statuses = &git_statuses;
+            // Sends a match; None stops the scan once the limit is reached.
+            let send = move |result_tx: &mut mpsc::Sender<(SearchResult, Option<FileId>)>,
+                             mut result: SearchResult| {
+                // Revision files are not compared with the working tree.
+                let on_disk = !archive::is_entry_path(&result.file_path)
+                    && context.options.revision.is_none();
+                if on_disk && !result.is_dir {
+                    result.git_status = git_statuses.status(Path::new(&result.file_path));
+                }
+                // Paths to the same file are collapsed once collected.
+                let id = if on_disk {
+                    file_id::of(Path::new(&result.file_path))
+                } else {
+                    None
+                };
+                let _ = result_tx.send((result, id));
+                if limit_reached.load(Ordering::Relaxed) {
+                    return None;
+                }
+                Some(())
+            };
+            let gitignore = &gitignore;
+            let overrides = &overrides;
+            // Files and folders listed without a walk (volume table, index or
+            // git); the size is read from the disk when the list does not carry it.
+            let visit_listed =
+                move |result_tx: &mut mpsc::Sender<(SearchResult, Option<FileId>)>,
+                      (path, size, is_dir): (PathBuf, Option<u64>, bool)| {
+                    token.wait_while_paused();
+                    if token.is_cancelled() {
+                        return None;
+                    }
+                    context.walked_entry();
+                    let too_deep = context.options.max_depth.is_some_and(|max| {
+                        path.strip_prefix(root_path)
+                            .is_ok_and(|relative| relative.components().count() > max)
+                    });
+                    let ignored = |is_dir: bool| {
+                        gitignore
+                            .as_ref()
+                            .is_some_and(|rules| rules.ignores(root_path, &path, is_dir))
+                    };
+                    if too_deep || overrides_exclude(overrides, root_path, &path) {
+                        return Some(());
+                    }
+                    // Folders deleted since the list was made are dropped.
+                    if is_dir {
+                        return match process_dir(&path, context) {
+                            Some(result) if path.is_dir() && !ignored(true) => {
+                                send(result_tx, result)
+                            }
+                            _ => Some(()),
+                        };
+                    }
+                    if !context.size_allowed(|| {
+                        size.or_else(|| std::fs::metadata(&path).ok().map(|m| m.len()))
+                    }) {
+                        return Some(());
+                    }
+                    // Files deleted since the list was made are dropped.
+                    let results = file_results(&path, context);
+                    if results.is_empty() || !path.is_file() || ignored(false) {
+                        return Some(());
+                    }
+                    for result in results {
+                        send(result_tx, result)?;
                     }
+                    Some(())
+                };
+            scope.spawn(move || {
+                pool.install(move || {
+                    if let Some((files, committed)) = revision_tree {
+                        let mut result_tx = result_tx;
+                        search_revision(files, committed, context, overrides, token, |result| {
+                            send(&mut result_tx, result)
+                        });
+                        return;
+                    }
+                    if let Some(files) = tracked_files {
+                        let include_hidden = context.options.include_hidden;
+                        files
+                            .into_par_iter()
+                            .filter(|path| include_hidden || !is_hidden_below(root_path, path))
+                            .map(|path| (path, None, false))
+                            .try_for_each_with(result_tx, visit_listed);
+                        return;
+                    }
+                    if let Some(entries) = volume_files {
+                        entries
+                            .into_par_iter()
+                            .map(|(path, is_dir)| (path, None, is_dir))
+                            .try_for_each_with(result_tx, visit_listed);
+                        return;
+                    }
+                    if let Some(file_index) = file_index {
+                        file_index
+                            .entries_under(root_path)
+                            .map(|(path, size, is_dir)| (path, Some(size), is_dir))
+                            .try_for_each_with(result_tx, visit_listed);
+                        return;
+                    }
+
+                    // A walk blocked on a stalled share still ends on cancel.
+                    let walk = builder.build();
+                    let entries: Box<dyn Iterator<Item = Result<DirEntry, ignore::Error>> + Send> =
+                        if network {
+                            let token = token.clone();
+                            Box::new(network::detach(walk, move || token.is_cancelled()))
+                        } else {
+                            Box::new(walk)
+                        };
+                    entries
+                        .par_bridge()
+                        .try_for_each_with(result_tx, |result_tx, entry| {
+                            // None short-circuits the walk once the scan is cancelled.
+                            token.wait_while_paused();
+                            if token.is_cancelled() {
+                                return None;
+                            }
+
+                            match entry {
+                                Ok(dir_entry) => {
+                                    context.walked_entry();
+                                    let path = dir_entry.path();
+
+                                    // Entries of a share that do not answer are skipped.
+                                    if network && !network_entry_answers(path, context, token)? {
+                                        return Some(());
+                                    }
+
+                                    // The size comes from the walker's metadata (cached
+                                    // from the directory listing on Windows).
+                                    if path.is_file()
+                                        && context.size_allowed(|| {
+                                            dir_entry.metadata().ok().map(|m| m.len())
+                                        })
+                                    {
+                                        for result in file_results(path, context) {
+                                            send(result_tx, result)?;
+                                        }
+                                    } else if dir_entry.depth() > 0
+                                        && dir_entry.file_type().is_some_and(|t| t.is_dir())
+                                    {
+                                        if let Some(result) = process_dir(path, context) {
+                                            return send(result_tx, result);
+                                        }
+                                    }
+                                }
+                                Err(err) => context.access_errors.record(err.to_string()),
+                            }
+                            Some(())
+                        });
+                })
+            });
+
+            let mut streamer = ResultStreamer::new(token.clone(), sender.clone());
+            let mut last_flush = Instant::now();
+            let mut last_report = Instant::now();
+            loop {
+                match result_rx.recv_timeout(STREAM_INTERVAL) {
+                    Ok((result, id)) => match id.and_then(|id| seen.get(&id).copied()) {
+                        Some(first) => {
+                            all_results[first].also_at.push(result.file_path);
+                            collapsed = true;
+                        }
+                        // Only distinct files count toward the limit; one past it
+                        // proves the list is truncated.
+                        None if max_results.is_some_and(|max| all_results.len() >= max) => {
+                            limit_reached.store(true, Ordering::Relaxed);
+                        }
+                        None => {
+                            if let Some(id) = id {
+                                seen.insert(id, all_results.len());
+                            }
+                            if !context.options.refresh {
+                                streamer.pending.push(result.clone());
+                            }
+                            all_results.push(result);
+                        }
+                    },
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+                if last_flush.elapsed() >= STREAM_INTERVAL {
+                    streamer.flush();
+                    last_flush = Instant::now();
+                }
+                // Progress, elapsed time and rate tick while the scan runs,
+                // results or not.
+                if last_report.elapsed() >= PROGRESS_INTERVAL {
+                    let label = if token.is_paused() {
+                        tr!("Paused...")
+                    } else if context.options.refresh {
+                        tr!("Refreshing...")
+                    } else {
+                        tr!("Scanning...")
+                    };
+                    let status = context.progress.status(
+                        label,
+                        all_results.len(),
+                        context.search_content,
+                        start_time.elapsed(),
+                    );
+                    update_ui(token, &sender, move |window| {
+                        window.set_status_text(status.into());
+                    });
+                    last_report = Instant::now();
                 }
             }
+            streamer.flush();
         });
 
+        sort_results(&mut all_results, context.options.order);
+        let truncated = max_results.filter(|_| limit_reached.load(Ordering::Relaxed));
+        let skipped_large = context.content_stats.skipped_large.load(Ordering::Relaxed);
+        let truncated_files = context.content_stats.truncated.load(Ordering::Relaxed);
+        let max_lines = context.options.max_content_lines.unwrap_or_default();
+        let ruled_out = context.content_stats.ruled_out.load(Ordering::Relaxed);
+        let cloud_only = context.content_stats.cloud_only.load(Ordering::Relaxed);
+        let content_index_updated = context
+            .content_candidates
+            .as_ref()
+            .and_then(|candidates| candidates.updated);
+
+        // A complete result set lets the time filters narrow it without a new scan.
+        if !token.is_cancelled() && truncated.is_none() {
+            let cached = all_results.clone();
+            let modified_since = context.options.modified_since;
+            let modified_until = context.options.modified_until;
+            update_ui(&token, &sender, move |window| {
+                crate::cache_search_results(window, modified_since, modified_until, cached);
+            });
+        }
+
+        // 3. Streamed results arrive in walk order; a sorted order replaces them
+        // once everything is known so the top of the list holds the best results
+        // (name matches first, when content matches are mixed in). So do
+        // results that gained other paths since they were streamed.
+        let total_results_count = all_results.len();
+        if context.options.order != ResultOrder::Unsorted
+            || context.options.refresh
+            || context.search_content
+            || collapsed
+        {
+            update_ui(&token, &sender, move |window| {
+                crate::show_results(window, all_results);
+            });
+        }
+
         // 4. End of scan
         let duration = start_time.elapsed().as_millis() as u64;
-        let _ = slint::invoke_from_event_loop(move || {
-            if let Some(window) = sender.upgrade() {
-                window.set_status_text(
-                    format!(
-                        "Completed: {} results in {}ms",
-                        total_results_count, duration
-                    )
-                    .into(),
-                );
-                window.set_active_threads(0);
+        let revision = context.options.revision.clone();
+        let access_errors = context.access_errors.count();
+        let skipped_paths = context.access_errors.take_listed();
+        update_ui(&token, &sender, move |window| {
+            let mut status = tr!(
+                "Completed: {} results in {}ms",
+                total_results_count,
+                duration
+            );
+            let mut notes = Vec::new();
+            if let Some(revision) = revision {
+                notes.push(tr!("at revision {}", revision));
+            }
+            if from_mft {
+                notes.push(tr!("from the master file table").to_string());
             }
+            if let Some(built) = index_built {
+                notes.push(tr!(
+                    "from the index of {}",
+                    DateTime::<Local>::from(built).format("%Y-%m-%d %H:%M")
+                ));
+            }
+            if let Some(max) = truncated {
+                notes.push(tr!("truncated at {}", max));
+            }
+            if skipped_large > 0 {
+                notes.push(tr!("{} large files not searched", skipped_large));
+            }
+            if cloud_only > 0 {
+                notes.push(tr!("{} cloud-only files not searched", cloud_only));
+            }
+            if truncated_files > 0 {
+                notes.push(tr!(
+                    "{} files searched up to line {} only",
+                    truncated_files,
+                    max_lines
+                ));
+            }
+            if ruled_out > 0 {
+                let mut note = tr!("{} files ruled out by the content index", ruled_out);
+                if let Some(updated) = content_index_updated {
+                    let updated = DateTime::<Local>::from(updated);
+                    note.push_str(&tr!(" of {}", updated.format("%Y-%m-%d %H:%M")));
+                }
+                notes.push(note);
+            }
+            if access_errors > 0 {
+                notes.push(tr!("{} paths skipped (access errors)", access_errors));
+            }
+            if !notes.is_empty() {
+                status.push_str(&format!(" ({})", notes.join(", ")));
+            }
+            window.set_status_text(status.into());
+            window.set_active_threads(0);
+            crate::show_skipped_paths(window, access_errors, skipped_paths);
+            crate::update_extension_facets(window);
+            crate::record_search_audit(window, total_results_count);
+        });
+    });
+}
+
+// A low-priority scan sleeps `LOW_PRIORITY_PAUSE` every `LOW_PRIORITY_BATCH`
+// entries, leaving the disk to foreground programs meanwhile.
+const LOW_PRIORITY_BATCH: usize = 256;
+const LOW_PRIORITY_PAUSE: Duration = Duration::from_millis(20);
+
+// Background mode of the calling thread: lower CPU, I/O and memory priority.
+#[cfg(windows)]
+fn lower_thread_priority() {
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentThread, SetThreadPriority, THREAD_MODE_BACKGROUND_BEGIN,
+    };
+    // SAFETY: the pseudo-handle of the current thread needs no closing.
+    unsafe {
+        SetThreadPriority(GetCurrentThread(), THREAD_MODE_BACKGROUND_BEGIN);
+    }
+}
+
+#[cfg(not(windows))]
+fn lower_thread_priority() {}
+
+// How often streamed results are pushed to the UI during a scan.
+const STREAM_INTERVAL: Duration = Duration::from_millis(100);
+
+// How often the status bar shows the progress of a running scan.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+// Forwards results to the UI as they are found.
+struct ResultStreamer {
+    token: SearchToken,
+    sender: slint::Weak<crate::AppWindow>,
+    pending: Vec<SearchResult>,
+    total: usize,
+}
+
+impl ResultStreamer {
+    fn new(token: SearchToken, sender: slint::Weak<crate::AppWindow>) -> Self {
+        Self {
+            token,
+            sender,
+            pending: Vec::new(),
+            total: 0,
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+
+        let batch = std::mem::take(&mut self.pending);
+        self.total += batch.len();
+
+        let total = self.total;
+        update_ui(&self.token, &self.sender, move |window| {
+            crate::add_results_batch_to_ui(window, batch);
+            window.set_total_results(total as i32);
         });
+    }
+}
+
+// Sorts the results before they are shown, best first.
+pub fn sort_results(results: &mut [SearchResult], order: ResultOrder) {
+    match order {
+        // Stable: walk order within each kind.
+        ResultOrder::Unsorted => results.sort_by_key(|result| result.match_kind),
+        ResultOrder::RecentFirst => results.sort_by(|a, b| {
+            a.match_kind
+                .cmp(&b.match_kind)
+                .then_with(|| b.modified.cmp(&a.modified))
+                .then_with(|| a.file_path.cmp(&b.file_path))
+        }),
+        // Ties: shorter names first (fewer unmatched characters).
+        ResultOrder::Relevance => results.sort_by(|a, b| {
+            a.match_kind
+                .cmp(&b.match_kind)
+                .then_with(|| b.score.cmp(&a.score))
+                .then_with(|| a.file_name.len().cmp(&b.file_name.len()))
+                .then_with(|| a.file_path.cmp(&b.file_path))
+        }),
+        ResultOrder::ShallowFirst => results.sort_by_cached_key(|result| {
+            (
+                result.match_kind,
+                Path::new(&result.relative_path).components().count(),
+                result.file_name.to_lowercase(),
+                result.file_path.clone(),
+            )
+        }),
+    }
+}
+
+// Sends the results to the UI (the list converts the rows it displays).
+pub(crate) fn publish_results(
+    token: &SearchToken,
+    sender: &slint::Weak<crate::AppWindow>,
+    all_results: Vec<SearchResult>,
+) -> usize {
+    let total_results_count = all_results.len();
+
+    update_ui(token, sender, move |window| {
+        // Note: This function is implemented in main.rs
+        crate::add_results_batch_to_ui(window, all_results);
+        window.set_total_results(total_results_count as i32);
+    });
+
+    total_results_count
+}
+
+// Runs `update` on the UI thread, unless the scan was cancelled in the meantime
+// (a newer scan owns the results list by then).
+pub(crate) fn update_ui(
+    token: &SearchToken,
+    sender: &slint::Weak<crate::AppWindow>,
+    update: impl FnOnce(&crate::AppWindow) + Send + 'static,
+) {
+    let token = token.clone();
+    let sender = sender.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if token.is_cancelled() {
+            return;
+        }
+        if let Some(window) = sender.upgrade() {
+            update(&window);
+        }
     });
 }
 
 /// Unit scan function (executed by Rayon threads)
 fn process_file(path: &Path, context: &SearchContext) -> Option<SearchResult> {
     let file_name = path.file_name()?.to_string_lossy();
+
+    let (since, until) = context.modified_bounds();
+    if since.is_some() || until.is_some() {
+        let modified = modified_time(path)?;
+        if since.is_some_and(|since| modified < since)
+            || until.is_some_and(|until| modified >= until)
+        {
+            return None;
+        }
+    }
+
     let extension = path
         .extension()
         .unwrap_or_default()
         .to_string_lossy()
         .to_string();
 
-    // Filter by excluded extension
-    let ext_lower = extension.to_lowercase();
-    if !context.exclude_extensions.is_empty() {
-        for excluded in &context.exclude_extensions {
-            if excluded.starts_with('.') && ext_lower == excluded[1..] {
-                return None;
-            } else if ext_lower == *excluded {
-                return None;
-            }
-            // Support for patterns like "node_modules"
-            if path.to_string_lossy().contains(excluded) {
-                return None;
-            }
-        }
+    let relative = path.strip_prefix(&context.root_path).unwrap_or(path);
+    if !context.name_allowed(&extension.to_lowercase(), relative) {
+        return None;
+    }
+
+    // Content-based type filter (reads the file header).
+    if !context.qualifiers.kinds.is_empty()
+        && !filetype::detect(path).is_some_and(|kind| context.qualifiers.kinds.contains(&kind))
+    {
+        return None;
+    }
+
+    // Authenticode filter (executables only).
+    if let Some(wanted) = context.qualifiers.signed {
+        if !signature::is_signable(&extension) {
+            return None;
+        }
+        match signature::verify(path) {
+            signature::SignatureStatus::Unsupported => return None,
+            status if status.is_trusted() != wanted => return None,
+            _ => {}
+        }
+    }
+
+    // A. Match on filename (Absolute priority & Fast)
+    if let Some(score) = context.name_score(&context.match_target(&file_name, relative)) {
+        return Some(SearchResult {
+            name_ranges: context.name_ranges(&file_name),
+            modified: modified_time(path),
+            size: file_size(path),
+            score,
+            ..SearchResult::for_path(path, &context.root_path)
+        });
+    }
+
+    // If not searching content, stop here
+    if !context.search_content {
+        return None;
+    }
+
+    // B. Match on content (Slower, requires reading)
+    // Ignore common binaries to avoid reading arbitrary data, unless OCR reads
+    // their text.
+    let read_with_ocr = context.options.ocr && ocr::is_supported(&extension);
+    if is_likely_binary(&extension) && !read_with_ocr {
+        return None;
+    }
+
+    // Size limit of the content search, checked before opening the file.
+    if let Some(max) = context.options.max_content_size {
+        if std::fs::metadata(path).is_ok_and(|m| m.len() > max) {
+            context
+                .content_stats
+                .skipped_large
+                .fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+    }
+
+    if !context.may_read(path) {
+        return None;
+    }
+
+    if let Some(candidates) = &context.content_candidates {
+        if candidates.rules_out(path, modified_time(path)) {
+            context
+                .content_stats
+                .ruled_out
+                .fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+    }
+
+    context.progress.searched.fetch_add(1, Ordering::Relaxed);
+    // Emails are searched in their decoded headers and body.
+    let matches = if read_with_ocr {
+        ocr::text(path)
+            .map(|text| find_matches_in_buffer(text.as_bytes(), context))
+            .unwrap_or_default()
+    } else if email::is_email(&extension) {
+        email::text(path)
+            .map(|text| find_matches_in_buffer(text.as_bytes(), context))
+            .unwrap_or_default()
+    } else {
+        find_line_matches(path, context)
+    };
+    let first = matches.first()?;
+    Some(SearchResult {
+        line_match: format!("L{}: {}", first.line_number, first.text),
+        matches,
+        modified: modified_time(path),
+        size: file_size(path),
+        match_kind: MatchKind::Content,
+        ..SearchResult::for_path(path, &context.root_path)
+    })
+}
+
+// Relative path with "/" separators on every platform.
+fn slash_path(relative: &Path) -> String {
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+// Reads an entry of a network root with a timeout: false when it does not
+// answer (recorded as an access error), None once the scan is cancelled.
+fn network_entry_answers(
+    path: &Path,
+    context: &SearchContext,
+    token: &SearchToken,
+) -> Option<bool> {
+    let probed = network::probe(path, context.search_content, network::IO_TIMEOUT, || {
+        token.is_cancelled()
+    });
+    match probed {
+        Ok(()) => Some(true),
+        Err(err) if err.kind() == std::io::ErrorKind::Interrupted => None,
+        Err(err) => {
+            context
+                .access_errors
+                .record(format!("{}: {}", path.display(), err));
+            Some(false)
+        }
+    }
+}
+
+// A walked folder whose name matches the query.
+fn process_dir(path: &Path, context: &SearchContext) -> Option<SearchResult> {
+    if !context.matches_folders() {
+        return None;
+    }
+    let file_name = path.file_name()?.to_string_lossy();
+    let relative = path.strip_prefix(&context.root_path).unwrap_or(path);
+    let score = if context.options.match_path {
+        context.name_score(&slash_path(relative))?
+    } else {
+        context.name_score(&file_name)?
+    };
+
+    let modified = modified_time(path);
+    let (since, until) = context.modified_bounds();
+    if since.is_some_and(|since| modified.is_none_or(|modified| modified < since))
+        || until.is_some_and(|until| modified.is_none_or(|modified| modified >= until))
+    {
+        return None;
+    }
+    if !context.qualifiers.path_allowed(&relative.to_string_lossy())
+        || context
+            .include_globs
+            .as_ref()
+            .is_some_and(|include| !include.is_match(relative))
+    {
+        return None;
+    }
+
+    Some(SearchResult {
+        extension: String::new(),
+        name_ranges: context.name_ranges(&file_name),
+        is_dir: true,
+        modified,
+        score,
+        ..SearchResult::for_path(path, &context.root_path)
+    })
+}
+
+// Results of a file: its own match, then the matching entries of an archive
+// when archives are searched.
+fn file_results(path: &Path, context: &SearchContext) -> Vec<SearchResult> {
+    let mut results: Vec<SearchResult> = process_file(path, context).into_iter().collect();
+    let is_archive = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(archive::is_archive);
+    if context.options.search_archives && is_archive && context.may_read(path) {
+        results.extend(process_archive(path, context));
+    }
+    results
+}
+
+// Archive entries larger than this are not searched for content.
+const MAX_ARCHIVE_ENTRY_SIZE: u64 = 64 * 1024 * 1024;
+
+// Entries of an archive matching the query, by name or (for a content search)
+// by content. Entries carry the modification time of the archive itself.
+fn process_archive(path: &Path, context: &SearchContext) -> Vec<SearchResult> {
+    let mut results = Vec::new();
+    let modified = modified_time(path);
+    let (since, until) = context.modified_bounds();
+    if since.is_some_and(|since| modified.is_none_or(|modified| modified < since))
+        || until.is_some_and(|until| modified.is_none_or(|modified| modified >= until))
+    {
+        return results;
+    }
+    // File kinds and signatures are read from the disk.
+    if !context.qualifiers.kinds.is_empty() || context.qualifiers.signed.is_some() {
+        return results;
+    }
+
+    let archive_path = path.to_string_lossy();
+    let relative_archive = path
+        .strip_prefix(&context.root_path)
+        .unwrap_or(path)
+        .to_string_lossy();
+    let _ = archive::for_each_file(path, |inner, size, reader| {
+        let inner_path = Path::new(inner);
+        let Some(file_name) = inner_path.file_name().map(|name| name.to_string_lossy()) else {
+            return;
+        };
+        let extension = inner_path
+            .extension()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let relative_path = archive::entry_path(&relative_archive, inner);
+        if !context.name_allowed(&extension.to_lowercase(), Path::new(&relative_path))
+            || !context.size_allowed(|| Some(size))
+        {
+            return;
+        }
+
+        let mut result = SearchResult {
+            file_name: file_name.to_string(),
+            file_path: archive::entry_path(&archive_path, inner),
+            relative_path,
+            extension,
+            modified,
+            size: Some(size),
+            ..SearchResult::default()
+        };
+        let target = context.match_target(&file_name, Path::new(&result.relative_path));
+        if let Some(score) = context.name_score(&target) {
+            result.score = score;
+            result.name_ranges = context.name_ranges(&file_name);
+            results.push(result);
+            return;
+        }
+
+        if !context.search_content || is_likely_binary(&result.extension) {
+            return;
+        }
+        if context
+            .options
+            .max_content_size
+            .is_some_and(|max| size > max)
+        {
+            context
+                .content_stats
+                .skipped_large
+                .fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        if size > MAX_ARCHIVE_ENTRY_SIZE {
+            return;
+        }
+        context.progress.searched.fetch_add(1, Ordering::Relaxed);
+        // Same decoding as the line reader of files on disk.
+        let mut text = Vec::new();
+        let decoded = DecodeReaderBytesBuilder::new()
+            .strip_bom(true)
+            .utf8_passthru(true)
+            .build(reader.take(MAX_ARCHIVE_ENTRY_SIZE))
+            .read_to_end(&mut text);
+        if decoded.is_err() {
+            return;
+        }
+        result.matches = find_matches_in_buffer(&text, context);
+        if let Some(first) = result.matches.first() {
+            result.match_kind = MatchKind::Content;
+            result.line_match = format!("L{}: {}", first.line_number, first.text);
+            results.push(result);
+        }
+    });
+    results
+}
+
+// Files of a git revision matching the query, by name or (for a content search)
+// by the content git reads. Results carry the working tree path of the file and
+// the commit time.
+fn search_revision(
+    files: Vec<git::TreeFile>,
+    committed: Option<SystemTime>,
+    context: &SearchContext,
+    overrides: &Override,
+    token: &SearchToken,
+    mut send: impl FnMut(SearchResult) -> Option<()>,
+) {
+    let (since, until) = context.modified_bounds();
+    if since.is_some_and(|since| committed.is_none_or(|committed| committed < since))
+        || until.is_some_and(|until| committed.is_none_or(|committed| committed >= until))
+    {
+        return;
+    }
+    // File kinds and signatures are read from the disk.
+    if !context.qualifiers.kinds.is_empty() || context.qualifiers.signed.is_some() {
+        return;
+    }
+
+    let root = &context.root_path;
+    // Files matched by name are sent right away; the others wait for git to
+    // read their content.
+    let mut to_read: Vec<(&git::TreeFile, SearchResult)> = Vec::new();
+    for file in &files {
+        token.wait_while_paused();
+        if token.is_cancelled() {
+            return;
+        }
+        context.walked_entry();
+        let Ok(relative) = file.path.strip_prefix(root) else {
+            continue;
+        };
+        let Some(file_name) = file.path.file_name().map(|name| name.to_string_lossy()) else {
+            continue;
+        };
+        let extension = file
+            .path
+            .extension()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let too_deep = context
+            .options
+            .max_depth
+            .is_some_and(|max| relative.components().count() > max);
+        if too_deep
+            || (!context.options.include_hidden && is_hidden_below(root, &file.path))
+            || overrides_exclude(overrides, root, &file.path)
+            || !context.name_allowed(&extension.to_lowercase(), relative)
+            || !context.size_allowed(|| Some(file.size))
+        {
+            continue;
+        }
+
+        let mut result = SearchResult {
+            modified: committed,
+            size: Some(file.size),
+            ..SearchResult::for_path(&file.path, root)
+        };
+        if let Some(score) = context.name_score(&context.match_target(&file_name, relative)) {
+            result.score = score;
+            result.name_ranges = context.name_ranges(&file_name);
+            if send(result).is_none() {
+                return;
+            }
+            continue;
+        }
+
+        if !context.search_content || is_likely_binary(&result.extension) {
+            continue;
+        }
+        if context
+            .options
+            .max_content_size
+            .is_some_and(|max| file.size > max)
+        {
+            context
+                .content_stats
+                .skipped_large
+                .fetch_add(1, Ordering::Relaxed);
+            continue;
+        }
+        to_read.push((file, result));
+    }
+    if to_read.is_empty() {
+        return;
+    }
+
+    let objects: Vec<&str> = to_read
+        .iter()
+        .map(|(file, _)| file.object.as_str())
+        .collect();
+    let read = git::read_blobs(root, &objects, |index, content| {
+        token.wait_while_paused();
+        if token.is_cancelled() {
+            return None;
+        }
+        context.progress.searched.fetch_add(1, Ordering::Relaxed);
+        // Same decoding as the line reader of files on disk.
+        let mut text = Vec::new();
+        let decoded = DecodeReaderBytesBuilder::new()
+            .strip_bom(true)
+            .utf8_passthru(true)
+            .build(content)
+            .read_to_end(&mut text);
+        if decoded.is_err() {
+            return Some(());
+        }
+        let matches = find_matches_in_buffer(&text, context);
+        let Some(first) = matches.first() else {
+            return Some(());
+        };
+        let mut result = to_read[index].1.clone();
+        result.line_match = format!("L{}: {}", first.line_number, first.text);
+        result.matches = matches;
+        result.match_kind = MatchKind::Content;
+        send(result)
+    });
+    // The files left unread are listed in the skipped paths panel.
+    if let Err(err) = read {
+        context
+            .access_errors
+            .record(format!("{}: git: {}", root.display(), err));
+    }
+}
+
+// Lines kept per file (the count shown in the UI stops there).
+const MAX_MATCHES_PER_FILE: usize = 1000;
+
+// Files at least this large are memory-mapped and scanned as a single buffer.
+const MMAP_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+// Every matching line of a text file (trimmed), in file order.
+fn find_line_matches(path: &Path, context: &SearchContext) -> Vec<LineMatch> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) => {
+            context
+                .access_errors
+                .record(format!("{}: {}", path.display(), err));
+            return Vec::new();
+        }
+    };
+
+    if file.metadata().is_ok_and(|m| m.len() >= MMAP_THRESHOLD) {
+        // SAFETY: the map is only read. If another process truncates the file
+        // meanwhile, reads past the new end fail, as for any mmap-based reader.
+        if let Ok(mmap) = unsafe { Mmap::map(&file) } {
+            // UTF-16 files go through the transcoding reader below.
+            match encoding_rs::Encoding::for_bom(&mmap) {
+                None => return find_matches_in_buffer(&mmap, context),
+                Some((encoding, bom_length)) if encoding == encoding_rs::UTF_8 => {
+                    return find_matches_in_buffer(&mmap[bom_length..], context)
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    let mut matches = Vec::new();
+    // Files starting with a UTF-16 (or UTF-8) BOM are transcoded to UTF-8;
+    // other files are read as they are. BufReader for I/O performance.
+    let mut reader = BufReader::new(
+        DecodeReaderBytesBuilder::new()
+            .strip_bom(true)
+            .utf8_passthru(true)
+            .build(file),
+    );
+    let mut line = Vec::new();
+
+    // Scan line by line with an index
+    for i in 0.. {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        if context
+            .options
+            .max_content_lines
+            .is_some_and(|max| i >= max)
+        {
+            context
+                .content_stats
+                .truncated
+                .fetch_add(1, Ordering::Relaxed);
+            break;
+        }
+        if let Some(found) = line_match(i + 1, &decode_line(&line), context) {
+            matches.push(found);
+            if matches.len() >= MAX_MATCHES_PER_FILE {
+                break;
+            }
+        }
+    }
+
+    matches
+}
+
+// Same as the line reader over an in-memory buffer. When the query allows it, a
+// byte-level regex jumps straight to the candidate lines and everything in
+// between is skipped without being decoded.
+fn find_matches_in_buffer(buffer: &[u8], context: &SearchContext) -> Vec<LineMatch> {
+    let mut buffer = buffer;
+    if let Some(max) = context.options.max_content_lines {
+        let end = match max {
+            0 => Some(0),
+            _ => memchr::memchr_iter(b'\n', buffer)
+                .nth(max - 1)
+                .map(|i| i + 1),
+        };
+        if let Some(end) = end.filter(|end| *end < buffer.len()) {
+            context
+                .content_stats
+                .truncated
+                .fetch_add(1, Ordering::Relaxed);
+            buffer = &buffer[..end];
+        }
+    }
+
+    // A regex matches the decoded lines (`caf.` the 0xE9 of "café" in
+    // Windows-1252): its prefilter only skips ahead in UTF-8 buffers.
+    let prefilter = context
+        .content_prefilter()
+        .filter(|_| !context.use_regex || std::str::from_utf8(buffer).is_ok());
+    let mut matches = Vec::new();
+    let mut line_number = 1;
+    let mut counted = 0;
+    let mut position = 0;
+
+    while position < buffer.len() {
+        let (start, end) = match &prefilter {
+            Some(re) => {
+                let Some(found) = re.find_at(buffer, position) else {
+                    break;
+                };
+                let start = memchr::memrchr(b'\n', &buffer[..found.start()]).map_or(0, |i| i + 1);
+                (start, line_end(buffer, found.start()))
+            }
+            None => (position, line_end(buffer, position)),
+        };
+
+        line_number += memchr::memchr_iter(b'\n', &buffer[counted..start]).count();
+        counted = start;
+        if let Some(found) = line_match(line_number, &decode_line(&buffer[start..end]), context) {
+            matches.push(found);
+            if matches.len() >= MAX_MATCHES_PER_FILE {
+                break;
+            }
+        }
+        position = end + 1;
+    }
+
+    matches
+}
+
+// Offset of the newline ending the line that contains `from` (or the buffer end).
+fn line_end(buffer: &[u8], from: usize) -> usize {
+    memchr::memchr(b'\n', &buffer[from..]).map_or(buffer.len(), |i| from + i)
+}
+
+// Builds the result entry of one line when it matches.
+fn line_match(line_number: usize, line: &str, context: &SearchContext) -> Option<LineMatch> {
+    let content = line.trim_end_matches(['\n', '\r']);
+    if !context.is_match(content) {
+        return None;
+    }
+    let text = content.trim();
+    let ranges = context.match_ranges(text);
+    let indent = content.len() - content.trim_start().len();
+    let column = ranges.first().map_or(0, |range| {
+        content[..indent + range.start].chars().count() + 1
+    });
+    Some(LineMatch {
+        line_number,
+        column,
+        text: text.to_string(),
+        ranges,
+    })
+}
+
+// A line that is not valid UTF-8 comes from a legacy file: it is decoded as
+// Windows-1252 (a superset of Latin-1), the usual encoding of older Windows text.
+fn decode_line(bytes: &[u8]) -> Cow<'_, str> {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Cow::Borrowed(text),
+        Err(_) => {
+            encoding_rs::WINDOWS_1252
+                .decode_without_bom_handling(bytes)
+                .0
+        }
+    }
+}
+
+// Splits a line into plain and matched parts, for highlighting.
+pub fn split_highlights<'a>(text: &'a str, ranges: &[Range<usize>]) -> Vec<(&'a str, bool)> {
+    let mut parts = Vec::new();
+    let mut position = 0;
+    for range in ranges {
+        let Some(matched) = text.get(range.clone()) else {
+            continue;
+        };
+        if range.start < position {
+            continue;
+        }
+        if range.start > position {
+            parts.push((&text[position..range.start], false));
+        }
+        parts.push((matched, true));
+        position = range.end;
+    }
+    if position < text.len() {
+        parts.push((&text[position..], false));
+    }
+    parts
+}
+
+// Placeholder attributes of cloud files (OneDrive, other cloud sync engines):
+// reading their data downloads them.
+const FILE_ATTRIBUTE_OFFLINE: u32 = 0x1000;
+const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x40000;
+const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x400000;
+
+#[cfg_attr(not(windows), allow(dead_code))]
+fn is_placeholder_attributes(attributes: u32) -> bool {
+    attributes
+        & (FILE_ATTRIBUTE_OFFLINE
+            | FILE_ATTRIBUTE_RECALL_ON_OPEN
+            | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS)
+        != 0
+}
+
+// A cloud-only file, whose content is not on disk. The metadata is read
+// without opening the data, which would download it.
+#[cfg(windows)]
+fn is_cloud_placeholder(path: &Path) -> bool {
+    use std::os::windows::fs::MetadataExt;
+
+    std::fs::symlink_metadata(path)
+        .is_ok_and(|metadata| is_placeholder_attributes(metadata.file_attributes()))
+}
+
+#[cfg(not(windows))]
+fn is_cloud_placeholder(_path: &Path) -> bool {
+    false
+}
+
+// Only looked up for matches, not for every scanned file.
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    path.metadata().and_then(|m| m.modified()).ok()
+}
+
+fn file_size(path: &Path) -> Option<u64> {
+    path.metadata().map(|m| m.len()).ok()
+}
+
+/// Helper to ignore binary extensions (non-exhaustive list)
+pub(crate) fn is_likely_binary(ext: &str) -> bool {
+    matches!(
+        ext.to_lowercase().as_str(),
+        "exe" | "dll" | "png" | "jpg" | "pdf" | "zip" | "class" | "jar" | "ico" | "mp3" | "mp4"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::path::PathBuf;
+
+    // ============================================================================
+    // Tests de SearchContext::new
+    // ============================================================================
+
+    #[test]
+    fn test_search_context_creation_valid() {
+        let ctx = SearchContext::new(
+            "test".to_string(),
+            false,
+            false,
+            false,
+            PathBuf::from("/tmp"),
+            ".exe,.dll".to_string(),
+            true,
+            None,
+        );
+        assert!(ctx.is_some());
+        let ctx = ctx.unwrap();
+        assert_eq!(ctx.query, "test");
+        assert_eq!(ctx.query_lower, "test");
+        assert_eq!(ctx.exclude_extensions, vec![".exe", ".dll"]);
+        assert!(!ctx.case_sensitive);
+        assert!(!ctx.use_regex);
+    }
+
+    #[test]
+    fn test_search_context_invalid_regex() {
+        let ctx = SearchContext::new(
+            "[invalid".to_string(),
+            false,
+            true,
+            false,
+            PathBuf::from("/tmp"),
+            "".to_string(),
+            true,
+            None,
+        );
+        assert!(ctx.is_none(), "Invalid regex should return None");
+    }
+
+    #[test]
+    fn test_search_context_valid_regex() {
+        let ctx = SearchContext::new(
+            r"\d+".to_string(),
+            false,
+            true,
+            false,
+            PathBuf::from("/tmp"),
+            "".to_string(),
+            true,
+            None,
+        );
+        assert!(ctx.is_some(), "Valid regex should return Some");
+    }
+
+    #[test]
+    fn test_exclude_extensions_parsing() {
+        let ctx = SearchContext::new(
+            "test".to_string(),
+            false,
+            false,
+            false,
+            PathBuf::from("/tmp"),
+            ".exe, .dll ,.jpg, .png".to_string(),
+            true,
+            None,
+        )
+        .unwrap();
+        assert_eq!(ctx.exclude_extensions, vec![".exe", ".dll", ".jpg", ".png"]);
+    }
+
+    #[test]
+    fn test_exclude_extensions_empty() {
+        let ctx = SearchContext::new(
+            "test".to_string(),
+            false,
+            false,
+            false,
+            PathBuf::from("/tmp"),
+            "".to_string(),
+            true,
+            None,
+        )
+        .unwrap();
+        assert!(ctx.exclude_extensions.is_empty());
+    }
+
+    // ============================================================================
+    // Tests de is_match - Recherche simple
+    // ============================================================================
+
+    #[test]
+    fn test_is_match_case_insensitive() {
+        let ctx = SearchContext::new(
+            "Test".to_string(),
+            false,
+            false,
+            false,
+            PathBuf::from("/tmp"),
+            "".to_string(),
+            true,
+            None,
+        )
+        .unwrap();
+
+        assert!(ctx.is_match("Test"));
+        assert!(ctx.is_match("test"));
+        assert!(ctx.is_match("TEST"));
+        assert!(ctx.is_match("This is a Test"));
+        assert!(!ctx.is_match("No match here"));
+    }
+
+    #[test]
+    fn test_is_match_case_sensitive() {
+        let ctx = SearchContext::new(
+            "Test".to_string(),
+            true,
+            false,
+            false,
+            PathBuf::from("/tmp"),
+            "".to_string(),
+            true,
+            None,
+        )
+        .unwrap();
+
+        assert!(ctx.is_match("Test"));
+        assert!(ctx.is_match("This is a Test"));
+        assert!(!ctx.is_match("test"));
+        assert!(!ctx.is_match("TEST"));
+    }
+
+    #[test]
+    fn test_is_match_empty_query() {
+        let ctx = SearchContext::new(
+            "".to_string(),
+            false,
+            false,
+            false,
+            PathBuf::from("/tmp"),
+            "".to_string(),
+            true,
+            None,
+        )
+        .unwrap();
+
+        assert!(ctx.is_match("anything"));
+        assert!(ctx.is_match(""));
+    }
+
+    // ============================================================================
+    // Tests de is_match - Regex
+    // ============================================================================
+
+    #[test]
+    fn test_is_match_regex_digits() {
+        let ctx = SearchContext::new(
+            r"\d+".to_string(),
+            false,
+            true,
+            false,
+            PathBuf::from("/tmp"),
+            "".to_string(),
+            true,
+            None,
+        )
+        .unwrap();
+
+        assert!(ctx.is_match("123"));
+        assert!(ctx.is_match("file123"));
+        assert!(!ctx.is_match("abc"));
+    }
+
+    #[test]
+    fn test_is_match_regex_word_boundary() {
+        let ctx = SearchContext::new(
+            r"\btest\b".to_string(),
+            false,
+            true,
+            false,
+            PathBuf::from("/tmp"),
+            "".to_string(),
+            true,
+            None,
+        )
+        .unwrap();
+
+        assert!(ctx.is_match("test"));
+        assert!(ctx.is_match("a test file"));
+        assert!(!ctx.is_match("testing"));
+    }
+
+    #[test]
+    fn test_is_match_regex_case_sensitive() {
+        let ctx = SearchContext::new(
+            r"Test".to_string(),
+            true,
+            true,
+            false,
+            PathBuf::from("/tmp"),
+            "".to_string(),
+            true,
+            None,
+        )
+        .unwrap();
+
+        assert!(ctx.is_match("Test"));
+        assert!(!ctx.is_match("test"));
+    }
+
+    #[test]
+    fn test_is_match_regex_complex_pattern() {
+        let ctx = SearchContext::new(
+            r"(TODO|FIXME|HACK):\s*.+".to_string(),
+            false,
+            true,
+            false,
+            PathBuf::from("/tmp"),
+            "".to_string(),
+            true,
+            None,
+        )
+        .unwrap();
+
+        assert!(ctx.is_match("TODO: Fix this bug"));
+        assert!(ctx.is_match("FIXME: Refactor"));
+        assert!(!ctx.is_match("NOTE: This is fine"));
+    }
+
+    // ============================================================================
+    // Tests Edge Cases
+    // ============================================================================
+
+    #[test]
+    fn test_is_match_unicode() {
+        let ctx = SearchContext::new(
+            "café".to_string(),
+            false,
+            false,
+            false,
+            PathBuf::from("/tmp"),
+            "".to_string(),
+            true,
+            None,
+        )
+        .unwrap();
+
+        assert!(ctx.is_match("café"));
+        assert!(ctx.is_match("CAFÉ"));
     }
 
-    // Calculate relative path
-    let relative_path = path
-        .strip_prefix(&context.root_path)
-        .unwrap_or(path)
-        .to_string_lossy()
-        .to_string();
-
-    // A. Match on filename (Absolute priority & Fast)
-    // If query contains wildcards, match on name without extension
-    let match_target = if context.query.contains('*') || context.query.contains('?') {
-        // For wildcards, match on name without extension (Eclipse style)
-        path.file_stem()
-            .map(|s| s.to_string_lossy().to_string())
-            .unwrap_or_else(|| file_name.to_string())
-    } else {
-        // For normal searches, match on full name
-        file_name.to_string()
-    };
+    #[test]
+    fn test_is_match_very_long_string() {
+        let ctx = SearchContext::new(
+            "needle".to_string(),
+            false,
+            false,
+            false,
+            PathBuf::from("/tmp"),
+            "".to_string(),
+            true,
+            None,
+        )
+        .unwrap();
 
-    if context.is_match(&match_target) {
-        return Some(SearchResult {
-            file_name: file_name.to_string(),
-            file_path: path.to_string_lossy().to_string(),
-            relative_path,
-            extension: extension.clone(),
-            line_match: String::new(), // No excerpt needed
-        });
+        let haystack = "a".repeat(10000) + "needle" + &"b".repeat(10000);
+        assert!(ctx.is_match(&haystack));
     }
 
-    // If not searching content, stop here
-    if !context.search_content {
-        return None;
-    }
+    #[test]
+    fn test_exclude_extensions_normalization() {
+        let ctx = SearchContext::new(
+            "test".to_string(),
+            false,
+            false,
+            false,
+            PathBuf::from("/tmp"),
+            ".EXE, .DLL, .Jpg".to_string(),
+            true,
+            None,
+        )
+        .unwrap();
 
-    // B. Match on content (Slower, requires reading)
-    // Ignore common binaries to avoid reading arbitrary data
-    if is_likely_binary(&extension) {
-        return None;
+        assert_eq!(ctx.exclude_extensions, vec![".exe", ".dll", ".jpg"]);
     }
 
-    if let Ok(file) = File::open(path) {
-        // Use BufReader for I/O performance
-        let reader = BufReader::new(file);
-
-        // Scan line by line with an index
-        for (i, line) in reader.lines().enumerate() {
-            if let Ok(content) = line {
-                if context.is_match(&content) {
-                    // Early return: stop at first match
-                    return Some(SearchResult {
-                        file_name: file_name.to_string(),
-                        file_path: path.to_string_lossy().to_string(),
-                        relative_path,
-                        extension,
-                        line_match: format!("L{}: {}", i + 1, content.trim()),
-                    });
-                }
-            }
-            // Safety: stop reading if file is too large or no match after N lines
-            if i > 5000 {
-                break;
-            }
-        }
+    #[test]
+    fn test_is_likely_binary() {
+        assert!(is_likely_binary("exe"));
+        assert!(is_likely_binary("CLASS"));
+        assert!(!is_likely_binary("txt"));
+        assert!(!is_likely_binary("rs"));
     }
 
-    None
-}
+    #[test]
+    fn test_regex_with_anchors() {
+        let ctx = SearchContext::new(
+            r"^test$".to_string(),
+            false,
+            true,
+            false,
+            PathBuf::from("/tmp"),
+            "".to_string(),
+            true,
+            None,
+        )
+        .unwrap();
 
-/// Helper to ignore binary extensions (non-exhaustive list)
-fn is_likely_binary(ext: &str) -> bool {
-    matches!(
-        ext.to_lowercase().as_str(),
-        "exe" | "dll" | "png" | "jpg" | "pdf" | "zip" | "class" | "jar" | "ico" | "mp3" | "mp4"
-    )
-}
+        assert!(ctx.is_match("test"));
+        assert!(!ctx.is_match("test "));
+        assert!(!ctx.is_match("testing"));
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::path::PathBuf;
+    #[test]
+    fn test_multiple_spaces_in_exclude_extensions() {
+        let ctx = SearchContext::new(
+            "test".to_string(),
+            false,
+            false,
+            false,
+            PathBuf::from("/tmp"),
+            "  .exe  ,  .dll  ".to_string(),
+            true,
+            None,
+        )
+        .unwrap();
 
-    // ============================================================================
-    // Tests de SearchContext::new
-    // ============================================================================
+        assert_eq!(ctx.exclude_extensions, vec![".exe", ".dll"]);
+    }
 
     #[test]
-    fn test_search_context_creation_valid() {
+    fn test_empty_extension_in_list() {
         let ctx = SearchContext::new(
             "test".to_string(),
             false,
             false,
             false,
             PathBuf::from("/tmp"),
-            ".exe,.dll".to_string(),
+            ".exe,,.dll".to_string(),
             true,
             None,
-        );
-        assert!(ctx.is_some());
-        let ctx = ctx.unwrap();
-        assert_eq!(ctx.query, "test");
-        assert_eq!(ctx.query_lower, "test");
+        )
+        .unwrap();
+
         assert_eq!(ctx.exclude_extensions, vec![".exe", ".dll"]);
-        assert!(!ctx.case_sensitive);
-        assert!(!ctx.use_regex);
     }
 
     #[test]
-    fn test_search_context_invalid_regex() {
+    fn test_context_properties_preserved() {
         let ctx = SearchContext::new(
-            "[invalid".to_string(),
+            "MyQuery".to_string(),
+            true,
             false,
             true,
+            PathBuf::from("/custom/path"),
+            ".rs,.toml".to_string(),
+            false,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(ctx.query, "MyQuery");
+        assert_eq!(ctx.query_lower, "myquery");
+        assert!(ctx.case_sensitive);
+        assert!(!ctx.use_regex);
+        assert!(ctx.search_content);
+        assert_eq!(ctx.root_path, PathBuf::from("/custom/path"));
+        assert!(!ctx.respect_gitignore);
+    }
+
+    // ============================================================================
+    // Tests de recherche avec wildcards (style Eclipse)
+    // ============================================================================
+
+    #[test]
+    fn test_wildcard_star_suffix() {
+        let ctx = SearchContext::new(
+            "*controller".to_string(),
+            false,
+            false,
             false,
             PathBuf::from("/tmp"),
             "".to_string(),
             true,
             None,
-        );
-        assert!(ctx.is_none(), "Invalid regex should return None");
+        )
+        .unwrap();
+
+        // Should match filenames (without extension) ending with "controller"
+        assert!(ctx.is_match("UserController"));
+        assert!(ctx.is_match("TotoController"));
+        assert!(ctx.is_match("MyController"));
+        assert!(ctx.is_match("controller"));
+
+        // Should not match
+        assert!(!ctx.is_match("ControllerService"));
+        assert!(!ctx.is_match("MyService"));
     }
 
     #[test]
-    fn test_search_context_valid_regex() {
+    fn test_wildcard_star_prefix() {
         let ctx = SearchContext::new(
-            r"\d+".to_string(),
+            "User*".to_string(),
+            false,
             false,
-            true,
             false,
             PathBuf::from("/tmp"),
             "".to_string(),
             true,
             None,
-        );
-        assert!(ctx.is_some(), "Valid regex should return Some");
+        )
+        .unwrap();
+
+        assert!(ctx.is_match("UserController"));
+        assert!(ctx.is_match("UserService"));
+        assert!(ctx.is_match("User"));
+        assert!(!ctx.is_match("MyUser"));
     }
 
     #[test]
-    fn test_exclude_extensions_parsing() {
+    fn test_wildcard_star_middle() {
         let ctx = SearchContext::new(
-            "test".to_string(),
+            "User*Service".to_string(),
             false,
             false,
             false,
             PathBuf::from("/tmp"),
-            ".exe, .dll ,.jpg, .png".to_string(),
+            "".to_string(),
             true,
             None,
         )
         .unwrap();
-        assert_eq!(ctx.exclude_extensions, vec![".exe", ".dll", ".jpg", ".png"]);
+
+        assert!(ctx.is_match("UserService"));
+        assert!(ctx.is_match("UserAuthService"));
+        assert!(ctx.is_match("UserManagementService"));
+        assert!(!ctx.is_match("UserController"));
     }
 
     #[test]
-    fn test_exclude_extensions_empty() {
+    fn test_wildcard_question_mark() {
         let ctx = SearchContext::new(
-            "test".to_string(),
+            "User?".to_string(),
             false,
             false,
             false,
@@ -482,17 +3093,18 @@ mod tests {
             None,
         )
         .unwrap();
-        assert!(ctx.exclude_extensions.is_empty());
-    }
 
-    // ============================================================================
-    // Tests de is_match - Recherche simple
-    // ============================================================================
+        assert!(ctx.is_match("User1"));
+        assert!(ctx.is_match("UserA"));
+        assert!(ctx.is_match("Users"));
+        assert!(!ctx.is_match("User"));
+        assert!(!ctx.is_match("User12"));
+    }
 
     #[test]
-    fn test_is_match_case_insensitive() {
+    fn test_wildcard_multiple_stars() {
         let ctx = SearchContext::new(
-            "Test".to_string(),
+            "*User*Controller*".to_string(),
             false,
             false,
             false,
@@ -503,18 +3115,36 @@ mod tests {
         )
         .unwrap();
 
-        assert!(ctx.is_match("Test"));
-        assert!(ctx.is_match("test"));
-        assert!(ctx.is_match("TEST"));
-        assert!(ctx.is_match("This is a Test"));
-        assert!(!ctx.is_match("No match here"));
+        assert!(ctx.is_match("MyUserController"));
+        assert!(ctx.is_match("AdminUserControllerImpl"));
+        assert!(ctx.is_match("UserController"));
+        assert!(!ctx.is_match("UserService"));
     }
 
     #[test]
-    fn test_is_match_case_sensitive() {
+    fn test_wildcard_case_insensitive() {
         let ctx = SearchContext::new(
-            "Test".to_string(),
+            "*CONTROLLER".to_string(),
+            false, // case insensitive
+            false,
+            false,
+            PathBuf::from("/tmp"),
+            "".to_string(),
             true,
+            None,
+        )
+        .unwrap();
+
+        assert!(ctx.is_match("UserController"));
+        assert!(ctx.is_match("usercontroller"));
+        assert!(ctx.is_match("MyController"));
+    }
+
+    #[test]
+    fn test_wildcard_with_special_chars() {
+        let ctx = SearchContext::new(
+            "User*.java".to_string(),
+            false,
             false,
             false,
             PathBuf::from("/tmp"),
@@ -524,16 +3154,16 @@ mod tests {
         )
         .unwrap();
 
-        assert!(ctx.is_match("Test"));
-        assert!(ctx.is_match("This is a Test"));
-        assert!(!ctx.is_match("test"));
-        assert!(!ctx.is_match("TEST"));
+        // The dot in .java should be escaped
+        assert!(ctx.is_match("UserController.java"));
+        assert!(ctx.is_match("User.java"));
+        assert!(!ctx.is_match("UserControllerXjava")); // The dot is literal
     }
 
     #[test]
-    fn test_is_match_empty_query() {
+    fn test_no_wildcard_still_works() {
         let ctx = SearchContext::new(
-            "".to_string(),
+            "Controller".to_string(),
             false,
             false,
             false,
@@ -544,20 +3174,26 @@ mod tests {
         )
         .unwrap();
 
-        assert!(ctx.is_match("anything"));
-        assert!(ctx.is_match(""));
+        // Without wildcard, should work as before (contains)
+        assert!(ctx.is_match("UserController.java"));
+        assert!(ctx.is_match("Controller"));
+        assert!(ctx.is_match("MyControllerService"));
     }
 
     // ============================================================================
-    // Tests de is_match - Regex
+    // Tests de recherche avec wildcards (style Eclipse)
+    // ============================================================================
+
+    // ============================================================================
+    // Tests de CamelCase Matching
     // ============================================================================
 
     #[test]
-    fn test_is_match_regex_digits() {
+    fn test_camelcase_basic() {
         let ctx = SearchContext::new(
-            r"\d+".to_string(),
+            "UC".to_string(),
+            false,
             false,
-            true,
             false,
             PathBuf::from("/tmp"),
             "".to_string(),
@@ -566,17 +3202,19 @@ mod tests {
         )
         .unwrap();
 
-        assert!(ctx.is_match("123"));
-        assert!(ctx.is_match("file123"));
-        assert!(!ctx.is_match("abc"));
+        assert!(ctx.is_match("UserController"));
+        assert!(ctx.is_match("UsersController"));
+        assert!(ctx.is_match("UpdateController"));
+        assert!(!ctx.is_match("usercontroller"));
+        assert!(!ctx.is_match("Usercontroller"));
     }
 
     #[test]
-    fn test_is_match_regex_word_boundary() {
+    fn test_camelcase_three_letters() {
         let ctx = SearchContext::new(
-            r"\btest\b".to_string(),
+            "UCS".to_string(),
+            false,
             false,
-            true,
             false,
             PathBuf::from("/tmp"),
             "".to_string(),
@@ -585,17 +3223,18 @@ mod tests {
         )
         .unwrap();
 
-        assert!(ctx.is_match("test"));
-        assert!(ctx.is_match("a test file"));
-        assert!(!ctx.is_match("testing"));
+        assert!(ctx.is_match("UserControllerService"));
+        assert!(ctx.is_match("UpdateCustomerService"));
+        assert!(!ctx.is_match("UserController"));
+        assert!(!ctx.is_match("UserService"));
     }
 
     #[test]
-    fn test_is_match_regex_case_sensitive() {
+    fn test_camelcase_with_numbers() {
         let ctx = SearchContext::new(
-            r"Test".to_string(),
-            true,
-            true,
+            "U2C".to_string(),
+            false,
+            false,
             false,
             PathBuf::from("/tmp"),
             "".to_string(),
@@ -604,16 +3243,16 @@ mod tests {
         )
         .unwrap();
 
-        assert!(ctx.is_match("Test"));
-        assert!(!ctx.is_match("test"));
+        assert!(ctx.is_match("User2Controller"));
+        assert!(!ctx.is_match("UserController"));
     }
 
     #[test]
-    fn test_is_match_regex_complex_pattern() {
+    fn test_camelcase_fallback_to_normal() {
         let ctx = SearchContext::new(
-            r"(TODO|FIXME|HACK):\s*.+".to_string(),
+            "UC".to_string(),
+            false,
             false,
-            true,
             false,
             PathBuf::from("/tmp"),
             "".to_string(),
@@ -622,19 +3261,15 @@ mod tests {
         )
         .unwrap();
 
-        assert!(ctx.is_match("TODO: Fix this bug"));
-        assert!(ctx.is_match("FIXME: Refactor"));
-        assert!(!ctx.is_match("NOTE: This is fine"));
+        // If no CamelCase match, should fallback to normal search
+        assert!(ctx.is_match("ABUC"));
+        assert!(ctx.is_match("testUCvalue"));
     }
 
-    // ============================================================================
-    // Tests Edge Cases
-    // ============================================================================
-
     #[test]
-    fn test_is_match_unicode() {
+    fn test_not_camelcase_query() {
         let ctx = SearchContext::new(
-            "café".to_string(),
+            "User".to_string(),
             false,
             false,
             false,
@@ -645,14 +3280,17 @@ mod tests {
         )
         .unwrap();
 
-        assert!(ctx.is_match("café"));
-        assert!(ctx.is_match("CAFÉ"));
+        // "User" is not a CamelCase pattern (not all uppercase)
+        // Should perform normal search
+        assert!(ctx.is_match("UserController"));
+        assert!(ctx.is_match("user"));
+        assert!(ctx.is_match("MyUser"));
     }
 
     #[test]
-    fn test_is_match_very_long_string() {
+    fn test_camelcase_single_letter() {
         let ctx = SearchContext::new(
-            "needle".to_string(),
+            "U".to_string(),
             false,
             false,
             false,
@@ -663,41 +3301,37 @@ mod tests {
         )
         .unwrap();
 
-        let haystack = "a".repeat(10000) + "needle" + &"b".repeat(10000);
-        assert!(ctx.is_match(&haystack));
+        // A single letter is not a CamelCase pattern
+        // Should perform normal search
+        assert!(ctx.is_match("UserController"));
+        assert!(ctx.is_match("user"));
     }
 
     #[test]
-    fn test_exclude_extensions_normalization() {
+    fn test_camelcase_long_pattern() {
         let ctx = SearchContext::new(
-            "test".to_string(),
+            "UACS".to_string(),
             false,
             false,
             false,
             PathBuf::from("/tmp"),
-            ".EXE, .DLL, .Jpg".to_string(),
+            "".to_string(),
             true,
             None,
         )
         .unwrap();
 
-        assert_eq!(ctx.exclude_extensions, vec![".exe", ".dll", ".jpg"]);
-    }
-
-    #[test]
-    fn test_is_likely_binary() {
-        assert!(is_likely_binary("exe"));
-        assert!(is_likely_binary("CLASS"));
-        assert!(!is_likely_binary("txt"));
-        assert!(!is_likely_binary("rs"));
+        assert!(ctx.is_match("UserAuthenticationControllerService"));
+        assert!(ctx.is_match("UpdateAccountCustomerService"));
+        assert!(!ctx.is_match("UserController"));
     }
 
     #[test]
-    fn test_regex_with_anchors() {
+    fn test_fuzzy_name_score() {
         let ctx = SearchContext::new(
-            r"^test$".to_string(),
+            "usrctl".to_string(),
+            false,
             false,
-            true,
             false,
             PathBuf::from("/tmp"),
             "".to_string(),
@@ -705,381 +3339,1098 @@ mod tests {
             None,
         )
         .unwrap();
+        assert_eq!(ctx.name_score("UserController.rs"), None);
 
-        assert!(ctx.is_match("test"));
-        assert!(!ctx.is_match("test "));
-        assert!(!ctx.is_match("testing"));
+        let ctx = ctx.with_options(SearchOptions {
+            fuzzy: true,
+            ..SearchOptions::default()
+        });
+        assert!(ctx.name_score("UserController.rs").unwrap() > 0);
+        assert_eq!(ctx.name_score("UserService.rs"), None);
     }
 
     #[test]
-    fn test_multiple_spaces_in_exclude_extensions() {
+    fn test_name_ranges() {
         let ctx = SearchContext::new(
-            "test".to_string(),
+            "report".to_string(),
             false,
             false,
             false,
             PathBuf::from("/tmp"),
-            "  .exe  ,  .dll  ".to_string(),
+            "".to_string(),
             true,
             None,
         )
         .unwrap();
+        assert_eq!(ctx.name_ranges("Q3_Report_report.pdf"), vec![3..9, 10..16]);
 
-        assert_eq!(ctx.exclude_extensions, vec![".exe", ".dll"]);
-    }
-
-    #[test]
-    fn test_empty_extension_in_list() {
         let ctx = SearchContext::new(
-            "test".to_string(),
+            "usrctl".to_string(),
             false,
             false,
             false,
             PathBuf::from("/tmp"),
-            ".exe,,.dll".to_string(),
+            "".to_string(),
             true,
             None,
         )
-        .unwrap();
+        .unwrap()
+        .with_options(SearchOptions {
+            fuzzy: true,
+            ..SearchOptions::default()
+        });
+        assert_eq!(
+            ctx.name_ranges("UserController.rs"),
+            vec![0..2, 3..5, 7..8, 10..11]
+        );
+    }
 
-        assert_eq!(ctx.exclude_extensions, vec![".exe", ".dll"]);
+    #[test]
+    fn test_search_token_cancel_is_shared_by_clones() {
+        let token = SearchToken::default();
+        let worker = token.clone();
+        assert!(!worker.is_cancelled());
+
+        token.cancel();
+        assert!(worker.is_cancelled());
+        assert!(!SearchToken::default().is_cancelled());
     }
 
     #[test]
-    fn test_context_properties_preserved() {
-        let ctx = SearchContext::new(
-            "MyQuery".to_string(),
-            true,
-            false,
-            true,
-            PathBuf::from("/custom/path"),
-            ".rs,.toml".to_string(),
-            false,
-            None,
-        )
-        .unwrap();
+    fn test_search_token_pause_parks_workers_until_resumed() {
+        let token = SearchToken::default();
+        token.pause();
+        let worker = token.clone();
+        let parked = std::thread::spawn(move || {
+            worker.wait_while_paused();
+            worker.is_paused()
+        });
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(!parked.is_finished());
+        token.resume();
+        assert!(!parked.join().unwrap());
 
-        assert_eq!(ctx.query, "MyQuery");
-        assert_eq!(ctx.query_lower, "myquery");
-        assert!(ctx.case_sensitive);
-        assert!(!ctx.use_regex);
-        assert!(ctx.search_content);
-        assert_eq!(ctx.root_path, PathBuf::from("/custom/path"));
-        assert!(!ctx.respect_gitignore);
+        // Cancelling a paused scan releases its workers.
+        token.pause();
+        let worker = token.clone();
+        let parked = std::thread::spawn(move || worker.wait_while_paused());
+        token.cancel();
+        parked.join().unwrap();
+        assert!(token.is_paused());
     }
 
-    // ============================================================================
-    // Tests de recherche avec wildcards (style Eclipse)
-    // ============================================================================
+    #[test]
+    fn test_sort_results_recent_first() {
+        let result = |name: &str, secs: Option<u64>| SearchResult {
+            modified: secs.map(|s| SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(s)),
+            ..SearchResult::for_path(&Path::new("/tmp").join(name), Path::new("/tmp"))
+        };
+        let mut results = vec![
+            result("old", Some(10)),
+            result("unknown", None),
+            result("new", Some(30)),
+            result("mid", Some(20)),
+        ];
+
+        sort_results(&mut results, ResultOrder::RecentFirst);
+
+        let names: Vec<_> = results.iter().map(|r| r.file_name.as_str()).collect();
+        assert_eq!(names, vec!["new", "mid", "old", "unknown"]);
+    }
 
     #[test]
-    fn test_wildcard_star_suffix() {
-        let ctx = SearchContext::new(
-            "*controller".to_string(),
-            false,
+    fn test_sort_results_names_before_content() {
+        let result = |name: &str, kind: MatchKind, secs: u64| SearchResult {
+            modified: Some(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs)),
+            match_kind: kind,
+            ..SearchResult::for_path(&Path::new("/tmp").join(name), Path::new("/tmp"))
+        };
+        let mut results = vec![
+            result("body-a", MatchKind::Content, 30),
+            result("name-a", MatchKind::Name, 10),
+            result("body-b", MatchKind::Content, 20),
+            result("name-b", MatchKind::Name, 20),
+        ];
+        let names = |results: &[SearchResult]| -> Vec<String> {
+            results.iter().map(|r| r.file_name.clone()).collect()
+        };
+
+        sort_results(&mut results, ResultOrder::Unsorted);
+        assert_eq!(names(&results), ["name-a", "name-b", "body-a", "body-b"]);
+        sort_results(&mut results, ResultOrder::RecentFirst);
+        assert_eq!(names(&results), ["name-b", "name-a", "body-a", "body-b"]);
+    }
+
+    #[test]
+    fn test_sort_results_shallow_first() {
+        let result = |relative: &str| {
+            SearchResult::for_path(&Path::new("/tmp").join(relative), Path::new("/tmp"))
+        };
+        let mut results = vec![
+            result("nested/vendor/lib/config.rs"),
+            result("src/main.rs"),
+            result("Cargo.toml"),
+            result("src/config.rs"),
+            result("build.rs"),
+        ];
+
+        sort_results(&mut results, ResultOrder::ShallowFirst);
+
+        let paths: Vec<_> = results.iter().map(|r| r.relative_path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "build.rs",
+                "Cargo.toml",
+                "src/config.rs",
+                "src/main.rs",
+                "nested/vendor/lib/config.rs"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_time_filter_period_start() {
+        // Thursday 2024-03-14, 15:30 local time.
+        let now = Local.with_ymd_and_hms(2024, 3, 14, 15, 30, 0).unwrap();
+        let start = |filter: TimeFilter| DateTime::<Local>::from(filter.since(now).unwrap());
+
+        assert!(TimeFilter::Any.since(now).is_none());
+        assert_eq!(
+            start(TimeFilter::Today),
+            Local.with_ymd_and_hms(2024, 3, 14, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            start(TimeFilter::ThisWeek),
+            Local.with_ymd_and_hms(2024, 3, 11, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            start(TimeFilter::ThisMonth),
+            Local.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap()
+        );
+        assert_eq!(TimeFilter::from_name("week"), TimeFilter::ThisWeek);
+    }
+
+    #[test]
+    fn test_modified_since_filters_older_files() {
+        let root = std::env::temp_dir().join("quick-findr-engine-modified-since");
+        std::fs::create_dir_all(&root).unwrap();
+        let file = root.join("notes.txt");
+        std::fs::write(&file, "x").unwrap();
+
+        let context = |since: SystemTime| {
+            SearchContext::new(
+                "notes".to_string(),
+                false,
+                false,
+                false,
+                root.clone(),
+                String::new(),
+                true,
+                None,
+            )
+            .unwrap()
+            .with_options(SearchOptions {
+                modified_since: Some(since),
+                ..Default::default()
+            })
+        };
+        let hour = std::time::Duration::from_secs(3600);
+
+        assert!(process_file(&file, &context(SystemTime::now() - hour)).is_some());
+        assert!(process_file(&file, &context(SystemTime::now() + hour)).is_none());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_content_match_keeps_every_line() {
+        let root = std::env::temp_dir().join("quick-findr-engine-all-matches");
+        std::fs::create_dir_all(&root).unwrap();
+        let file = root.join("notes.txt");
+        std::fs::write(&file, "TODO: first\nnothing here\n  todo second  \n").unwrap();
+
+        let context = SearchContext::new(
+            "todo".to_string(),
             false,
             false,
-            PathBuf::from("/tmp"),
-            "".to_string(),
+            true,
+            root.clone(),
+            String::new(),
             true,
             None,
         )
         .unwrap();
 
-        // Should match filenames (without extension) ending with "controller"
-        assert!(ctx.is_match("UserController"));
-        assert!(ctx.is_match("TotoController"));
-        assert!(ctx.is_match("MyController"));
-        assert!(ctx.is_match("controller"));
+        let result = process_file(&file, &context).unwrap();
+        assert_eq!(result.line_match, "L1: TODO: first");
+        assert_eq!(
+            result.matches,
+            vec![
+                LineMatch {
+                    line_number: 1,
+                    column: 1,
+                    text: "TODO: first".to_string(),
+                    ranges: vec![Range { start: 0, end: 4 }],
+                },
+                LineMatch {
+                    line_number: 3,
+                    column: 3,
+                    text: "todo second".to_string(),
+                    ranges: vec![Range { start: 0, end: 4 }],
+                },
+            ]
+        );
+        let _ = std::fs::remove_dir_all(&root);
+    }
 
-        // Should not match
-        assert!(!ctx.is_match("ControllerService"));
-        assert!(!ctx.is_match("MyService"));
+    #[test]
+    fn test_split_highlights() {
+        assert_eq!(
+            split_highlights("let total = total + 1;", &[4..9, 12..17]),
+            vec![
+                ("let ", false),
+                ("total", true),
+                (" = ", false),
+                ("total", true),
+                (" + 1;", false),
+            ]
+        );
+        assert_eq!(split_highlights("abc", &[]), vec![("abc", false)]);
+        // Out-of-bounds or overlapping ranges are skipped.
+        assert_eq!(
+            split_highlights("abc", &[0..2, 1..3, 2..9]),
+            vec![("ab", true), ("c", false)]
+        );
     }
 
     #[test]
-    fn test_wildcard_star_prefix() {
-        let ctx = SearchContext::new(
-            "User*".to_string(),
-            false,
-            false,
-            false,
-            PathBuf::from("/tmp"),
-            "".to_string(),
-            true,
-            None,
+    fn test_language_filter_restricts_extensions() {
+        let root = std::env::temp_dir().join("quick-findr-engine-language");
+        std::fs::create_dir_all(&root).unwrap();
+        let rust = root.join("main.rs");
+        let script = root.join("main.py");
+        std::fs::write(&rust, "").unwrap();
+        std::fs::write(&script, "").unwrap();
+
+        let context = |language: Option<&str>| {
+            SearchContext::new(
+                "main".to_string(),
+                false,
+                false,
+                false,
+                root.clone(),
+                String::new(),
+                true,
+                language.map(str::to_string),
+            )
+            .unwrap()
+        };
+
+        assert!(process_file(&rust, &context(Some("rust"))).is_some());
+        assert!(process_file(&script, &context(Some("rust"))).is_none());
+        assert!(process_file(&script, &context(Some("python"))).is_some());
+        // Unknown languages do not restrict the scan.
+        assert!(process_file(&script, &context(Some("cobol"))).is_some());
+        assert!(process_file(&script, &context(None)).is_some());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_include_globs() {
+        let root = std::env::temp_dir().join("quick-findr-engine-include");
+        std::fs::create_dir_all(root.join("src/ui")).unwrap();
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        let source = root.join("src/ui/view.rs");
+        let doc = root.join("docs/view.md");
+        std::fs::write(&source, "").unwrap();
+        std::fs::write(&doc, "").unwrap();
+
+        let context = |patterns: &str| {
+            SearchContext::new(
+                "view".to_string(),
+                false,
+                false,
+                false,
+                root.clone(),
+                String::new(),
+                true,
+                None,
+            )
+            .unwrap()
+            .with_include_globs(patterns)
+            .unwrap()
+        };
+
+        assert!(process_file(&source, &context("src/**/*.rs")).is_some());
+        assert!(process_file(&doc, &context("src/**/*.rs")).is_none());
+        assert!(process_file(&doc, &context("SRC/**/*.rs, *.md")).is_some());
+        assert!(process_file(&doc, &context(" , ")).is_some());
+        assert!(build_globset("src/[a").is_err());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_overrides_exclude_paths() {
+        let root = std::env::temp_dir().join("quick-findr-engine-overrides");
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["src", "build/out", "node_modules/pkg", "docs", "docs.cste"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in [
+            "src/main.rs",
+            "src/rebuild.rs",
+            "src/Legacy.CS",
+            "build/out/app.exe",
+            "node_modules/pkg/index.js",
+            "docs/guide.md",
+            "docs/build.log",
+            "docs.cste/notes.txt",
+        ] {
+            std::fs::write(root.join(file), "").unwrap();
+        }
+
+        let overrides = build_overrides(
+            &root,
+            &ignored_dirs(),
+            &[".log".to_string(), ".cs".to_string()],
+            "**/build/**, DOCS/*.md",
         )
         .unwrap();
+        let mut found: Vec<String> = WalkBuilder::new(&root)
+            .overrides(overrides)
+            .build()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().is_file())
+            .map(|entry| {
+                entry
+                    .path()
+                    .strip_prefix(&root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        found.sort();
+
+        // No substring false positive on "rebuild.rs" or "docs.cste/".
+        assert_eq!(
+            found,
+            vec!["docs.cste/notes.txt", "src/main.rs", "src/rebuild.rs"]
+        );
+        assert!(build_overrides(&root, &ignored_dirs(), &[], "src/[a").is_err());
+
+        // Same exclusions on the paths of a file-name index.
+        let overrides = build_overrides(&root, &ignored_dirs(), &[], "dist/").unwrap();
+        assert!(overrides_exclude(
+            &overrides,
+            &root,
+            &root.join("dist/a/app.js")
+        ));
+        assert!(overrides_exclude(
+            &overrides,
+            &root,
+            &root.join("node_modules/pkg/index.js")
+        ));
+        assert!(!overrides_exclude(
+            &overrides,
+            &root,
+            &root.join("src/dist.rs")
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_size_filter() {
+        let filter = SizeFilter::parse(">10MB, <=1gb").unwrap();
+        assert_eq!(filter.min, Some(10 * 1024 * 1024 + 1));
+        assert_eq!(filter.max, Some(1024 * 1024 * 1024));
+        assert!(!filter.contains(10 * 1024 * 1024));
+        assert!(filter.contains(20 * 1024 * 1024));
+
+        let filter = SizeFilter::parse("<1.5KB").unwrap();
+        assert!(filter.contains(0));
+        assert!(filter.contains(1535));
+        assert!(!filter.contains(1536));
+
+        assert!(SizeFilter::parse("").unwrap().is_empty());
+        assert!(SizeFilter::parse("10MB").is_err());
+        assert!(SizeFilter::parse(">10XB").is_err());
+    }
+
+    #[test]
+    fn test_date_range_parse() {
+        let range = DateRange::parse("2024-03-01", "2024-03-31").unwrap();
+        let local =
+            |y, m, d| -> SystemTime { Local.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap().into() };
+        assert_eq!(range.from, Some(local(2024, 3, 1)));
+        // The end date is inclusive.
+        assert_eq!(range.until, Some(local(2024, 4, 1)));
+
+        assert_eq!(DateRange::parse("", "").unwrap(), DateRange::default());
+        assert!(DateRange::parse("03/01/2024", "").is_err());
+        assert!(DateRange::parse("2024-04-01", "2024-03-01").is_err());
+    }
+
+    #[test]
+    fn test_modified_until_filters_newer_files() {
+        let root = std::env::temp_dir().join("quick-findr-engine-modified-until");
+        std::fs::create_dir_all(&root).unwrap();
+        let file = root.join("notes.txt");
+        std::fs::write(&file, "x").unwrap();
 
-        assert!(ctx.is_match("UserController"));
-        assert!(ctx.is_match("UserService"));
-        assert!(ctx.is_match("User"));
-        assert!(!ctx.is_match("MyUser"));
+        let context = |until: SystemTime| {
+            SearchContext::new(
+                "notes".to_string(),
+                false,
+                false,
+                false,
+                root.clone(),
+                String::new(),
+                true,
+                None,
+            )
+            .unwrap()
+            .with_options(SearchOptions {
+                modified_until: Some(until),
+                ..Default::default()
+            })
+        };
+        let hour = std::time::Duration::from_secs(3600);
+
+        assert!(process_file(&file, &context(SystemTime::now() + hour)).is_some());
+        assert!(process_file(&file, &context(SystemTime::now() - hour)).is_none());
+        let _ = std::fs::remove_dir_all(&root);
     }
 
     #[test]
-    fn test_wildcard_star_middle() {
+    fn test_query_qualifiers_filter_files() {
+        let root = std::env::temp_dir().join("quick-findr-engine-qualifiers");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(root.join("docs/invoice.pdf"), "x").unwrap();
+        std::fs::write(root.join("docs/invoice.txt"), "x").unwrap();
+        std::fs::write(root.join("invoice.pdf"), "x").unwrap();
+
         let ctx = SearchContext::new(
-            "User*Service".to_string(),
+            "invoice ext:pdf path:docs".to_string(),
             false,
             false,
             false,
-            PathBuf::from("/tmp"),
-            "".to_string(),
+            root.clone(),
+            String::new(),
             true,
             None,
         )
         .unwrap();
+        assert_eq!(ctx.query, "invoice");
 
-        assert!(ctx.is_match("UserService"));
-        assert!(ctx.is_match("UserAuthService"));
-        assert!(ctx.is_match("UserManagementService"));
-        assert!(!ctx.is_match("UserController"));
+        assert!(process_file(&root.join("docs/invoice.pdf"), &ctx).is_some());
+        assert!(process_file(&root.join("docs/invoice.txt"), &ctx).is_none());
+        assert!(process_file(&root.join("invoice.pdf"), &ctx).is_none());
+        let _ = std::fs::remove_dir_all(&root);
     }
 
     #[test]
-    fn test_wildcard_question_mark() {
+    fn test_is_match_boolean_query() {
         let ctx = SearchContext::new(
-            "User?".to_string(),
+            "controller OR service -Test".to_string(),
             false,
             false,
             false,
-            PathBuf::from("/tmp"),
-            "".to_string(),
+            PathBuf::from("."),
+            String::new(),
             true,
             None,
         )
         .unwrap();
 
-        assert!(ctx.is_match("User1"));
-        assert!(ctx.is_match("UserA"));
-        assert!(ctx.is_match("Users"));
-        assert!(!ctx.is_match("User"));
-        assert!(!ctx.is_match("User12"));
+        assert!(ctx.is_match("UserController.java"));
+        assert!(ctx.is_match("UserService.java"));
+        assert!(!ctx.is_match("UserServiceTest.java"));
+        assert!(!ctx.is_match("User.java"));
+        // Only the searched terms are highlighted.
+        assert_eq!(ctx.match_ranges("a service test"), vec![2..9]);
     }
 
     #[test]
-    fn test_wildcard_multiple_stars() {
-        let ctx = SearchContext::new(
-            "*User*Controller*".to_string(),
-            false,
-            false,
-            false,
-            PathBuf::from("/tmp"),
-            "".to_string(),
-            true,
-            None,
-        )
-        .unwrap();
+    fn test_whole_word() {
+        let context = |query: &str, use_regex: bool| {
+            SearchContext::new(
+                query.to_string(),
+                false,
+                use_regex,
+                false,
+                PathBuf::from("."),
+                String::new(),
+                true,
+                None,
+            )
+            .unwrap()
+            .with_options(SearchOptions {
+                whole_word: true,
+                ..Default::default()
+            })
+        };
 
-        assert!(ctx.is_match("MyUserController"));
-        assert!(ctx.is_match("AdminUserControllerImpl"));
-        assert!(ctx.is_match("UserController"));
-        assert!(!ctx.is_match("UserService"));
+        let ctx = context("test", false);
+        assert!(ctx.is_match("run the Test suite"));
+        assert!(ctx.is_match("test_data/test"));
+        assert!(!ctx.is_match("latest contest"));
+        assert_eq!(ctx.match_ranges("contest test"), vec![8..12]);
+
+        let ctx = context("te.t", true);
+        assert!(ctx.is_match("a text"));
+        assert!(!ctx.is_match("texts"));
+
+        // Anchored wildcard patterns are left alone.
+        assert!(context("*.rs", false).is_match("main.rs"));
     }
 
     #[test]
-    fn test_wildcard_case_insensitive() {
-        let ctx = SearchContext::new(
-            "*CONTROLLER".to_string(),
-            false, // case insensitive
-            false,
-            false,
-            PathBuf::from("/tmp"),
-            "".to_string(),
-            true,
-            None,
-        )
-        .unwrap();
+    fn test_ignore_accents() {
+        let context = |query: &str| {
+            SearchContext::new(
+                query.to_string(),
+                false,
+                false,
+                false,
+                PathBuf::from("."),
+                String::new(),
+                true,
+                None,
+            )
+            .unwrap()
+            .with_options(SearchOptions {
+                ignore_accents: true,
+                ..Default::default()
+            })
+        };
 
-        assert!(ctx.is_match("UserController"));
-        assert!(ctx.is_match("usercontroller"));
-        assert!(ctx.is_match("MyController"));
+        let ctx = context("cafe");
+        assert!(ctx.is_match("café_menu.txt"));
+        assert!(ctx.is_match("CAFÉ"));
+        assert_eq!(ctx.match_ranges("le café"), vec![3..8]);
+        assert!(context("crème").is_match("creme_brulee.md"));
+        assert!(!context("cafe").is_match("caffe"));
     }
 
     #[test]
-    fn test_wildcard_with_special_chars() {
+    fn test_initials_match() {
         let ctx = SearchContext::new(
-            "User*.java".to_string(),
+            "uc".to_string(),
             false,
             false,
             false,
-            PathBuf::from("/tmp"),
-            "".to_string(),
+            PathBuf::from("."),
+            String::new(),
             true,
             None,
         )
         .unwrap();
 
-        // The dot in .java should be escaped
-        assert!(ctx.is_match("UserController.java"));
-        assert!(ctx.is_match("User.java"));
-        assert!(!ctx.is_match("UserControllerXjava")); // The dot is literal
+        assert!(ctx.name_score("user_controller.rs").is_some());
+        assert!(ctx.name_score("user-auth-controller.ts").is_some());
+        assert!(ctx.name_score("UserController.java").is_some());
+        assert!(ctx.name_score("sauce.txt").is_some()); // Plain substring
+        assert!(ctx.name_score("user_service.rs").is_none());
+        assert!(ctx.name_score("usercontroller.rs").is_none());
+        // File names only: a content line needs the literal text.
+        assert!(!ctx.is_match("use_case: true"));
+
+        assert!(initials_match("u2c", "user_2_controller"));
+        assert!(!is_initials_query("u"));
+        assert!(!is_initials_query("UC"));
     }
 
     #[test]
-    fn test_no_wildcard_still_works() {
+    fn test_placeholder_attributes() {
+        // OneDrive "online-only" file: recall on data access, sparse, pinned state unset.
+        assert!(is_placeholder_attributes(0x0040_0220));
+        assert!(is_placeholder_attributes(FILE_ATTRIBUTE_OFFLINE));
+        // Archive bit and sparse file: content on disk.
+        assert!(!is_placeholder_attributes(0x220));
+    }
+
+    #[test]
+    fn test_max_content_size_skips_large_files() {
+        let root = std::env::temp_dir().join("quick-findr-engine-content-size");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("small.txt"), "needle").unwrap();
+        std::fs::write(
+            root.join("large.txt"),
+            format!("needle{}", " ".repeat(2048)),
+        )
+        .unwrap();
+
         let ctx = SearchContext::new(
-            "Controller".to_string(),
-            false,
+            "needle".to_string(),
             false,
             false,
-            PathBuf::from("/tmp"),
-            "".to_string(),
+            true,
+            root.clone(),
+            String::new(),
             true,
             None,
         )
-        .unwrap();
+        .unwrap()
+        .with_options(SearchOptions {
+            max_content_size: Some(1024),
+            ..Default::default()
+        });
 
-        // Without wildcard, should work as before (contains)
-        assert!(ctx.is_match("UserController.java"));
-        assert!(ctx.is_match("Controller"));
-        assert!(ctx.is_match("MyControllerService"));
+        assert!(process_file(&root.join("small.txt"), &ctx).is_some());
+        assert!(process_file(&root.join("large.txt"), &ctx).is_none());
+        assert_eq!(ctx.content_stats.skipped_large.load(Ordering::Relaxed), 1);
+        assert_eq!(parse_megabytes(" 0,5 "), Ok(Some(512 * 1024)));
+        assert!(parse_megabytes("-1").is_err());
     }
 
-    // ============================================================================
-    // Tests de recherche avec wildcards (style Eclipse)
-    // ============================================================================
+    #[test]
+    fn test_parse_depth() {
+        assert_eq!(parse_depth(" "), Ok(None));
+        assert_eq!(parse_depth("2"), Ok(Some(2)));
+        assert!(parse_depth("0").is_err());
+        assert!(parse_depth("deep").is_err());
+    }
 
-    // ============================================================================
-    // Tests de CamelCase Matching
-    // ============================================================================
+    #[test]
+    fn test_max_content_lines_counts_truncated_files() {
+        let root = std::env::temp_dir().join("quick-findr-engine-content-lines");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("log.txt"), "a\nb\nc\nneedle\n").unwrap();
+
+        let context = |max_content_lines| {
+            SearchContext::new(
+                "needle".to_string(),
+                false,
+                false,
+                true,
+                root.clone(),
+                String::new(),
+                true,
+                None,
+            )
+            .unwrap()
+            .with_options(SearchOptions {
+                max_content_lines,
+                ..Default::default()
+            })
+        };
+
+        let ctx = context(Some(3));
+        assert!(process_file(&root.join("log.txt"), &ctx).is_none());
+        assert_eq!(ctx.content_stats.truncated.load(Ordering::Relaxed), 1);
+
+        let ctx = context(Some(4));
+        assert!(process_file(&root.join("log.txt"), &ctx).is_some());
+        assert_eq!(ctx.content_stats.truncated.load(Ordering::Relaxed), 0);
+        assert!(process_file(&root.join("log.txt"), &context(None)).is_some());
+        let _ = std::fs::remove_dir_all(&root);
+    }
 
     #[test]
-    fn test_camelcase_basic() {
+    fn test_content_search_decodes_legacy_encodings() {
+        let root = std::env::temp_dir().join("quick-findr-engine-latin1");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        // "menu\r\nCrème brûlée\r\n" in Latin-1.
+        std::fs::write(root.join("menu.txt"), b"menu\r\nCr\xe8me br\xfbl\xe9e\r\n").unwrap();
+
         let ctx = SearchContext::new(
-            "UC".to_string(),
-            false,
+            "brûlée".to_string(),
             false,
             false,
-            PathBuf::from("/tmp"),
-            "".to_string(),
+            true,
+            root.clone(),
+            String::new(),
             true,
             None,
         )
         .unwrap();
 
-        assert!(ctx.is_match("UserController"));
-        assert!(ctx.is_match("UsersController"));
-        assert!(ctx.is_match("UpdateController"));
-        assert!(!ctx.is_match("usercontroller"));
-        assert!(!ctx.is_match("Usercontroller"));
+        let result = process_file(&root.join("menu.txt"), &ctx).unwrap();
+        assert_eq!(result.matches[0].line_number, 2);
+        assert_eq!(result.matches[0].text, "Crème brûlée");
+        let _ = std::fs::remove_dir_all(&root);
     }
 
     #[test]
-    fn test_camelcase_three_letters() {
+    fn test_content_search_reads_utf16_with_bom() {
+        let root = std::env::temp_dir().join("quick-findr-engine-utf16");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        let utf16 = |bom: [u8; 2], little_endian: bool| {
+            let mut bytes = bom.to_vec();
+            for unit in "Transcript\r\nPS> Get-Needle\r\n".encode_utf16() {
+                bytes.extend(if little_endian {
+                    unit.to_le_bytes()
+                } else {
+                    unit.to_be_bytes()
+                });
+            }
+            bytes
+        };
+        std::fs::write(root.join("le.txt"), utf16([0xFF, 0xFE], true)).unwrap();
+        std::fs::write(root.join("be.txt"), utf16([0xFE, 0xFF], false)).unwrap();
+
         let ctx = SearchContext::new(
-            "UCS".to_string(),
-            false,
+            "get-needle".to_string(),
             false,
             false,
-            PathBuf::from("/tmp"),
-            "".to_string(),
+            true,
+            root.clone(),
+            String::new(),
             true,
             None,
         )
         .unwrap();
 
-        assert!(ctx.is_match("UserControllerService"));
-        assert!(ctx.is_match("UpdateCustomerService"));
-        assert!(!ctx.is_match("UserController"));
-        assert!(!ctx.is_match("UserService"));
+        for name in ["le.txt", "be.txt"] {
+            let result = process_file(&root.join(name), &ctx).unwrap();
+            assert_eq!(result.matches[0].line_number, 2);
+            assert_eq!(result.matches[0].text, "PS> Get-Needle");
+        }
+        let _ = std::fs::remove_dir_all(&root);
     }
 
     #[test]
-    fn test_camelcase_with_numbers() {
-        let ctx = SearchContext::new(
-            "U2C".to_string(),
-            false,
-            false,
-            false,
-            PathBuf::from("/tmp"),
-            "".to_string(),
-            true,
-            None,
-        )
-        .unwrap();
+    fn test_find_matches_in_buffer() {
+        let context = |query: &str, use_regex: bool| {
+            SearchContext::new(
+                query.to_string(),
+                false,
+                use_regex,
+                true,
+                PathBuf::from("."),
+                String::new(),
+                true,
+                None,
+            )
+            .unwrap()
+        };
+        let buffer = b"first line\r\nERROR: disk full\nok\n  error again\nerr\xe9ur ERROR\nlast";
 
-        assert!(ctx.is_match("User2Controller"));
-        assert!(!ctx.is_match("UserController"));
+        for ctx in [context("error", false), context("err(or)?", true)] {
+            assert!(ctx.content_prefilter().is_some());
+            let lines: Vec<_> = find_matches_in_buffer(buffer, &ctx)
+                .into_iter()
+                .map(|m| (m.line_number, m.text))
+                .collect();
+            assert_eq!(
+                lines,
+                vec![
+                    (2, "ERROR: disk full".to_string()),
+                    (4, "error again".to_string()),
+                    (5, "err\u{e9}ur ERROR".to_string()),
+                ]
+            );
+        }
+        let ctx = context("error", false);
+        assert_eq!(find_matches_in_buffer(buffer, &ctx)[2].column, 8);
+
+        // Anchors match at every line, CRLF endings included.
+        let utf8 = b"ERROR: first
+ok
+ERROR: disk full
+last ERROR
+not full
+";
+        let lines = |query: &str, buffer: &[u8]| -> Vec<usize> {
+            find_matches_in_buffer(buffer, &context(query, true))
+                .into_iter()
+                .map(|m| m.line_number)
+                .collect()
+        };
+        assert_eq!(lines("^ERROR", utf8), vec![1, 3]);
+        assert_eq!(lines("full$", utf8), vec![3, 5]);
+
+        // Regexes see the Windows-1252 lines decoded.
+        let legacy = b"first line\ncaf\xe9 au lait\nlast\n";
+        assert_eq!(lines("caf.", legacy), vec![2]);
+        assert!(context("caf\u{e9}", true).content_prefilter().is_none());
+        assert_eq!(lines("caf\u{e9}", legacy), vec![2]);
+
+        // Boolean queries scan every line.
+        let ctx = context("error -disk", false);
+        assert!(ctx.content_prefilter().is_none());
+        assert_eq!(find_matches_in_buffer(buffer, &ctx).len(), 2);
+
+        let ctx = context("error", false).with_options(SearchOptions {
+            max_content_lines: Some(3),
+            ..Default::default()
+        });
+        assert_eq!(find_matches_in_buffer(buffer, &ctx).len(), 1);
+        assert_eq!(ctx.content_stats.truncated.load(Ordering::Relaxed), 1);
     }
 
     #[test]
-    fn test_camelcase_fallback_to_normal() {
-        let ctx = SearchContext::new(
-            "UC".to_string(),
-            false,
-            false,
-            false,
-            PathBuf::from("/tmp"),
-            "".to_string(),
-            true,
-            None,
-        )
-        .unwrap();
+    fn test_literal_matcher() {
+        let context = |query: &str, case_sensitive: bool, whole_word: bool| {
+            SearchContext::new(
+                query.to_string(),
+                case_sensitive,
+                false,
+                true,
+                PathBuf::from("."),
+                String::new(),
+                true,
+                None,
+            )
+            .unwrap()
+            .with_options(SearchOptions {
+                whole_word,
+                ..Default::default()
+            })
+        };
 
-        // If no CamelCase match, should fallback to normal search
-        assert!(ctx.is_match("ABUC"));
-        assert!(ctx.is_match("testUCvalue"));
+        let ctx = context("todo", false, false);
+        assert!(ctx.literal.is_some());
+        assert!(ctx.is_match("// TODO: remove"));
+        assert!(ctx.is_match("mastodon"));
+        assert!(!ctx.is_match("to do"));
+
+        let ctx = context("todo", false, true);
+        assert!(ctx.is_match("// ToDo: remove"));
+        assert!(!ctx.is_match("mastodon todos"));
+
+        assert!(context("todo", true, false).literal.is_none());
+        assert!(context("café", false, false).literal.is_none());
     }
 
     #[test]
-    fn test_not_camelcase_query() {
-        let ctx = SearchContext::new(
-            "User".to_string(),
-            false,
-            false,
-            false,
-            PathBuf::from("/tmp"),
-            "".to_string(),
-            true,
-            None,
-        )
-        .unwrap();
+    fn test_gitignore_rules_of_listed_files() {
+        let root = std::env::temp_dir().join("quick-findr-engine-gitignore");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("app/logs")).unwrap();
+        std::fs::write(root.join("app/.gitignore"), "logs/\n*.tmp\n").unwrap();
 
-        // "User" is not a CamelCase pattern (not all uppercase)
-        // Should perform normal search
-        assert!(ctx.is_match("UserController"));
-        assert!(ctx.is_match("user"));
-        assert!(ctx.is_match("MyUser"));
+        let rules = GitignoreRules::default();
+        assert!(rules.ignores(&root, &root.join("app/logs/today.txt"), false));
+        assert!(rules.ignores(&root, &root.join("app/cache.tmp"), false));
+        assert!(!rules.ignores(&root, &root.join("app/main.rs"), false));
+        assert!(!rules.ignores(&root, &root.join("cache.tmp"), false));
+        // "logs/" only names folders.
+        assert!(rules.ignores(&root, &root.join("app/logs"), true));
+        assert!(!rules.ignores(&root, &root.join("app/logs"), false));
+
+        // .ignore and .rgignore next to it, the later one winning; then the
+        // custom ignore file.
+        std::fs::write(root.join("app/.ignore"), "*.bak\nkeep.*\n").unwrap();
+        std::fs::write(root.join("app/.rgignore"), "!keep.bak\n").unwrap();
+        std::fs::write(root.join("custom.ignore"), "*.log\n").unwrap();
+        let rules = GitignoreRules::with_ignore_file(&root, Some(root.join("custom.ignore")));
+        assert!(rules.ignores(&root, &root.join("app/old.bak"), false));
+        assert!(!rules.ignores(&root, &root.join("app/keep.bak"), false));
+        assert!(rules.ignores(&root, &root.join("app/keep.txt"), false));
+        assert!(rules.ignores(&root, &root.join("run.log"), false));
+        let _ = std::fs::remove_dir_all(&root);
     }
 
     #[test]
-    fn test_camelcase_single_letter() {
-        let ctx = SearchContext::new(
-            "U".to_string(),
-            false,
-            false,
-            false,
-            PathBuf::from("/tmp"),
-            "".to_string(),
-            true,
-            None,
-        )
-        .unwrap();
+    fn test_archive_entries_by_name_and_content() {
+        use std::io::Write;
 
-        // A single letter is not a CamelCase pattern
-        // Should perform normal search
-        assert!(ctx.is_match("UserController"));
-        assert!(ctx.is_match("user"));
+        let root = std::env::temp_dir().join("quick-findr-engine-archive");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        let jar = root.join("app.jar");
+        let mut writer = zip::ZipWriter::new(File::create(&jar).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.add_directory("config/", options).unwrap();
+        writer
+            .start_file("config/needle.properties", options)
+            .unwrap();
+        writer.start_file("docs/readme.txt", options).unwrap();
+        writer.write_all(b"intro\nthe needle is here\n").unwrap();
+        writer.start_file("lib/needle.class", options).unwrap();
+        writer.finish().unwrap();
+
+        let context = |search_content, search_archives| {
+            SearchContext::new(
+                "needle".to_string(),
+                false,
+                false,
+                search_content,
+                root.clone(),
+                String::new(),
+                true,
+                None,
+            )
+            .unwrap()
+            .with_options(SearchOptions {
+                search_archives,
+                ..Default::default()
+            })
+        };
+
+        assert!(file_results(&jar, &context(true, false)).is_empty());
+        let names: Vec<String> = file_results(&jar, &context(false, true))
+            .into_iter()
+            .map(|result| result.relative_path)
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "app.jar!/config/needle.properties",
+                "app.jar!/lib/needle.class"
+            ]
+        );
+
+        let results = file_results(&jar, &context(true, true));
+        assert_eq!(results.len(), 3);
+        let readme = &results[1];
+        assert_eq!(readme.file_name, "readme.txt");
+        assert_eq!(
+            readme.file_path,
+            format!("{}!/docs/readme.txt", jar.display())
+        );
+        assert_eq!(readme.line_match, "L2: the needle is here");
+        let _ = std::fs::remove_dir_all(&root);
     }
 
     #[test]
-    fn test_camelcase_long_pattern() {
+    fn test_process_dir_matches_folder_names() {
+        let root = std::env::temp_dir().join("quick-findr-engine-folders");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("db/migrations")).unwrap();
+
+        let context = |query: &str| {
+            SearchContext::new(
+                query.to_string(),
+                false,
+                false,
+                false,
+                root.clone(),
+                String::new(),
+                true,
+                None,
+            )
+            .unwrap()
+        };
+
+        let result = process_dir(&root.join("db/migrations"), &context("migration")).unwrap();
+        assert!(result.is_dir);
+        assert_eq!(result.file_name, "migrations");
+        assert_eq!(
+            Path::new(&result.relative_path),
+            Path::new("db").join("migrations")
+        );
+        assert!(process_dir(&root.join("db"), &context("migration")).is_none());
+        assert!(process_dir(&root.join("db/migrations"), &context("path:db migrations")).is_some());
+        // File filters and qualifier-only queries leave folders out.
+        assert!(process_dir(&root.join("db/migrations"), &context("migrations ext:sql")).is_none());
+        assert!(process_dir(&root.join("db/migrations"), &context("path:db")).is_none());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_match_path() {
+        let root = std::env::temp_dir().join("quick-findr-engine-match-path");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("app/models")).unwrap();
+        std::fs::write(root.join("app/models/user.rb"), "").unwrap();
+
+        let context = |query: &str, match_path| {
+            SearchContext::new(
+                query.to_string(),
+                false,
+                false,
+                false,
+                root.clone(),
+                String::new(),
+                true,
+                None,
+            )
+            .unwrap()
+            .with_options(SearchOptions {
+                match_path,
+                ..Default::default()
+            })
+        };
+
+        let file = root.join("app/models/user.rb");
+        assert!(process_file(&file, &context("models/user", false)).is_none());
+        let result = process_file(&file, &context("models/user", true)).unwrap();
+        assert_eq!(result.file_name, "user.rb");
+        assert!(process_file(&file, &context("app/*/user.rb", true)).is_some());
+        assert!(process_dir(&root.join("app/models"), &context("app/models", true)).is_some());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_followed_links_skip_loops_and_repeats() {
+        use std::os::unix::fs::symlink;
+
+        let base = std::env::temp_dir().join("quick-findr-engine-links");
+        let _ = std::fs::remove_dir_all(&base);
+        let root = base.join("root");
+        let outside = base.join("shared/docs");
+        std::fs::create_dir_all(root.join("a")).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        symlink(&root, root.join("a/loop")).unwrap();
+        symlink(&outside, root.join("docs")).unwrap();
+        symlink(&outside, root.join("a/docs-again")).unwrap();
+        symlink(base.join("shared"), root.join("shared")).unwrap();
+
+        let links = FollowedLinks::new(&root);
+        assert!(!links.should_follow(&root.join("a/loop")));
+        assert!(links.should_follow(&root.join("docs")));
+        assert!(!links.should_follow(&root.join("a/docs-again")));
+        // A parent of a walked target is still walked (its other folders are new).
+        assert!(links.should_follow(&root.join("shared")));
+        assert!(!links.should_follow(&root.join("missing")));
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_access_errors() {
+        let errors = AccessErrors::default();
+        for i in 0..MAX_LISTED_ERRORS + 5 {
+            errors.record(format!("C:\\locked\\{}.txt: Access is denied", i));
+        }
+        assert_eq!(errors.count(), MAX_LISTED_ERRORS + 5);
+        let listed = errors.take_listed();
+        assert_eq!(listed.len(), MAX_LISTED_ERRORS);
+        assert_eq!(listed[0], "C:\\locked\\0.txt: Access is denied");
+        assert!(errors.take_listed().is_empty());
+    }
+
+    #[test]
+    fn test_scan_progress_status() {
+        let progress = ScanProgress::default();
+        progress.walked.store(1200, Ordering::Relaxed);
+        progress.searched.store(340, Ordering::Relaxed);
+        assert_eq!(
+            progress.status("Scanning...", 12, true, Duration::ZERO),
+            "Scanning... 1200 entries, 340 files searched, 12 matches"
+        );
+        assert_eq!(
+            progress.status("Refreshing...", 0, false, Duration::from_millis(2500)),
+            "Refreshing... 1200 entries, 0 matches (2.5s, 480 entries/s)"
+        );
+
+        let root = std::env::temp_dir().join("quick-findr-engine-progress");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("notes.txt"), "needle").unwrap();
         let ctx = SearchContext::new(
-            "UACS".to_string(),
-            false,
+            "needle".to_string(),
             false,
             false,
-            PathBuf::from("/tmp"),
-            "".to_string(),
+            true,
+            root.clone(),
+            String::new(),
             true,
             None,
         )
         .unwrap();
-
-        assert!(ctx.is_match("UserAuthenticationControllerService"));
-        assert!(ctx.is_match("UpdateAccountCustomerService"));
-        assert!(!ctx.is_match("UserController"));
+        assert!(process_file(&root.join("notes.txt"), &ctx).is_some());
+        assert_eq!(ctx.progress.searched.load(Ordering::Relaxed), 1);
+        ctx.walked_entry();
+        assert_eq!(ctx.progress.walked.load(Ordering::Relaxed), 1);
+        let _ = std::fs::remove_dir_all(&root);
     }
 }
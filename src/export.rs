@@ -0,0 +1,372 @@
+// Export of the search results, for scripts and colleagues: CSV (one row per
+// matched line) or JSON, a standalone HTML report kept as a record of the
+// search, and a Markdown table to paste into an issue.
+
+use crate::compare::csv_field;
+use crate::i18n::{self, tr};
+use serde::Serialize;
+use std::io::{self, Write};
+
+// One result as exported. Exports cover every result of the list, not only
+// the rows scrolled into view.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedResult {
+    pub path: String,
+    pub name: String,
+    pub relative_path: String,
+    pub extension: String,
+    pub is_dir: bool,
+    // Matched lines of a content match (empty for a name match).
+    pub matches: Vec<ExportedMatch>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedMatch {
+    pub line_number: usize,
+    pub column: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Json,
+}
+
+impl Format {
+    // From the extension of the chosen file; CSV unless it is ".json".
+    pub fn from_path(path: &std::path::Path) -> Self {
+        match path.extension() {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Csv,
+        }
+    }
+}
+
+pub fn write<W: Write>(
+    results: &[ExportedResult],
+    format: Format,
+    writer: &mut W,
+) -> io::Result<()> {
+    match format {
+        Format::Csv => write_csv(results, writer),
+        Format::Json => write_json(results, writer),
+    }
+}
+
+// One row per matched line (RFC 4180 quoting); a name match is one row with
+// no line.
+pub fn write_csv<W: Write>(results: &[ExportedResult], writer: &mut W) -> io::Result<()> {
+    writeln!(
+        writer,
+        "path,name,extension,relative_path,line,column,match"
+    )?;
+    for result in results {
+        let prefix = format!(
+            "{},{},{},{}",
+            csv_field(&result.path),
+            csv_field(&result.name),
+            csv_field(&result.extension),
+            csv_field(&result.relative_path)
+        );
+        if result.matches.is_empty() {
+            writeln!(writer, "{},,,", prefix)?;
+        }
+        for hit in &result.matches {
+            writeln!(
+                writer,
+                "{},{},{},{}",
+                prefix,
+                hit.line_number,
+                hit.column,
+                csv_field(&hit.text)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+// An array of results, each with its matched lines.
+pub fn write_json<W: Write>(results: &[ExportedResult], writer: &mut W) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *writer, results)?;
+    writeln!(writer)
+}
+
+// What the HTML report says about the search itself.
+#[derive(Debug, Clone, Default)]
+pub struct ReportInfo {
+    pub query: String,
+    pub folder: String,
+    // Active options, as labelled in the search bar ("Casse", "Taille : >10MB").
+    pub options: Vec<String>,
+    // "2026-10-16 14:05:09".
+    pub generated: String,
+}
+
+const REPORT_STYLE: &str = "body{font-family:Segoe UI,Arial,sans-serif;margin:24px;color:#222}\
+h1{font-size:20px}dl{display:grid;grid-template-columns:max-content auto;gap:4px 16px}\
+dt{font-weight:600}table{border-collapse:collapse;width:100%;font-size:13px}\
+th,td{border:1px solid #ddd;padding:4px 8px;text-align:left;vertical-align:top}\
+th{background:#f0f0f0}tr:nth-child(even){background:#fafafa}\
+code{font-family:Consolas,monospace;white-space:pre-wrap}";
+
+// One table row per matched line (a name match has an empty line), each with a
+// `file://` link to the file.
+pub fn write_html<W: Write>(
+    results: &[ExportedResult],
+    info: &ReportInfo,
+    writer: &mut W,
+) -> io::Result<()> {
+    writeln!(writer, "<!DOCTYPE html>")?;
+    writeln!(
+        writer,
+        "<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">",
+        i18n::language().name()
+    )?;
+    writeln!(
+        writer,
+        "<title>{}</title>",
+        escape_html(&tr!("Quick Findr: {}", info.query))
+    )?;
+    writeln!(writer, "<style>{}</style>\n</head>\n<body>", REPORT_STYLE)?;
+    writeln!(writer, "<h1>{}</h1>\n<dl>", tr!("Search report"))?;
+    let options = if info.options.is_empty() {
+        tr!("none").to_string()
+    } else {
+        info.options.join(", ")
+    };
+    for (label, value) in [
+        (tr!("Search"), &info.query),
+        (tr!("Folder"), &info.folder),
+        ("Options", &options),
+        (tr!("Generated on"), &info.generated),
+        (tr!("Results"), &results.len().to_string()),
+    ] {
+        writeln!(writer, "<dt>{}</dt><dd>{}</dd>", label, escape_html(value))?;
+    }
+    writeln!(writer, "</dl>\n<table>")?;
+    writeln!(
+        writer,
+        "<tr><th>{}</th><th>{}</th><th>{}</th><th>{}</th></tr>",
+        tr!("Name"),
+        tr!("Path"),
+        tr!("Line"),
+        tr!("Match")
+    )?;
+    for result in results {
+        let link = format!(
+            "<a href=\"{}\">{}</a>",
+            escape_html(&file_url(&result.path)),
+            escape_html(&result.name)
+        );
+        let path = escape_html(&result.relative_path);
+        if result.matches.is_empty() {
+            writeln!(
+                writer,
+                "<tr><td>{}</td><td>{}</td><td></td><td></td></tr>",
+                link, path
+            )?;
+        }
+        for hit in &result.matches {
+            writeln!(
+                writer,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td><code>{}</code></td></tr>",
+                link,
+                path,
+                hit.line_number,
+                escape_html(&hit.text)
+            )?;
+        }
+    }
+    writeln!(writer, "</table>\n</body>\n</html>")
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// `file:///C:/My%20Docs/a.txt` for a drive path, `file://server/share/a.txt`
+// for a UNC path; bytes other than unreserved ones are percent-encoded.
+pub fn file_url(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let (prefix, rest) = match path.strip_prefix("//") {
+        Some(unc) => ("file://", unc.to_string()),
+        None if path.starts_with('/') => ("file://", path),
+        None => ("file:///", path),
+    };
+    let mut url = prefix.to_string();
+    for byte in rest.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                url.push(byte as char)
+            }
+            _ => url.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    url
+}
+
+// Name / relative path / matched line, one row per matched line (GitHub
+// flavoured Markdown).
+pub fn markdown_table(results: &[ExportedResult]) -> String {
+    let mut table = format!(
+        "| {} | {} | {} |\n| --- | --- | --- |\n",
+        tr!("Name"),
+        tr!("Path"),
+        tr!("Line")
+    );
+    for result in results {
+        let name = result.name.replace('|', "\\|");
+        let path = code_cell(&result.relative_path);
+        if result.matches.is_empty() {
+            table.push_str(&format!("| {} | {} | |\n", name, path));
+        }
+        for hit in &result.matches {
+            table.push_str(&format!(
+                "| {} | {} | {} |\n",
+                name,
+                path,
+                tr!("{}: {}", hit.line_number, code_cell(&hit.text))
+            ));
+        }
+    }
+    table
+}
+
+// Inline code in a table cell: `|` still splits cells there, and a text with
+// backticks needs a longer fence.
+fn code_cell(text: &str) -> String {
+    let text = text.trim().replace('|', "\\|");
+    if text.is_empty() {
+        String::new()
+    } else if text.contains('`') {
+        format!("`` {} ``", text)
+    } else {
+        format!("`{}`", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results() -> Vec<ExportedResult> {
+        vec![
+            ExportedResult {
+                path: r"C:\src\notes, v2.md".to_string(),
+                name: "notes, v2.md".to_string(),
+                relative_path: "notes, v2.md".to_string(),
+                extension: "md".to_string(),
+                is_dir: false,
+                matches: vec![],
+            },
+            ExportedResult {
+                path: r"C:\src\main.rs".to_string(),
+                name: "main.rs".to_string(),
+                relative_path: "main.rs".to_string(),
+                extension: "rs".to_string(),
+                is_dir: false,
+                matches: vec![
+                    ExportedMatch {
+                        line_number: 3,
+                        column: 5,
+                        text: r#"let q = "report";"#.to_string(),
+                    },
+                    ExportedMatch {
+                        line_number: 9,
+                        column: 1,
+                        text: "report()".to_string(),
+                    },
+                ],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let mut out = Vec::new();
+        write_csv(&results(), &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "path,name,extension,relative_path,line,column,match\n\
+             \"C:\\src\\notes, v2.md\",\"notes, v2.md\",md,\"notes, v2.md\",,,\n\
+             C:\\src\\main.rs,main.rs,rs,main.rs,3,5,\"let q = \"\"report\"\";\"\n\
+             C:\\src\\main.rs,main.rs,rs,main.rs,9,1,report()\n"
+        );
+    }
+
+    #[test]
+    fn test_write_html() {
+        let info = ReportInfo {
+            query: "<report>".to_string(),
+            folder: r"C:\src".to_string(),
+            options: vec!["Content".to_string(), "Match case".to_string()],
+            generated: "2026-10-16 14:05:09".to_string(),
+        };
+        let mut out = Vec::new();
+        write_html(&results(), &info, &mut out).unwrap();
+
+        let html = String::from_utf8(out).unwrap();
+        assert!(html.contains("<html lang=\"en\">"));
+        assert!(html.contains("<dt>Search</dt><dd>&lt;report&gt;</dd>"));
+        assert!(html.contains("<dd>Content, Match case</dd>"));
+        assert!(html.contains("<dd>2026-10-16 14:05:09</dd>"));
+        assert!(html.contains(r#"<a href="file:///C:/src/notes%2C%20v2.md">notes, v2.md</a>"#));
+        assert!(html.contains("<td>3</td><td><code>let q = &quot;report&quot;;</code></td>"));
+        assert_eq!(html.matches("<tr>").count(), 4);
+    }
+
+    #[test]
+    fn test_markdown_table() {
+        let mut results = results();
+        results[1].matches[1].text = "a | `b`".to_string();
+
+        assert_eq!(
+            markdown_table(&results),
+            "| Name | Path | Line |\n\
+             | --- | --- | --- |\n\
+             | notes, v2.md | `notes, v2.md` | |\n\
+             | main.rs | `main.rs` | 3: `let q = \"report\";` |\n\
+             | main.rs | `main.rs` | 9: `` a \\| `b` `` |\n"
+        );
+    }
+
+    #[test]
+    fn test_file_url() {
+        assert_eq!(
+            file_url(r"C:\My Docs\é.txt"),
+            "file:///C:/My%20Docs/%C3%A9.txt"
+        );
+        assert_eq!(
+            file_url(r"\\server\share\a#1.txt"),
+            "file://server/share/a%231.txt"
+        );
+        assert_eq!(file_url("/home/me/a.txt"), "file:///home/me/a.txt");
+    }
+
+    #[test]
+    fn test_write_json() {
+        let mut out = Vec::new();
+        write(&results(), Format::Json, &mut out).unwrap();
+
+        let value: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(value[1]["matches"][0]["line_number"], 3);
+        assert_eq!(value[0]["matches"].as_array().unwrap().len(), 0);
+        assert_eq!(
+            Format::from_path(std::path::Path::new("out.JSON")),
+            Format::Json
+        );
+    }
+}
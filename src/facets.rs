@@ -0,0 +1,45 @@
+// Extension summary of the results ("rs (120) · md (34)"), shown as chips that
+// narrow the list to one extension.
+
+use std::collections::HashMap;
+
+// Chips shown; the rarer extensions are left out.
+pub const MAX_FACETS: usize = 12;
+
+// Extensions (lowercase, "" for files without one) with their number of
+// results, most frequent first, then by name.
+pub fn extension_counts<'a>(extensions: impl IntoIterator<Item = &'a str>) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for extension in extensions {
+        *counts.entry(extension.to_lowercase()).or_default() += 1;
+    }
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.truncate(MAX_FACETS);
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extension_counts() {
+        let counts = extension_counts(["rs", "md", "RS", "", "json", "rs", "md"]);
+        assert_eq!(
+            counts,
+            vec![
+                ("rs".to_string(), 3),
+                ("md".to_string(), 2),
+                ("".to_string(), 1),
+                ("json".to_string(), 1),
+            ]
+        );
+
+        let many: Vec<String> = (0..20).map(|i| format!("e{:02}", i)).collect();
+        assert_eq!(
+            extension_counts(many.iter().map(String::as_str)).len(),
+            MAX_FACETS
+        );
+    }
+}
@@ -7,6 +7,9 @@ pub struct FavoriteFolder {
     pub path: String,
     pub name: String,
     pub last_used: u64,
+    // Opt-in full-text index of the folder (see `indexer`).
+    #[serde(default)]
+    pub content_indexed: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -63,6 +66,7 @@ impl FavoritesManager {
                 path,
                 name,
                 last_used: Self::current_timestamp(),
+                content_indexed: false,
             });
             let _ = self.save();
         }
@@ -92,6 +96,7 @@ impl FavoritesManager {
                 path,
                 name,
                 last_used: timestamp,
+                content_indexed: false,
             },
         );
 
@@ -103,6 +108,17 @@ impl FavoritesManager {
         let _ = self.save();
     }
 
+    // Returns the new state (false for an unknown folder).
+    pub fn toggle_content_index(&mut self, path: &str) -> bool {
+        let Some(fav) = self.favorites.iter_mut().find(|f| f.path == path) else {
+            return false;
+        };
+        fav.content_indexed = !fav.content_indexed;
+        let enabled = fav.content_indexed;
+        let _ = self.save();
+        enabled
+    }
+
     pub fn update_last_used(&mut self, path: &str) {
         let timestamp = Self::current_timestamp();
 
@@ -159,6 +175,17 @@ mod tests {
         assert_eq!(manager.favorites.len(), 0);
     }
 
+    #[test]
+    fn test_toggle_content_index() {
+        let mut manager = FavoritesManager::new();
+        manager.add_favorite("/test/path".to_string(), "Test".to_string());
+
+        assert!(manager.toggle_content_index("/test/path"));
+        assert!(manager.favorites[0].content_indexed);
+        assert!(!manager.toggle_content_index("/test/path"));
+        assert!(!manager.toggle_content_index("/unknown"));
+    }
+
     #[test]
     fn test_add_recent() {
         let mut manager = FavoritesManager::new();
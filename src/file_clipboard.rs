@@ -0,0 +1,114 @@
+// Files on the clipboard as files (CF_HDROP), the way Explorer copies them, so
+// that they can be pasted into a folder or attached to a mail. Only Windows
+// has it; elsewhere the caller copies the paths as text.
+
+use std::path::PathBuf;
+
+// Size of the DROPFILES header: pFiles, pt.x, pt.y, fNC, fWide.
+const DROPFILES_SIZE: u32 = 20;
+
+// CF_HDROP data: the DROPFILES header, then the paths in UTF-16, each ended by
+// a NUL, and a final NUL.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn drop_files_data(paths: &[PathBuf]) -> Vec<u8> {
+    let mut data = Vec::new();
+    // pFiles (offset of the list), pt.x, pt.y, fNC, fWide (UTF-16 list).
+    for field in [DROPFILES_SIZE, 0, 0, 0, 1] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    for path in paths {
+        for unit in path.to_string_lossy().encode_utf16().chain(Some(0)) {
+            data.extend_from_slice(&unit.to_le_bytes());
+        }
+    }
+    data.extend_from_slice(&0u16.to_le_bytes());
+    data
+}
+
+#[cfg(windows)]
+pub fn copy_files(paths: &[PathBuf]) -> Result<(), String> {
+    use windows_sys::Win32::Foundation::GlobalFree;
+    use windows_sys::Win32::System::DataExchange::{
+        CloseClipboard, EmptyClipboard, OpenClipboard, RegisterClipboardFormatW, SetClipboardData,
+    };
+
+    const CF_HDROP: u32 = 15;
+    const DROPEFFECT_COPY: u32 = 1;
+
+    // SAFETY: the clipboard is opened by this thread and closed below; memory
+    // handed to the clipboard is owned by it, other memory is freed.
+    unsafe {
+        if OpenClipboard(std::ptr::null_mut()) == 0 {
+            return Err("the clipboard is used by another application".to_string());
+        }
+        EmptyClipboard();
+        let result = global_copy(&drop_files_data(paths)).and_then(|memory| {
+            if SetClipboardData(CF_HDROP, memory).is_null() {
+                GlobalFree(memory);
+                return Err("the clipboard refused the files".to_string());
+            }
+            Ok(())
+        });
+        // Paste as a copy, not a move.
+        if result.is_ok() {
+            let name: Vec<u16> = "Preferred DropEffect"
+                .encode_utf16()
+                .chain(Some(0))
+                .collect();
+            let format = RegisterClipboardFormatW(name.as_ptr());
+            if let Ok(memory) = global_copy(&DROPEFFECT_COPY.to_le_bytes()) {
+                if format == 0 || SetClipboardData(format, memory).is_null() {
+                    GlobalFree(memory);
+                }
+            }
+        }
+        CloseClipboard();
+        result
+    }
+}
+
+// Movable global memory holding `data`, as the clipboard wants it.
+#[cfg(windows)]
+unsafe fn global_copy(data: &[u8]) -> Result<windows_sys::Win32::Foundation::HGLOBAL, String> {
+    use windows_sys::Win32::Foundation::GlobalFree;
+    use windows_sys::Win32::System::Memory::{
+        GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE,
+    };
+
+    let memory = GlobalAlloc(GMEM_MOVEABLE, data.len());
+    if memory.is_null() {
+        return Err("out of memory".to_string());
+    }
+    let target = GlobalLock(memory) as *mut u8;
+    if target.is_null() {
+        GlobalFree(memory);
+        return Err("out of memory".to_string());
+    }
+    std::ptr::copy_nonoverlapping(data.as_ptr(), target, data.len());
+    GlobalUnlock(memory);
+    Ok(memory)
+}
+
+#[cfg(not(windows))]
+pub fn copy_files(_paths: &[PathBuf]) -> Result<(), String> {
+    Err("copying files is only supported on Windows".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drop_files_data() {
+        let data = drop_files_data(&[PathBuf::from(r"C:\a.txt"), PathBuf::from(r"D:\é")]);
+
+        assert_eq!(&data[..4], &20u32.to_le_bytes());
+        assert_eq!(&data[16..20], &1u32.to_le_bytes());
+        let list: Vec<u16> = data[20..]
+            .chunks(2)
+            .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+            .collect();
+        let expected: Vec<u16> = "C:\\a.txt\0D:\\é\0\0".encode_utf16().collect();
+        assert_eq!(list, expected);
+    }
+}
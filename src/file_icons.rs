@@ -0,0 +1,300 @@
+// System icons of the results, by extension: the shell icon on Windows
+// (SHGetFileInfo), the icon theme elsewhere (freedesktop MIME database, then
+// the icon of that type in a common theme). The caller caches them.
+
+use crate::thumbnails::Thumbnail;
+#[cfg(not(windows))]
+use std::collections::HashMap;
+#[cfg(not(windows))]
+use std::path::PathBuf;
+
+// Icon of files with `extension`, or of folders; None when the system has
+// none to offer (the result keeps its colored badge).
+pub fn icon(extension: &str, is_dir: bool) -> Option<Thumbnail> {
+    system_icon(extension, is_dir)
+}
+
+#[cfg(windows)]
+fn system_icon(extension: &str, is_dir: bool) -> Option<Thumbnail> {
+    use windows_sys::Win32::Storage::FileSystem::{
+        FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_NORMAL,
+    };
+    use windows_sys::Win32::UI::Shell::{
+        SHGetFileInfoW, SHFILEINFOW, SHGFI_ICON, SHGFI_LARGEICON, SHGFI_USEFILEATTRIBUTES,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::DestroyIcon;
+
+    // With SHGFI_USEFILEATTRIBUTES the file does not have to exist: the icon
+    // comes from the name and attributes alone.
+    let (name, attributes) = if is_dir {
+        ("folder".to_string(), FILE_ATTRIBUTE_DIRECTORY)
+    } else {
+        (format!("file.{}", extension), FILE_ATTRIBUTE_NORMAL)
+    };
+    let name: Vec<u16> = name.encode_utf16().chain(Some(0)).collect();
+    // SAFETY: `name` is NUL-terminated and `info` is a zeroed SHFILEINFOW of
+    // the given size; the icon handed back is destroyed once read.
+    unsafe {
+        let mut info: SHFILEINFOW = std::mem::zeroed();
+        let found = SHGetFileInfoW(
+            name.as_ptr(),
+            attributes,
+            &mut info,
+            std::mem::size_of::<SHFILEINFOW>() as u32,
+            SHGFI_ICON | SHGFI_LARGEICON | SHGFI_USEFILEATTRIBUTES,
+        );
+        if found == 0 || info.hIcon.is_null() {
+            return None;
+        }
+        let pixels = icon_pixels(info.hIcon);
+        DestroyIcon(info.hIcon);
+        pixels
+    }
+}
+
+// RGBA pixels of an icon handle.
+#[cfg(windows)]
+unsafe fn icon_pixels(
+    icon: windows_sys::Win32::UI::WindowsAndMessaging::HICON,
+) -> Option<Thumbnail> {
+    use windows_sys::Win32::Graphics::Gdi::DeleteObject;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{GetIconInfo, ICONINFO};
+
+    let mut info: ICONINFO = std::mem::zeroed();
+    if GetIconInfo(icon, &mut info) == 0 {
+        return None;
+    }
+    // Monochrome icons have no color bitmap.
+    let pixels = if info.hbmColor.is_null() {
+        None
+    } else {
+        bitmap_pixels(info.hbmColor)
+    };
+    DeleteObject(info.hbmColor);
+    DeleteObject(info.hbmMask);
+    pixels
+}
+
+#[cfg(windows)]
+unsafe fn bitmap_pixels(bitmap: windows_sys::Win32::Graphics::Gdi::HBITMAP) -> Option<Thumbnail> {
+    use windows_sys::Win32::Graphics::Gdi::{
+        CreateCompatibleDC, DeleteDC, GetDIBits, GetObjectW, BITMAP, BITMAPINFO, BITMAPINFOHEADER,
+        BI_RGB, DIB_RGB_COLORS,
+    };
+
+    let mut size: BITMAP = std::mem::zeroed();
+    let read = GetObjectW(
+        bitmap,
+        std::mem::size_of::<BITMAP>() as i32,
+        (&mut size as *mut BITMAP).cast(),
+    );
+    if read == 0 || size.bmWidth <= 0 || size.bmHeight <= 0 {
+        return None;
+    }
+    let (width, height) = (size.bmWidth, size.bmHeight);
+
+    let mut format: BITMAPINFO = std::mem::zeroed();
+    format.bmiHeader.biSize = std::mem::size_of::<BITMAPINFOHEADER>() as u32;
+    format.bmiHeader.biWidth = width;
+    // Negative height: rows top to bottom.
+    format.bmiHeader.biHeight = -height;
+    format.bmiHeader.biPlanes = 1;
+    format.bmiHeader.biBitCount = 32;
+    format.bmiHeader.biCompression = BI_RGB;
+    let mut bgra = vec![0u8; width as usize * height as usize * 4];
+    let dc = CreateCompatibleDC(std::ptr::null_mut());
+    let lines = GetDIBits(
+        dc,
+        bitmap,
+        0,
+        height as u32,
+        bgra.as_mut_ptr().cast(),
+        &mut format,
+        DIB_RGB_COLORS,
+    );
+    DeleteDC(dc);
+    if lines == 0 {
+        return None;
+    }
+
+    // Icons from before alpha channels leave it at zero: they are opaque.
+    let no_alpha = bgra.chunks(4).all(|pixel| pixel[3] == 0);
+    let rgba = bgra
+        .chunks(4)
+        .flat_map(|pixel| {
+            let alpha = if no_alpha { 255 } else { pixel[3] };
+            [pixel[2], pixel[1], pixel[0], alpha]
+        })
+        .collect();
+    Some(Thumbnail {
+        width: width as u32,
+        height: height as u32,
+        rgba,
+    })
+}
+
+// Themes searched, in order; hicolor is the fallback every theme inherits.
+#[cfg(not(windows))]
+const THEMES: [&str; 5] = ["Adwaita", "breeze", "Papirus", "gnome", "hicolor"];
+
+#[cfg(not(windows))]
+fn system_icon(extension: &str, is_dir: bool) -> Option<Thumbnail> {
+    let (names, context) = if is_dir {
+        (vec!["folder".to_string()], "places")
+    } else {
+        let database = mime_database();
+        let mime = database.types.get(&extension.to_lowercase())?;
+        (
+            icon_names(mime, database.generic_icons.get(mime).map(String::as_str)),
+            "mimetypes",
+        )
+    };
+    let path = names.iter().find_map(|name| theme_icon(name, context))?;
+    crate::thumbnails::decode(&path, crate::thumbnails::ICON_SIZE)
+}
+
+// Extension to MIME type, and the generic icon of some types.
+#[cfg(not(windows))]
+#[derive(Default)]
+struct MimeDatabase {
+    types: HashMap<String, String>,
+    generic_icons: HashMap<String, String>,
+}
+
+// Read once from the `mime` folders of the data directories.
+#[cfg(not(windows))]
+fn mime_database() -> &'static MimeDatabase {
+    static DATABASE: std::sync::OnceLock<MimeDatabase> = std::sync::OnceLock::new();
+    DATABASE.get_or_init(|| {
+        let mut database = MimeDatabase::default();
+        // The first data directory wins (the user's own, then the system's).
+        for dir in data_dirs().iter().rev() {
+            if let Ok(text) = std::fs::read_to_string(dir.join("mime/globs2")) {
+                database.types.extend(parse_globs(&text));
+            }
+            if let Ok(text) = std::fs::read_to_string(dir.join("mime/generic-icons")) {
+                database.generic_icons.extend(parse_generic_icons(&text));
+            }
+        }
+        database
+    })
+}
+
+// `$XDG_DATA_HOME`, then `$XDG_DATA_DIRS` (with their defaults).
+#[cfg(not(windows))]
+fn data_dirs() -> Vec<PathBuf> {
+    let home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".local/share")));
+    let system = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    home.into_iter()
+        .chain(system.split(':').map(PathBuf::from))
+        .collect()
+}
+
+// Lines `weight:type:*.ext`; for an extension listed more than once the
+// heaviest type wins (the file is sorted by weight, heaviest first).
+#[cfg(not(windows))]
+fn parse_globs(text: &str) -> HashMap<String, String> {
+    let mut types = HashMap::new();
+    for line in text.lines().filter(|line| !line.starts_with('#')) {
+        let mut fields = line.split(':');
+        let (Some(_weight), Some(mime), Some(glob)) = (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        // Only plain `*.ext` patterns name an extension.
+        let Some(extension) = glob.strip_prefix("*.") else {
+            continue;
+        };
+        if extension.contains(['*', '?', '[', '.']) {
+            continue;
+        }
+        types
+            .entry(extension.to_lowercase())
+            .or_insert_with(|| mime.to_string());
+    }
+    types
+}
+
+// Lines `type:icon-name`.
+#[cfg(not(windows))]
+fn parse_generic_icons(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(mime, icon)| (mime.to_string(), icon.to_string()))
+        .collect()
+}
+
+// Icon names of a MIME type, most specific first: `text-x-rust`, then its
+// generic icon (`text-x-generic` when the database names none).
+#[cfg(not(windows))]
+fn icon_names(mime: &str, generic: Option<&str>) -> Vec<String> {
+    let media = mime.split('/').next().unwrap_or(mime);
+    let generic = generic.map_or_else(|| format!("{}-x-generic", media), str::to_string);
+    vec![mime.replace('/', "-"), generic]
+}
+
+// First file of the icon in the themes, at a size close to the result badge.
+#[cfg(not(windows))]
+fn theme_icon(name: &str, context: &str) -> Option<PathBuf> {
+    let dirs = data_dirs();
+    THEMES.iter().find_map(|theme| {
+        let candidates = [
+            format!("{}/32x32/{}/{}.png", theme, context, name),
+            format!("{}/48x48/{}/{}.png", theme, context, name),
+            format!("{}/32x32/{}/{}.svg", theme, context, name),
+            format!("{}/{}/32/{}.svg", theme, context, name),
+            format!("{}/scalable/{}/{}.svg", theme, context, name),
+        ];
+        dirs.iter().find_map(|dir| {
+            candidates
+                .iter()
+                .map(|candidate| dir.join("icons").join(candidate))
+                .find(|path| path.is_file())
+        })
+    })
+}
+
+#[cfg(all(test, not(windows)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_globs() {
+        let types = parse_globs(
+            "# comment\n\
+             80:text/x-rust:*.rs\n\
+             50:text/x-readme:README*\n\
+             50:application/x-compressed-tar:*.tar.gz\n\
+             50:text/markdown:*.md\n\
+             10:text/x-mdx:*.md\n\
+             50:text/x-csrc:*.c:cs\n",
+        );
+
+        assert_eq!(types.get("rs").map(String::as_str), Some("text/x-rust"));
+        assert_eq!(types.get("md").map(String::as_str), Some("text/markdown"));
+        assert_eq!(types.get("c").map(String::as_str), Some("text/x-csrc"));
+        assert_eq!(types.len(), 3);
+    }
+
+    #[test]
+    fn test_icon_names() {
+        let generic = parse_generic_icons("application/zip:package-x-generic\n");
+
+        assert_eq!(
+            icon_names("text/x-rust", None),
+            vec!["text-x-rust", "text-x-generic"]
+        );
+        assert_eq!(
+            icon_names(
+                "application/zip",
+                generic.get("application/zip").map(String::as_str)
+            ),
+            vec!["application-zip", "package-x-generic"]
+        );
+    }
+}
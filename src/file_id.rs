@@ -0,0 +1,92 @@
+// Identity of a file on disk, the same through every path that leads to it
+// (hardlinks, junctions and symlinks followed by the walk).
+
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FileId {
+    // Volume serial number on Windows, device elsewhere.
+    volume: u64,
+    // File index on Windows, inode elsewhere.
+    file: u64,
+}
+
+// None when the file cannot be opened.
+#[cfg(unix)]
+pub fn of(path: &Path) -> Option<FileId> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = std::fs::metadata(path).ok()?;
+    Some(FileId {
+        volume: metadata.dev(),
+        file: metadata.ino(),
+    })
+}
+
+#[cfg(windows)]
+pub fn of(path: &Path) -> Option<FileId> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::Storage::FileSystem::{
+        CreateFileW, GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION,
+        FILE_FLAG_BACKUP_SEMANTICS, FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE,
+        OPEN_EXISTING,
+    };
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    // No access right is asked: only the metadata is read. Backup semantics
+    // let folders be opened too. SAFETY: `wide` is NUL-terminated; the handle
+    // is closed below.
+    let handle = unsafe {
+        CreateFileW(
+            wide.as_ptr(),
+            0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+            std::ptr::null(),
+            OPEN_EXISTING,
+            FILE_FLAG_BACKUP_SEMANTICS,
+            std::ptr::null_mut(),
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        return None;
+    }
+    // SAFETY: `info` is written by the call, `handle` is open until closed here.
+    let (ok, info) = unsafe {
+        let mut info: BY_HANDLE_FILE_INFORMATION = std::mem::zeroed();
+        let ok = GetFileInformationByHandle(handle, &mut info);
+        CloseHandle(handle);
+        (ok, info)
+    };
+    (ok != 0).then(|| FileId {
+        volume: u64::from(info.dwVolumeSerialNumber),
+        file: (u64::from(info.nFileIndexHigh) << 32) | u64::from(info.nFileIndexLow),
+    })
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn of(_path: &Path) -> Option<FileId> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_hardlinks_share_their_id() {
+        let root = std::env::temp_dir().join("quick-findr-file-id");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("report.txt"), "q3").unwrap();
+        fs::write(root.join("other.txt"), "q3").unwrap();
+        fs::hard_link(root.join("report.txt"), root.join("report-link.txt")).unwrap();
+
+        let id = of(&root.join("report.txt")).unwrap();
+        assert_eq!(of(&root.join("report-link.txt")), Some(id));
+        assert_ne!(of(&root.join("other.txt")), Some(id));
+        assert_eq!(of(&root.join("missing.txt")), None);
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,67 @@
+use infer::MatcherType;
+use std::path::Path;
+
+// Content-based file categories, detected from magic bytes rather than from the extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    Image,
+    Video,
+    Audio,
+    Archive,
+    Document,
+    Executable,
+    Font,
+}
+
+impl FileKind {
+    // Name used after `type:` in the query (a few aliases are accepted).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "image" | "img" => Some(Self::Image),
+            "video" => Some(Self::Video),
+            "audio" | "music" => Some(Self::Audio),
+            "archive" | "zip" => Some(Self::Archive),
+            "document" | "doc" => Some(Self::Document),
+            "executable" | "exe" | "binary" => Some(Self::Executable),
+            "font" => Some(Self::Font),
+            _ => None,
+        }
+    }
+}
+
+// Reads the file header (a few KB at most); None for unknown or text formats.
+pub fn detect(path: &Path) -> Option<FileKind> {
+    let kind = infer::get_from_path(path).ok()??;
+    match kind.matcher_type() {
+        MatcherType::Image => Some(FileKind::Image),
+        MatcherType::Video => Some(FileKind::Video),
+        MatcherType::Audio => Some(FileKind::Audio),
+        MatcherType::Archive => Some(FileKind::Archive),
+        MatcherType::Doc | MatcherType::Book => Some(FileKind::Document),
+        MatcherType::App => Some(FileKind::Executable),
+        MatcherType::Font => Some(FileKind::Font),
+        MatcherType::Text | MatcherType::Custom => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_detect_ignores_extension() {
+        let root = std::env::temp_dir().join("quick-findr-filetype-detect");
+        fs::create_dir_all(&root).unwrap();
+
+        // A PNG signature saved as .txt, and plain text saved as .png.
+        let disguised = root.join("picture.txt");
+        fs::write(&disguised, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+        let fake = root.join("notes.png");
+        fs::write(&fake, "just some text").unwrap();
+
+        assert_eq!(detect(&disguised), Some(FileKind::Image));
+        assert_eq!(detect(&fake), None);
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,201 @@
+// fzf-style fuzzy matching: every query character must appear in order in the
+// text; the score rewards matches at word starts, CamelCase humps and
+// consecutive runs, and penalizes gaps.
+
+use std::ops::Range;
+
+const SCORE_MATCH: i64 = 16;
+const SCORE_GAP_START: i64 = -3;
+const SCORE_GAP_EXTENSION: i64 = -1;
+const BONUS_BOUNDARY: i64 = 8;
+const BONUS_CAMEL: i64 = 7;
+const BONUS_CONSECUTIVE: i64 = 4;
+const BONUS_FIRST_CHAR_MULTIPLIER: i64 = 2;
+
+// Score of `pattern` against `text`, or None when it does not match.
+pub fn score(pattern: &str, text: &str, case_sensitive: bool) -> Option<i64> {
+    let pattern = normalized_pattern(pattern, case_sensitive)?;
+    let text: Vec<char> = text.chars().collect();
+    let folded: Vec<char> = text.iter().map(|&c| normalize(c, case_sensitive)).collect();
+    let (start, end) = match_window(&pattern, &folded)?;
+
+    let mut total = 0;
+    let mut pattern_index = 0;
+    let mut in_gap = false;
+    let mut consecutive = 0;
+    let mut chunk_bonus = 0;
+    for (index, &c) in folded.iter().enumerate().take(end + 1).skip(start) {
+        if pattern_index < pattern.len() && c == pattern[pattern_index] {
+            let mut bonus = bonus_at(&text, index);
+            if consecutive == 0 {
+                chunk_bonus = bonus;
+            } else {
+                // A run keeps the bonus of its first character.
+                if bonus == BONUS_BOUNDARY {
+                    chunk_bonus = bonus;
+                }
+                bonus = bonus.max(chunk_bonus).max(BONUS_CONSECUTIVE);
+            }
+            if pattern_index == 0 {
+                bonus *= BONUS_FIRST_CHAR_MULTIPLIER;
+            }
+            total += SCORE_MATCH + bonus;
+            pattern_index += 1;
+            consecutive += 1;
+            in_gap = false;
+        } else {
+            total += if in_gap {
+                SCORE_GAP_EXTENSION
+            } else {
+                SCORE_GAP_START
+            };
+            in_gap = true;
+            consecutive = 0;
+        }
+    }
+
+    Some(total)
+}
+
+// Byte ranges of the characters `score` matches, adjacent ones merged (for
+// highlighting); None when the pattern does not match.
+pub fn positions(pattern: &str, text: &str, case_sensitive: bool) -> Option<Vec<Range<usize>>> {
+    let pattern = normalized_pattern(pattern, case_sensitive)?;
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let folded: Vec<char> = chars
+        .iter()
+        .map(|&(_, c)| normalize(c, case_sensitive))
+        .collect();
+    let (start, end) = match_window(&pattern, &folded)?;
+
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    let mut pattern_index = 0;
+    for (index, &(offset, c)) in chars.iter().enumerate().take(end + 1).skip(start) {
+        if pattern_index < pattern.len() && folded[index] == pattern[pattern_index] {
+            let range = offset..offset + c.len_utf8();
+            match ranges.last_mut() {
+                Some(last) if last.end == range.start => last.end = range.end,
+                _ => ranges.push(range),
+            }
+            pattern_index += 1;
+        }
+    }
+    Some(ranges)
+}
+
+fn normalize(c: char, case_sensitive: bool) -> char {
+    if case_sensitive {
+        c
+    } else {
+        c.to_lowercase().next().unwrap_or(c)
+    }
+}
+
+// Pattern characters without whitespace; None when nothing is left.
+fn normalized_pattern(pattern: &str, case_sensitive: bool) -> Option<Vec<char>> {
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| normalize(c, case_sensitive))
+        .collect();
+    (!pattern.is_empty()).then_some(pattern)
+}
+
+// First and last character indices of the tightest leftmost match.
+fn match_window(pattern: &[char], folded: &[char]) -> Option<(usize, usize)> {
+    // Forward pass: end of the leftmost complete match.
+    let mut pattern_index = 0;
+    let mut end = None;
+    for (index, &c) in folded.iter().enumerate() {
+        if c == pattern[pattern_index] {
+            pattern_index += 1;
+            if pattern_index == pattern.len() {
+                end = Some(index);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    // Backward pass: tightest start for that end.
+    let mut pattern_index = pattern.len();
+    let mut start = end;
+    for index in (0..=end).rev() {
+        if folded[index] == pattern[pattern_index - 1] {
+            pattern_index -= 1;
+            if pattern_index == 0 {
+                start = index;
+                break;
+            }
+        }
+    }
+    Some((start, end))
+}
+
+// Bonus for matching the character at `index` (word start, CamelCase hump, digits).
+fn bonus_at(text: &[char], index: usize) -> i64 {
+    let current = text[index];
+    let Some(&previous) = index.checked_sub(1).and_then(|i| text.get(i)) else {
+        return BONUS_BOUNDARY;
+    };
+
+    if !previous.is_alphanumeric() && current.is_alphanumeric() {
+        BONUS_BOUNDARY
+    } else if (previous.is_lowercase() && current.is_uppercase())
+        || (!previous.is_numeric() && current.is_numeric())
+    {
+        BONUS_CAMEL
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subsequence_matches() {
+        assert!(score("usrctl", "UserController.rs", false).is_some());
+        assert!(score("usrctl", "UserService.rs", false).is_none());
+        assert!(score("Usr", "userController.rs", true).is_none());
+        assert!(score("", "anything", false).is_none());
+    }
+
+    fn ranked<'a>(query: &str, names: &[&'a str]) -> Vec<&'a str> {
+        let mut names: Vec<_> = names
+            .iter()
+            .map(|name| (score(query, name, false).unwrap(), *name))
+            .collect();
+        names.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        names.into_iter().map(|(_, name)| name).collect()
+    }
+
+    #[test]
+    fn test_ranking() {
+        // Word starts and humps beat letters buried inside words.
+        assert_eq!(
+            ranked("usrctl", &["bus_rectangle_tl.rs", "UserController.rs"]),
+            vec!["UserController.rs", "bus_rectangle_tl.rs"]
+        );
+        // A contiguous run beats the same letters spread out.
+        assert_eq!(
+            ranked("main", &["my_animation.rs", "main.rs"]),
+            vec!["main.rs", "my_animation.rs"]
+        );
+    }
+
+    #[test]
+    fn test_positions() {
+        assert_eq!(
+            positions("usrctl", "UserController.rs", false),
+            Some(vec![0..2, 3..5, 7..8, 10..11])
+        );
+        // A run is one range; ranges are byte offsets.
+        assert_eq!(
+            positions("mainé", "main_é.rs", false),
+            Some(vec![0..4, 5..7])
+        );
+        assert_eq!(positions("xyz", "main.rs", false), None);
+    }
+}
@@ -0,0 +1,386 @@
+// Git repositories of searched folders, read through the git command line
+// (git must be on the PATH).
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
+
+// Runs git in `dir`; its output, or an error with git's message.
+fn run(dir: &Path, args: &[&str]) -> io::Result<Vec<u8>> {
+    let output = Command::new("git").args(args).current_dir(dir).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(output.stdout)
+}
+
+// Files known to git under `folder` (`git ls-files`: committed or staged),
+// whether or not they still exist.
+pub fn tracked_files(folder: &Path) -> io::Result<Vec<PathBuf>> {
+    let output = run(folder, &["ls-files", "-z"])?;
+    Ok(output
+        .split(|&byte| byte == 0)
+        .filter(|relative| !relative.is_empty())
+        .map(|relative| folder.join(&*String::from_utf8_lossy(relative)))
+        .collect())
+}
+
+// Files under `folder` changed since `since`, committed or not: "N" counts
+// back N commits from HEAD, a branch (or any revision) means since HEAD left
+// it (their merge base). Deleted files are listed too.
+pub fn changed_files(folder: &Path, since: &str) -> io::Result<Vec<PathBuf>> {
+    let base = match since.parse::<usize>() {
+        Ok(count) => format!("HEAD~{}", count),
+        Err(_) => {
+            check_revision(since)?;
+            let output = run(folder, &["merge-base", since, "HEAD"])?;
+            String::from_utf8_lossy(&output).trim().to_string()
+        }
+    };
+    // `--relative`: the files of `folder` only, relative to it.
+    let output = run(
+        folder,
+        &["diff", "--name-only", "--relative", "-z", &base, "--"],
+    )?;
+    Ok(output
+        .split(|&byte| byte == 0)
+        .filter(|relative| !relative.is_empty())
+        .map(|relative| folder.join(&*String::from_utf8_lossy(relative)))
+        .collect())
+}
+
+// Uncommitted state of a file, as `git status` reports it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileStatus {
+    // Changed in the working tree (staged or not).
+    Modified,
+    // Changes staged, none left in the working tree.
+    Staged,
+    Untracked,
+}
+
+// Working tree of the repository `folder` belongs to (the folder holding
+// `.git`, a folder or the file of a worktree).
+pub fn repo_root(folder: &Path) -> Option<&Path> {
+    folder.ancestors().find(|dir| dir.join(".git").exists())
+}
+
+// Changed and untracked files of the repository at `repo`, by absolute path
+// (unchanged files are not listed).
+pub fn status(repo: &Path) -> io::Result<HashMap<PathBuf, FileStatus>> {
+    // Without optional locks, a status does not block git commands run meanwhile.
+    let output = run(
+        repo,
+        &[
+            "--no-optional-locks",
+            "status",
+            "--porcelain",
+            "-z",
+            "--untracked-files=all",
+        ],
+    )?;
+    Ok(parse_status(repo, &output))
+}
+
+// "XY path" entries (X: staged, Y: working tree), NUL-separated; a rename or
+// copy is followed by its source path.
+fn parse_status(repo: &Path, output: &[u8]) -> HashMap<PathBuf, FileStatus> {
+    let mut files = HashMap::new();
+    let mut entries = output.split(|&byte| byte == 0);
+    while let Some(entry) = entries.next() {
+        let (&[staged, worktree, b' '], relative) = entry.split_at(entry.len().min(3)) else {
+            continue;
+        };
+        if matches!(staged, b'R' | b'C') {
+            entries.next();
+        }
+        let status = match (staged, worktree) {
+            (b'?', b'?') => FileStatus::Untracked,
+            (b'!', b'!') => continue,
+            (_, b' ') => FileStatus::Staged,
+            _ => FileStatus::Modified,
+        };
+        files.insert(repo.join(&*String::from_utf8_lossy(relative)), status);
+    }
+    files
+}
+
+// A file of the tree of a commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeFile {
+    // Where the file would be in the working tree.
+    pub path: PathBuf,
+    // Blob id, read with `read_blobs`.
+    pub object: String,
+    pub size: u64,
+}
+
+// Files under `folder` in the tree of `revision` (branch, tag or commit).
+pub fn tree_files(folder: &Path, revision: &str) -> io::Result<Vec<TreeFile>> {
+    check_revision(revision)?;
+    // From a subfolder, ls-tree lists that subfolder only, relative to it.
+    let output = run(folder, &["ls-tree", "-r", "-z", "--long", revision])?;
+    Ok(parse_tree(folder, &output))
+}
+
+// Commit time of `revision`.
+pub fn commit_time(folder: &Path, revision: &str) -> io::Result<SystemTime> {
+    check_revision(revision)?;
+    let output = run(folder, &["log", "-1", "--format=%ct", revision])?;
+    String::from_utf8_lossy(&output)
+        .trim()
+        .parse()
+        .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+        .map_err(|_| io::Error::other(format!("no commit time for {}", revision)))
+}
+
+// A revision is passed as an argument: it must not read as an option.
+fn check_revision(revision: &str) -> io::Result<()> {
+    if revision.is_empty() || revision.starts_with('-') {
+        return Err(io::Error::other(format!(
+            "invalid revision \"{}\"",
+            revision
+        )));
+    }
+    Ok(())
+}
+
+// "<mode> <type> <object> <size>\t<path>" entries, NUL-separated; submodules
+// (commits) and links are left out.
+fn parse_tree(folder: &Path, output: &[u8]) -> Vec<TreeFile> {
+    output
+        .split(|&byte| byte == 0)
+        .filter_map(|entry| {
+            let tab = entry.iter().position(|&byte| byte == b'\t')?;
+            let info = String::from_utf8_lossy(&entry[..tab]);
+            let [mode, "blob", object, size] = info.split_whitespace().collect::<Vec<_>>()[..]
+            else {
+                return None;
+            };
+            if mode == "120000" {
+                return None;
+            }
+            Some(TreeFile {
+                path: folder.join(&*String::from_utf8_lossy(&entry[tab + 1..])),
+                object: object.to_string(),
+                size: size.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+// Contents of the `objects`, read in order by one `git cat-file --batch`;
+// `visit` gets the index and content of each one and returns None to stop.
+pub fn read_blobs(
+    folder: &Path,
+    objects: &[&str],
+    mut visit: impl FnMut(usize, &[u8]) -> Option<()>,
+) -> io::Result<()> {
+    let mut child = Command::new("git")
+        .args(["cat-file", "--batch"])
+        .current_dir(folder)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let (Some(mut stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+        return Err(io::Error::other("git cat-file has no pipes"));
+    };
+    // Written from another thread: git stops reading ids while its output is
+    // not read.
+    let ids: String = objects
+        .iter()
+        .map(|object| format!("{}\n", object))
+        .collect();
+    let writer = std::thread::spawn(move || stdin.write_all(ids.as_bytes()));
+
+    let mut reader = BufReader::new(stdout);
+    let mut header = String::new();
+    let mut content = Vec::new();
+    for index in 0..objects.len() {
+        header.clear();
+        if reader.read_line(&mut header)? == 0 {
+            break;
+        }
+        // "<object> <type> <size>", or "<object> missing".
+        let Some(size) = header
+            .split_whitespace()
+            .nth(2)
+            .and_then(|s| s.parse::<u64>().ok())
+        else {
+            continue;
+        };
+        content.clear();
+        // The content is followed by a newline.
+        (&mut reader).take(size + 1).read_to_end(&mut content)?;
+        content.truncate(size as usize);
+        if visit(index, &content).is_none() {
+            let _ = child.kill();
+            break;
+        }
+    }
+    drop(reader);
+    let _ = writer.join();
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_tracked_files() {
+        let root = std::env::temp_dir().join("quick-findr-git-tracked");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src")).unwrap();
+        if run(&root, &["init", "-q"]).is_err() {
+            return; // No git on this machine.
+        }
+        fs::write(root.join("src/main.rs"), "").unwrap();
+        fs::write(root.join("build.log"), "").unwrap();
+        run(&root, &["add", "src/main.rs"]).unwrap();
+
+        assert_eq!(tracked_files(&root).unwrap(), [root.join("src/main.rs")]);
+        assert_eq!(
+            tracked_files(&root.join("src")).unwrap(),
+            [root.join("src/main.rs")]
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_parse_status() {
+        let repo = Path::new("/repo");
+        let files = parse_status(
+            repo,
+            b" M src/main.rs\0M  README.md\0MM Cargo.toml\0R  new.rs\0old.rs\0?? notes/todo.txt\0",
+        );
+        assert_eq!(files.len(), 5);
+        assert_eq!(files[&repo.join("src/main.rs")], FileStatus::Modified);
+        assert_eq!(files[&repo.join("README.md")], FileStatus::Staged);
+        assert_eq!(files[&repo.join("Cargo.toml")], FileStatus::Modified);
+        assert_eq!(files[&repo.join("new.rs")], FileStatus::Staged);
+        assert_eq!(files[&repo.join("notes/todo.txt")], FileStatus::Untracked);
+    }
+
+    #[test]
+    fn test_parse_tree() {
+        let folder = Path::new("/repo/src");
+        let files = parse_tree(
+            folder,
+            b"100644 blob 1f2e3d     120\tmain.rs\0\
+              160000 commit 4a5b6c       -\tvendor/lib\0\
+              120000 blob 7d8e9f      10\tlatest\0\
+              100755 blob a1b2c3    4096\tbin/run.sh\0",
+        );
+        assert_eq!(
+            files,
+            [
+                TreeFile {
+                    path: folder.join("main.rs"),
+                    object: "1f2e3d".to_string(),
+                    size: 120,
+                },
+                TreeFile {
+                    path: folder.join("bin/run.sh"),
+                    object: "a1b2c3".to_string(),
+                    size: 4096,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tree_files_and_blobs() {
+        let root = std::env::temp_dir().join("quick-findr-git-tree");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src")).unwrap();
+        if run(&root, &["init", "-q"]).is_err() {
+            return; // No git on this machine.
+        }
+        fs::write(root.join("src/main.rs"), "fn old_name() {}\n").unwrap();
+        run(&root, &["add", "."]).unwrap();
+        if commit(&root, "first").is_err() {
+            let _ = fs::remove_dir_all(&root);
+            return;
+        }
+        fs::write(root.join("src/main.rs"), "fn new_name() {}\n").unwrap();
+
+        let files = tree_files(&root, "HEAD").unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, root.join("src/main.rs"));
+        let objects: Vec<&str> = files.iter().map(|file| file.object.as_str()).collect();
+        let mut contents = Vec::new();
+        read_blobs(&root, &objects, |index, content| {
+            contents.push((index, content.to_vec()));
+            Some(())
+        })
+        .unwrap();
+        assert_eq!(contents, [(0, b"fn old_name() {}\n".to_vec())]);
+        assert!(commit_time(&root, "HEAD").is_ok());
+        assert!(tree_files(&root, "--output=x").is_err());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_changed_files() {
+        let root = std::env::temp_dir().join("quick-findr-git-changed");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src")).unwrap();
+        if run(&root, &["init", "-q"]).is_err() {
+            return; // No git on this machine.
+        }
+        fs::write(root.join("README.md"), "v1").unwrap();
+        fs::write(root.join("src/lib.rs"), "v1").unwrap();
+        fs::write(root.join("src/main.rs"), "v1").unwrap();
+        run(&root, &["add", "."]).unwrap();
+        if commit(&root, "first").is_err() {
+            let _ = fs::remove_dir_all(&root);
+            return;
+        }
+        run(&root, &["branch", "base"]).unwrap();
+        fs::write(root.join("src/lib.rs"), "v2").unwrap();
+        run(&root, &["add", "."]).unwrap();
+        commit(&root, "second").unwrap();
+        fs::write(root.join("README.md"), "v2").unwrap();
+
+        assert_eq!(changed_files(&root, "0").unwrap(), [root.join("README.md")]);
+        assert_eq!(
+            changed_files(&root, "1").unwrap(),
+            [root.join("README.md"), root.join("src/lib.rs")]
+        );
+        assert_eq!(
+            changed_files(&root, "base").unwrap(),
+            [root.join("README.md"), root.join("src/lib.rs")]
+        );
+        assert_eq!(
+            changed_files(&root.join("src"), "base").unwrap(),
+            [root.join("src/lib.rs")]
+        );
+        assert!(changed_files(&root, "no-such-branch").is_err());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    // Commits the staged files with a throwaway identity.
+    fn commit(root: &Path, message: &str) -> io::Result<Vec<u8>> {
+        run(
+            root,
+            &[
+                "-c",
+                "user.name=t",
+                "-c",
+                "user.email=t@t",
+                "commit",
+                "-q",
+                "-m",
+                message,
+            ],
+        )
+    }
+}
@@ -0,0 +1,269 @@
+use crate::engine::{publish_results, update_ui, SearchResult, SearchToken};
+use crate::i18n::tr;
+use ignore::WalkBuilder;
+use md5::Md5;
+use rayon::prelude::*;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+}
+
+impl HashAlgorithm {
+    // Guesses the algorithm from the length of a hex digest.
+    pub fn from_hex_len(len: usize) -> Option<Self> {
+        match len {
+            32 => Some(Self::Md5),
+            40 => Some(Self::Sha1),
+            64 => Some(Self::Sha256),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Md5 => "MD5",
+            Self::Sha1 => "SHA-1",
+            Self::Sha256 => "SHA-256",
+        }
+    }
+}
+
+// Parses a pasted digest (spaces and case are ignored) and detects its algorithm.
+pub fn parse_hash(input: &str) -> Option<(HashAlgorithm, String)> {
+    let hex: String = input
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase();
+
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    HashAlgorithm::from_hex_len(hex.len()).map(|algorithm| (algorithm, hex))
+}
+
+// Computes the lowercase hex digest of a file.
+pub fn hash_file(path: &Path, algorithm: HashAlgorithm) -> io::Result<String> {
+    match algorithm {
+        HashAlgorithm::Md5 => digest_file::<Md5>(path),
+        HashAlgorithm::Sha1 => digest_file::<Sha1>(path),
+        HashAlgorithm::Sha256 => digest_file::<Sha256>(path),
+    }
+}
+
+fn digest_file<D: Digest>(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = D::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(to_hex(&hasher.finalize()))
+}
+
+pub struct Checksums {
+    pub md5: String,
+    pub sha256: String,
+}
+
+// MD5 and SHA-256 of a file in a single read.
+pub fn checksums(path: &Path) -> io::Result<Checksums> {
+    let mut file = File::open(path)?;
+    let mut md5 = Md5::new();
+    let mut sha256 = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        md5.update(&buffer[..read]);
+        sha256.update(&buffer[..read]);
+    }
+
+    Ok(Checksums {
+        md5: to_hex(&md5.finalize()),
+        sha256: to_hex(&sha256.finalize()),
+    })
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Lists the files under `root` whose digest equals `expected`.
+// When the size is known, other files are skipped without being read.
+pub fn find_files_by_hash(
+    root: &Path,
+    algorithm: HashAlgorithm,
+    expected: &str,
+    size: Option<u64>,
+    token: &SearchToken,
+) -> Vec<PathBuf> {
+    // No filters: a copy hidden in an ignored folder still counts.
+    let mut builder = WalkBuilder::new(root);
+    builder.standard_filters(false).threads(num_cpus::get());
+
+    builder
+        .build()
+        .par_bridge()
+        .filter_map(|entry| {
+            if token.is_cancelled() {
+                return None;
+            }
+
+            let dir_entry = entry.ok()?;
+            let metadata = dir_entry.metadata().ok()?;
+            if !metadata.is_file() || size.is_some_and(|s| s != metadata.len()) {
+                return None;
+            }
+
+            match hash_file(dir_entry.path(), algorithm) {
+                Ok(digest) if digest == expected => Some(dir_entry.into_path()),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+// Spawns the hash lookup in a separate thread.
+pub fn spawn_hash_search(
+    hash: String,
+    size: Option<u64>,
+    root_path: PathBuf,
+    sender: slint::Weak<crate::AppWindow>,
+    token: SearchToken,
+) {
+    std::thread::spawn(move || {
+        let start_time = Instant::now();
+
+        let (algorithm, expected) = match parse_hash(&hash) {
+            Some(parsed) => parsed,
+            None => {
+                update_ui(&token, &sender, move |window| {
+                    window.set_status_text(
+                        tr!("Error: Not a valid MD5, SHA-1 or SHA-256 hash").into(),
+                    );
+                    window.set_active_threads(0);
+                });
+                return;
+            }
+        };
+
+        let mut matches = find_files_by_hash(&root_path, algorithm, &expected, size, &token);
+        matches.sort();
+
+        let results: Vec<SearchResult> = matches
+            .into_iter()
+            .map(|path| SearchResult {
+                line_match: format!("{}: {}", algorithm.name(), expected),
+                size: std::fs::metadata(&path).map(|m| m.len()).ok(),
+                ..SearchResult::for_path(&path, &root_path)
+            })
+            .collect();
+
+        let total_results_count = publish_results(&token, &sender, results);
+
+        let duration = start_time.elapsed().as_millis() as u64;
+        update_ui(&token, &sender, move |window| {
+            window.set_status_text(
+                tr!(
+                    "Completed: {} files matching the {} hash in {}ms",
+                    total_results_count,
+                    algorithm.name(),
+                    duration
+                )
+                .into(),
+            );
+            window.set_active_threads(0);
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_root;
+    use std::fs;
+
+    #[test]
+    fn test_parse_hash_detects_algorithm() {
+        let md5 = parse_hash("D41D8CD98F00B204E9800998ECF8427E").unwrap();
+        assert_eq!(md5.0, HashAlgorithm::Md5);
+        assert_eq!(md5.1, "d41d8cd98f00b204e9800998ecf8427e");
+
+        let sha1 = parse_hash("da39a3ee 5e6b4b0d 3255bfef 95601890 afd80709").unwrap();
+        assert_eq!(sha1.0, HashAlgorithm::Sha1);
+
+        assert!(parse_hash("not a hash").is_none());
+        assert!(parse_hash("abcd").is_none());
+    }
+
+    #[test]
+    fn test_hash_file_known_digests() {
+        let root = temp_root("hash-digests");
+        let file = root.join("abc.txt");
+        fs::write(&file, "abc").unwrap();
+
+        assert_eq!(
+            hash_file(&file, HashAlgorithm::Md5).unwrap(),
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+        assert_eq!(
+            hash_file(&file, HashAlgorithm::Sha1).unwrap(),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        assert_eq!(
+            hash_file(&file, HashAlgorithm::Sha256).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        let both = checksums(&file).unwrap();
+        assert_eq!(both.md5, hash_file(&file, HashAlgorithm::Md5).unwrap());
+        assert_eq!(
+            both.sha256,
+            hash_file(&file, HashAlgorithm::Sha256).unwrap()
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_find_files_by_hash() {
+        let root = temp_root("hash-find");
+        fs::create_dir_all(root.join("nested")).unwrap();
+        fs::write(root.join("a.txt"), "abc").unwrap();
+        fs::write(root.join("nested/copy.bin"), "abc").unwrap();
+        fs::write(root.join("other.txt"), "abd").unwrap();
+
+        let token = SearchToken::default();
+        let expected = "900150983cd24fb0d6963f7d28e17f72";
+        let mut found = find_files_by_hash(&root, HashAlgorithm::Md5, expected, None, &token);
+        found.sort();
+        assert_eq!(
+            found,
+            vec![root.join("a.txt"), root.join("nested/copy.bin")]
+        );
+
+        // Size prefilter: nothing is 4 bytes long.
+        assert!(
+            find_files_by_hash(&root, HashAlgorithm::Md5, expected, Some(4), &token).is_empty()
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+}
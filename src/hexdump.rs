@@ -0,0 +1,78 @@
+// Hex/ASCII dump of the start of a binary file, for the detail pane: magic
+// bytes and embedded strings at a glance.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+// Bytes shown (4 KB).
+pub const PREVIEW_SIZE: usize = 4 * 1024;
+// Bytes per line, narrow enough for the detail pane.
+const BYTES_PER_LINE: usize = 8;
+
+// The first `max` bytes of a file (fewer when it is shorter).
+pub fn read_head(path: &Path, max: usize) -> io::Result<Vec<u8>> {
+    let mut head = Vec::with_capacity(max);
+    File::open(path)?.take(max as u64).read_to_end(&mut head)?;
+    Ok(head)
+}
+
+// A NUL byte in the head: text files have none, in any 8-bit encoding.
+pub fn looks_binary(head: &[u8]) -> bool {
+    head.contains(&0)
+}
+
+// One line per 8 bytes: offset, bytes in hex, then printable ASCII ("." for
+// the rest).
+//   00000000  4d 5a 90 00 03 00 00 00  MZ......
+pub fn dump(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() / BYTES_PER_LINE * 46);
+    for (index, line) in bytes.chunks(BYTES_PER_LINE).enumerate() {
+        out.push_str(&format!("{:08x} ", index * BYTES_PER_LINE));
+        for position in 0..BYTES_PER_LINE {
+            match line.get(position) {
+                Some(byte) => out.push_str(&format!(" {:02x}", byte)),
+                None => out.push_str("   "),
+            }
+        }
+        out.push_str("  ");
+        out.extend(line.iter().map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            }
+        }));
+        out.push('\n');
+    }
+    out.pop();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dump() {
+        let bytes = b"MZ\x90\x00\x03\x00\x00\x00This is";
+        assert_eq!(
+            dump(bytes),
+            "00000000  4d 5a 90 00 03 00 00 00  MZ......\n\
+             00000008  54 68 69 73 20 69 73     This is"
+        );
+        assert_eq!(dump(&[]), "");
+    }
+
+    #[test]
+    fn test_read_head() {
+        let path = std::env::temp_dir().join("quick-findr-hexdump.bin");
+        std::fs::write(&path, [0u8, 1, 2, 3, 4, 5]).unwrap();
+
+        let head = read_head(&path, 4).unwrap();
+        assert_eq!(head, vec![0, 1, 2, 3]);
+        assert!(looks_binary(&head));
+        assert!(!looks_binary(b"plain text"));
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,213 @@
+// Global hotkey that brings the window to the front from any application
+// ("Alt+Space", "Ctrl+Shift+F"). Only Windows has it (RegisterHotKey): a thread
+// of its own registers the hotkey and runs the message loop WM_HOTKEY is
+// posted to.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hotkey {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub win: bool,
+    // Windows virtual-key code.
+    pub key: u32,
+}
+
+impl Hotkey {
+    // "Alt+Space", "ctrl + shift + f"...; None when a part is unknown, or
+    // without a modifier (a plain key would be taken from every application).
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut hotkey = Hotkey {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            win: false,
+            key: 0,
+        };
+        let (modifiers, key) = text.rsplit_once('+')?;
+        for modifier in modifiers.split('+') {
+            match modifier.trim().to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => hotkey.ctrl = true,
+                "alt" => hotkey.alt = true,
+                "shift" | "maj" => hotkey.shift = true,
+                "win" => hotkey.win = true,
+                _ => return None,
+            }
+        }
+        hotkey.key = virtual_key(key.trim())?;
+        Some(hotkey)
+    }
+}
+
+// Virtual-key code of a key name: a letter, a digit, F1 to F24 or a named key.
+fn virtual_key(name: &str) -> Option<u32> {
+    let upper = name.to_ascii_uppercase();
+    let mut chars = upper.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if c.is_ascii_alphanumeric() {
+            return Some(c as u32);
+        }
+    }
+    if let Some(number) = upper.strip_prefix('F').and_then(|n| n.parse::<u32>().ok()) {
+        return (1..=24).contains(&number).then_some(0x70 + number - 1);
+    }
+    let key = match upper.as_str() {
+        "SPACE" | "ESPACE" => 0x20,
+        "ENTER" => 0x0d,
+        "TAB" => 0x09,
+        "ESC" | "ESCAPE" => 0x1b,
+        "INSERT" | "INS" => 0x2d,
+        "DELETE" | "DEL" | "SUPPR" => 0x2e,
+        "HOME" => 0x24,
+        "END" => 0x23,
+        "PAGEUP" => 0x21,
+        "PAGEDOWN" => 0x22,
+        "LEFT" => 0x25,
+        "UP" => 0x26,
+        "RIGHT" => 0x27,
+        "DOWN" => 0x28,
+        _ => return None,
+    };
+    Some(key)
+}
+
+#[cfg(windows)]
+mod win32 {
+    use super::Hotkey;
+    use std::io;
+    use std::sync::mpsc;
+    use std::thread::JoinHandle;
+    use windows_sys::Win32::System::Threading::GetCurrentThreadId;
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+        RegisterHotKey, UnregisterHotKey, MOD_ALT, MOD_CONTROL, MOD_NOREPEAT, MOD_SHIFT, MOD_WIN,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetMessageW, PostThreadMessageW, MSG, WM_HOTKEY, WM_QUIT,
+    };
+
+    const HOTKEY_ID: i32 = 1;
+
+    // A registered hotkey; dropping it unregisters it.
+    pub struct Registration {
+        thread_id: u32,
+        thread: Option<JoinHandle<()>>,
+    }
+
+    impl Registration {
+        pub fn new(hotkey: Hotkey, on_press: impl Fn() + Send + 'static) -> Result<Self, String> {
+            let mut modifiers = MOD_NOREPEAT;
+            for (held, modifier) in [
+                (hotkey.ctrl, MOD_CONTROL),
+                (hotkey.alt, MOD_ALT),
+                (hotkey.shift, MOD_SHIFT),
+                (hotkey.win, MOD_WIN),
+            ] {
+                if held {
+                    modifiers |= modifier;
+                }
+            }
+
+            let (sender, receiver) = mpsc::channel();
+            let thread = std::thread::spawn(move || {
+                // SAFETY: the hotkey belongs to this thread (no window), which
+                // unregisters it once its message loop gets WM_QUIT.
+                unsafe {
+                    if RegisterHotKey(std::ptr::null_mut(), HOTKEY_ID, modifiers, hotkey.key) == 0 {
+                        let _ = sender.send(Err(io::Error::last_os_error()));
+                        return;
+                    }
+                    let _ = sender.send(Ok(GetCurrentThreadId()));
+                    let mut message: MSG = std::mem::zeroed();
+                    while GetMessageW(&mut message, std::ptr::null_mut(), 0, 0) > 0 {
+                        if message.message == WM_HOTKEY {
+                            on_press();
+                        }
+                    }
+                    UnregisterHotKey(std::ptr::null_mut(), HOTKEY_ID);
+                }
+            });
+            match receiver.recv() {
+                Ok(Ok(thread_id)) => Ok(Self {
+                    thread_id,
+                    thread: Some(thread),
+                }),
+                Ok(Err(err)) => Err(format!("the hotkey is not available ({})", err)),
+                Err(_) => Err("the hotkey thread stopped".to_string()),
+            }
+        }
+    }
+
+    impl Drop for Registration {
+        fn drop(&mut self) {
+            // SAFETY: the thread has a message queue (RegisterHotKey made it);
+            // WM_QUIT ends its loop.
+            unsafe { PostThreadMessageW(self.thread_id, WM_QUIT, 0, 0) };
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+// Hotkey in use, unregistered when replaced.
+#[cfg(windows)]
+static REGISTERED: std::sync::Mutex<Option<win32::Registration>> = std::sync::Mutex::new(None);
+
+// Registers `hotkey` in place of the previous one (None: no hotkey); `on_press`
+// runs on the hotkey thread.
+#[cfg(windows)]
+pub fn set(hotkey: Option<Hotkey>, on_press: impl Fn() + Send + 'static) -> Result<(), String> {
+    let mut registered = REGISTERED
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    // Released first: the new hotkey may use the same keys.
+    *registered = None;
+    if let Some(hotkey) = hotkey {
+        *registered = Some(win32::Registration::new(hotkey, on_press)?);
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn set(hotkey: Option<Hotkey>, _on_press: impl Fn() + Send + 'static) -> Result<(), String> {
+    match hotkey {
+        Some(_) => Err("global hotkeys are only available on Windows".to_string()),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Hotkey::parse("Alt+Space"),
+            Some(Hotkey {
+                ctrl: false,
+                alt: true,
+                shift: false,
+                win: false,
+                key: 0x20,
+            })
+        );
+        let hotkey = Hotkey::parse(" ctrl + Shift + f ").unwrap();
+        assert!(hotkey.ctrl && hotkey.shift && !hotkey.alt);
+        assert_eq!(hotkey.key, 'F' as u32);
+        assert_eq!(
+            Hotkey::parse("Win+F12").map(|hotkey| hotkey.key),
+            Some(0x7b)
+        );
+        assert_eq!(
+            Hotkey::parse("Ctrl+1").map(|hotkey| hotkey.key),
+            Some('1' as u32)
+        );
+
+        assert_eq!(Hotkey::parse("Space"), None);
+        assert_eq!(Hotkey::parse("Alt+"), None);
+        assert_eq!(Hotkey::parse("Hyper+A"), None);
+        assert_eq!(Hotkey::parse("Alt+F25"), None);
+        assert_eq!(Hotkey::parse("Alt+Tab+X"), None);
+    }
+}
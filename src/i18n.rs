@@ -0,0 +1,311 @@
+// Language of the UI. The labels of the .slint files are translated by Slint
+// from the catalogs of lang/ (the sources are in French); the status messages
+// built here are written in English and go through `tr!`, which looks them up
+// in the French table below.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    // The language of the system: French if it is French, English otherwise.
+    #[default]
+    #[serde(rename = "system")]
+    System,
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "fr")]
+    French,
+}
+
+impl Language {
+    // Name used by the UI chips and by the catalogs ("system", "en", "fr").
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "en" => Self::English,
+            "fr" => Self::French,
+            _ => Self::System,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::English => "en",
+            Self::French => "fr",
+        }
+    }
+
+    // English or French.
+    pub fn resolve(self) -> Self {
+        match self {
+            Self::System => Self::from_locale(sys_locale::get_locale().as_deref()),
+            language => language,
+        }
+    }
+
+    // "fr-FR", "fr_CA.UTF-8"...
+    fn from_locale(locale: Option<&str>) -> Self {
+        match locale {
+            Some(locale) if locale.to_ascii_lowercase().starts_with("fr") => Self::French,
+            _ => Self::English,
+        }
+    }
+}
+
+// Status messages are shown in French (set from the settings at startup).
+static FRENCH: AtomicBool = AtomicBool::new(false);
+
+// Language of the next status messages (English or French, see `resolve`).
+pub fn set_language(language: Language) {
+    FRENCH.store(language == Language::French, Ordering::Relaxed);
+}
+
+// Language of the status messages: English or French.
+pub fn language() -> Language {
+    if FRENCH.load(Ordering::Relaxed) {
+        Language::French
+    } else {
+        Language::English
+    }
+}
+
+// `text` in the language of the UI (unchanged when it has no translation).
+pub fn translate(text: &'static str) -> &'static str {
+    if !FRENCH.load(Ordering::Relaxed) {
+        return text;
+    }
+    FRENCH_TEXTS
+        .iter()
+        .find(|(english, _)| *english == text)
+        .map_or(text, |(_, french)| french)
+}
+
+// `template` with its `{}` replaced in order by `args`.
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut args = args.iter();
+    let mut parts = template.split("{}");
+    let mut text = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        if let Some(arg) = args.next() {
+            text.push_str(&arg.to_string());
+        }
+        text.push_str(part);
+    }
+    text
+}
+
+// Status message in the language of the UI, written like `format!` with `{}`
+// placeholders only: `tr!("Indexing {}...", root.display())`.
+macro_rules! tr {
+    ($text:literal) => {
+        $crate::i18n::translate($text)
+    };
+    ($text:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::fill(
+            $crate::i18n::translate($text),
+            &[$(&$arg as &dyn std::fmt::Display),+],
+        )
+    };
+}
+pub(crate) use tr;
+
+// English status messages and their French translation.
+const FRENCH_TEXTS: &[(&str, &str)] = &[
+    ("Ready", "Prêt"),
+    ("Scanning...", "Scan en cours..."),
+    ("Refreshing...", "Actualisation..."),
+    ("Paused...", "En pause..."),
+    ("{} {} entries", "{} {} entrées"),
+    (", {} files searched", ", {} fichiers lus"),
+    (", {} matches", ", {} correspondances"),
+    (" ({}s, {} entries/s)", " ({} s, {} entrées/s)"),
+    ("Completed: {} results in {}ms", "Terminé : {} résultats en {} ms"),
+    ("at revision {}", "à la révision {}"),
+    ("from the master file table", "depuis la table MFT"),
+    ("from the index of {}", "depuis l'index du {}"),
+    ("truncated at {}", "tronqué à {}"),
+    ("{} large files not searched", "{} gros fichiers non lus"),
+    ("{} cloud-only files not searched", "{} fichiers cloud non lus"),
+    ("{} files searched up to line {} only", "{} fichiers lus jusqu'à la ligne {} seulement"),
+    ("{} files ruled out by the content index", "{} fichiers écartés par l'index de contenu"),
+    (" of {}", " du {}"),
+    ("{} paths skipped (access errors)", "{} chemins ignorés (erreurs d'accès)"),
+    ("Completed: {} files matching the {} hash in {}ms", "Terminé : {} fichiers avec l'empreinte {} en {} ms"),
+    ("Completed: {} similar images in {}ms", "Terminé : {} images similaires en {} ms"),
+    ("Completed: {} empty folders in {}ms", "Terminé : {} dossiers vides en {} ms"),
+    ("Completed: {} differences in {}ms", "Terminé : {} différences en {} ms"),
+    ("Distance: {}", "Distance : {}"),
+    ("Filtered: {} results", "Filtré : {} résultats"),
+    ("Filtered: {} of {} results", "Filtré : {} sur {} résultats"),
+    ("Looking for empty folders...", "Recherche des dossiers vides..."),
+    ("Hashing files...", "Calcul des empreintes..."),
+    ("Comparing images...", "Comparaison des images..."),
+    ("Comparing folders...", "Comparaison des dossiers..."),
+    ("Analysing {} files...", "Analyse de {} fichiers..."),
+    ("Indexing {}...", "Indexation de {}..."),
+    ("Indexing the content of {}...", "Indexation du contenu de {}..."),
+    ("Checking signature...", "Vérification de la signature..."),
+    ("Calculating...", "Calcul en cours..."),
+    ("Exported {} rows to {}", "{} lignes exportées vers {}"),
+    ("Exported {} results to {}", "{} résultats exportés vers {}"),
+    ("Exported {} entries to {}", "{} entrées exportées vers {}"),
+    ("Report saved to {}", "Rapport enregistré dans {}"),
+    ("Replaced {} lines in {} files ({} files skipped)", "{} lignes remplacées dans {} fichiers ({} fichiers ignorés)"),
+    ("{} lines in {} files", "{} lignes dans {} fichiers"),
+    ("Nothing to undo", "Rien à annuler"),
+    ("Restored {} files", "{} fichiers restaurés"),
+    ("Restored {} files ({} files edited since the replace were kept)", "{} fichiers restaurés ({} fichiers modifiés depuis le remplacement conservés)"),
+    ("Replacing {} lines...", "Remplacement de {} lignes..."),
+    ("Renamed {} files ({} failed)", "{} fichiers renommés ({} échecs)"),
+    ("{} files renamed, {} conflicts", "{} fichiers renommés, {} conflits"),
+    ("Removed {} from the list", "{} retiré de la liste"),
+    ("Zipped {} files into {} ({} failed)", "{} fichiers ajoutés à {} ({} échecs)"),
+    ("Zip cancelled", "Zip annulé"),
+    ("Deleted {} empty folders ({} failed)", "{} dossiers vides supprimés ({} échecs)"),
+    ("Index of {} removed", "Index de {} supprimé"),
+    ("Index of {} updated: {} files in {}ms", "Index de {} mis à jour : {} fichiers en {} ms"),
+    ("Content index of {} updated: {} files indexed, {} removed ({} in the index) in {}ms", "Index de contenu de {} mis à jour : {} fichiers indexés, {} supprimés ({} dans l'index) en {} ms"),
+    ("Index updated {} ({})", "Index mis à jour à {} ({})"),
+    ("{} file names", "{} noms de fichiers"),
+    ("{} files with content", "{} fichiers avec contenu"),
+    ("Copied {} to the clipboard", "{} copié dans le presse-papiers"),
+    ("Copied the path only: {}", "Seul le chemin a été copié : {}"),
+    ("Copied {} paths to the clipboard", "{} chemins copiés dans le presse-papiers"),
+    ("Copied {} results as Markdown", "{} résultats copiés en Markdown"),
+    ("Settings loaded from {}", "Paramètres chargés depuis {}"),
+    ("{} entries, integrity verified", "{} entrées, intégrité vérifiée"),
+    ("Warning: the log was modified at entry {} of {}", "Attention : le journal a été modifié à l'entrée {} sur {}"),
+    ("Warning: line {} of the log is not a valid entry ({} such lines)", "Attention : la ligne {} du journal n'est pas une entrée valide ({} lignes de ce type)"),
+    ("Warning: the log holds {} entries, {} were written", "Attention : le journal contient {} entrées, {} ont été écrites"),
+    ("Audit log not written: {}", "Journal d'audit non écrit : {}"),
+    ("Unable to save the session: {}", "Impossible d'enregistrer la session : {}"),
+    ("Error: {}", "Erreur : {}"),
+    ("Error: Invalid {}: {}", "Erreur : {} invalide : {}"),
+    ("Error: Invalid size filter: {}", "Erreur : filtre de taille invalide : {}"),
+    ("Error: Invalid content size limit: {}", "Erreur : limite de taille du contenu invalide : {}"),
+    ("Error: Invalid depth: {}", "Erreur : profondeur invalide : {}"),
+    ("Error: Invalid date range: {}", "Erreur : période invalide : {}"),
+    ("Error: Invalid file size", "Erreur : taille de fichier invalide"),
+    ("Error: Invalid search pattern", "Erreur : motif de recherche invalide"),
+    ("Error: Invalid regex: {}", "Erreur : regex invalide : {}"),
+    ("Error: Invalid regular expression", "Erreur : expression régulière invalide"),
+    ("Error: Invalid include pattern: {}", "Erreur : motif d'inclusion invalide : {}"),
+    ("Error: Invalid exclude pattern: {}", "Erreur : motif d'exclusion invalide : {}"),
+    ("Error: Invalid ignore file: {}", "Erreur : fichier d'exclusion invalide : {}"),
+    ("Error: Not a valid MD5, SHA-1 or SHA-256 hash", "Erreur : empreinte MD5, SHA-1 ou SHA-256 invalide"),
+    ("Error: Choose a reference image first", "Erreur : choisissez d'abord une image de référence"),
+    ("Error: Choose a folder to compare with", "Erreur : choisissez un dossier à comparer"),
+    ("Error: Unable to decode the reference image", "Erreur : impossible de décoder l'image de référence"),
+    ("Error: Export failed: {}", "Erreur : échec de l'export : {}"),
+    ("Error: Report failed: {}", "Erreur : échec du rapport : {}"),
+    ("Error: Replace failed: {}", "Erreur : échec du remplacement : {}"),
+    ("Error: Undo failed: {}", "Erreur : échec de l'annulation : {}"),
+    ("Error: Zip failed: {}", "Erreur : échec du zip : {}"),
+    ("Error: files inside an archive cannot be renamed", "Erreur : les fichiers d'une archive ne peuvent pas être renommés"),
+    ("Error: files inside an archive cannot be edited", "Erreur : les fichiers d'une archive ne peuvent pas être modifiés"),
+    ("Error: files inside an archive cannot be copied", "Erreur : les fichiers d'une archive ne peuvent pas être copiés"),
+    ("Error: The maximum result count must be a whole number (0: no limit)", "Erreur : le nombre maximal de résultats doit être un entier (0 : illimité)"),
+    ("Error: The line limit must be a whole number (0: no limit)", "Erreur : la limite de lignes doit être un entier (0 : illimité)"),
+    ("Error: Cannot copy the paths: {}", "Erreur : impossible de copier les chemins : {}"),
+    ("Error: Cannot copy the table: {}", "Erreur : impossible de copier le tableau : {}"),
+    ("Error: Unable to start the search threads: {}", "Erreur : impossible de démarrer les threads de recherche : {}"),
+    ("Error: Unable to list the changed git files: {}", "Erreur : impossible de lister les fichiers git modifiés : {}"),
+    ("Error: Unable to list the git files: {}", "Erreur : impossible de lister les fichiers git : {}"),
+    ("Error: Unable to read the git revision: {}", "Erreur : impossible de lire la révision git : {}"),
+    ("Error: Unable to remove the index: {}", "Erreur : impossible de supprimer l'index : {}"),
+    ("Error: Unable to remove the content index: {}", "Erreur : impossible de supprimer l'index de contenu : {}"),
+    ("Error: Unable to index {}: {}", "Erreur : impossible d'indexer {} : {}"),
+    ("Error: Unable to watch {}: {}", "Erreur : impossible de surveiller {} : {}"),
+    ("Error: Unable to update the index: {}", "Erreur : impossible de mettre à jour l'index : {}"),
+    ("Error: Unable to update the content index: {}", "Erreur : impossible de mettre à jour l'index de contenu : {}"),
+    ("Error: Unable to save settings: {}", "Erreur : impossible d'enregistrer les paramètres : {}"),
+    ("Error: Unable to read the log: {}", "Erreur : impossible de lire le journal : {}"),
+    ("Error: Unknown hotkey: {}", "Erreur : raccourci inconnu : {}"),
+    ("Error: Unable to open a new window: {}", "Erreur : impossible d'ouvrir une nouvelle fenêtre : {}"),
+    ("Folder", "Dossier"),
+    ("File", "Fichier"),
+    ("Size", "Taille"),
+    ("Created", "Créé"),
+    ("Modified", "Modifié"),
+    ("Last accessed", "Dernier accès"),
+    ("Read-only", "Lecture seule"),
+    ("yes", "oui"),
+    ("no", "non"),
+    ("{} bytes", "{} octets"),
+    ("{} ({} bytes)", "{} ({} octets)"),
+    ("{} B", "{} o"),
+    ("KB", "Ko"),
+    ("MB", "Mo"),
+    ("GB", "Go"),
+    ("TB", "To"),
+    ("Quick Findr: {}", "Quick Findr : {}"),
+    ("Search report", "Rapport de recherche"),
+    ("none", "aucune"),
+    ("Search", "Recherche"),
+    ("Generated on", "Généré le"),
+    ("Results", "Résultats"),
+    ("Name", "Nom"),
+    ("Path", "Chemin"),
+    ("Line", "Ligne"),
+    ("Match", "Correspondance"),
+    ("{}: {}", "{} : {}"),
+    ("Content", "Contenu"),
+    ("Match case", "Casse"),
+    ("Whole word", "Mot entier"),
+    ("Full path", "Chemin complet"),
+    ("Ignore accents", "Sans accents"),
+    ("Include ignored folders", "Inclure dossiers ignorés"),
+    ("Git files", "Fichiers git"),
+    ("Fuzzy", "Flou"),
+    ("Language: {}", "Langage : {}"),
+    ("Size: {}", "Taille : {}"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(fill("{} of {} results", &[&3, &"10"]), "3 of 10 results");
+        assert_eq!(fill("Ready", &[]), "Ready");
+        assert_eq!(fill("{} and {}", &[&1]), "1 and ");
+    }
+
+    #[test]
+    fn test_french_texts() {
+        for (index, (english, french)) in FRENCH_TEXTS.iter().enumerate() {
+            assert_eq!(
+                english.matches("{}").count(),
+                french.matches("{}").count(),
+                "{}",
+                english
+            );
+            assert!(
+                FRENCH_TEXTS[..index]
+                    .iter()
+                    .all(|(other, _)| other != english),
+                "{} is listed twice",
+                english
+            );
+        }
+    }
+
+    #[test]
+    fn test_language() {
+        assert_eq!(
+            Language::from_name(Language::French.name()),
+            Language::French
+        );
+        assert_eq!(Language::from_name("de"), Language::System);
+        assert_eq!(Language::English.resolve(), Language::English);
+        assert_eq!(Language::from_locale(Some("fr-CA")), Language::French);
+        assert_eq!(
+            Language::from_locale(Some("en_US.UTF-8")),
+            Language::English
+        );
+        assert_eq!(Language::from_locale(None), Language::English);
+    }
+}
@@ -0,0 +1,348 @@
+use crate::engine::{apply_ignore_files, build_overrides, ignored_dirs, overrides_exclude};
+use ignore::overrides::Override;
+use ignore::WalkBuilder;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+// Every file and folder under an indexed root, as the walker of a search would
+// list them (hidden files and ignored directories skipped), stored on disk so
+// that filename-only searches do not walk the folder again.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileIndex {
+    pub root: PathBuf,
+    // A search with the other ignore files setting walks the disk instead.
+    pub respect_gitignore: bool,
+    pub built: SystemTime,
+    pub entries: Vec<IndexEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    // Relative to the root (paths that are not valid Unicode are not indexed).
+    pub relative: String,
+    // 0 for a folder.
+    pub size: u64,
+    // Folders are listed for folder-name matches.
+    pub is_dir: bool,
+}
+
+// Indexes loaded by searches, kept for the next ones.
+static LOADED: Mutex<Vec<Arc<FileIndex>>> = Mutex::new(Vec::new());
+
+impl FileIndex {
+    pub fn build(root: &Path, respect_gitignore: bool) -> Result<Self, ignore::Error> {
+        let mut builder = WalkBuilder::new(root);
+        builder
+            .hidden(true)
+            .overrides(build_overrides(root, &ignored_dirs(), &[], "")?);
+        apply_ignore_files(&mut builder, respect_gitignore)?;
+
+        let entries = builder
+            .build()
+            .par_bridge()
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let file_type = entry.file_type()?;
+                if entry.depth() == 0 || !(file_type.is_file() || file_type.is_dir()) {
+                    return None;
+                }
+                let relative = entry.path().strip_prefix(root).ok()?.to_str()?.to_string();
+                let is_dir = file_type.is_dir();
+                let size = if is_dir {
+                    0
+                } else {
+                    entry.metadata().ok()?.len()
+                };
+                Some(IndexEntry {
+                    relative,
+                    size,
+                    is_dir,
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            respect_gitignore,
+            built: SystemTime::now(),
+            entries,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let bytes = bincode::serialize(self).map_err(io::Error::other)?;
+        fs::write(path, bytes)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        bincode::deserialize(&fs::read(path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    // Entries inside `folder` (the root itself or one of its subfolders): path,
+    // size and whether it is a folder.
+    pub fn entries_under<'a>(
+        &'a self,
+        folder: &'a Path,
+    ) -> impl ParallelIterator<Item = (PathBuf, u64, bool)> + 'a {
+        let prefix = folder.strip_prefix(&self.root).unwrap_or(Path::new(""));
+        self.entries
+            .par_iter()
+            .filter(move |entry| {
+                let relative = Path::new(&entry.relative);
+                relative.starts_with(prefix) && relative != prefix
+            })
+            .map(|entry| (self.root.join(&entry.relative), entry.size, entry.is_dir))
+    }
+
+    pub fn file_count(&self) -> usize {
+        self.entries.iter().filter(|entry| !entry.is_dir).count()
+    }
+}
+
+pub fn index_dir() -> PathBuf {
+    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("quick-findr");
+    path.push("index");
+    path
+}
+
+// One file per root, named after a digest of its path.
+fn index_file(dir: &Path, root: &Path) -> PathBuf {
+    let digest = Sha256::digest(root.to_string_lossy().as_bytes());
+    let name: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    dir.join(format!("{}.bin", name))
+}
+
+// Builds and saves the index of `root`; returns the number of indexed files.
+pub fn update(dir: &Path, root: &Path, respect_gitignore: bool) -> io::Result<usize> {
+    let index = FileIndex::build(root, respect_gitignore).map_err(io::Error::other)?;
+    index.save(&index_file(dir, root))?;
+    let count = index.file_count();
+
+    let mut loaded = LOADED.lock().unwrap_or_else(|e| e.into_inner());
+    loaded.retain(|cached| cached.root != root);
+    loaded.push(Arc::new(index));
+    Ok(count)
+}
+
+// Applies file system changes (created, modified, deleted or renamed paths) to
+// the index of `root`. Returns the new file count, None when `root` has no index.
+// .gitignore rules only apply to new folders; new files are checked at the
+// next full update.
+pub fn apply_changes(dir: &Path, root: &Path, changed: &[PathBuf]) -> io::Result<Option<usize>> {
+    let file = index_file(dir, root);
+    let mut loaded = LOADED.lock().unwrap_or_else(|e| e.into_inner());
+    let current = match loaded.iter().find(|cached| cached.root == root) {
+        Some(cached) => cached.clone(),
+        None => match FileIndex::load(&file) {
+            Ok(index) => Arc::new(index),
+            Err(_) => return Ok(None),
+        },
+    };
+
+    // Entries at or under a changed path are dropped, then what exists now is
+    // listed again (a changed folder inside another one is covered by it).
+    let changed: HashSet<&Path> = changed
+        .iter()
+        .filter_map(|path| path.strip_prefix(root).ok())
+        .filter(|relative| !relative.as_os_str().is_empty())
+        .collect();
+    let mut entries: Vec<IndexEntry> = current
+        .entries
+        .iter()
+        .filter(|entry| {
+            !Path::new(&entry.relative)
+                .ancestors()
+                .any(|ancestor| changed.contains(ancestor))
+        })
+        .cloned()
+        .collect();
+
+    let overrides = build_overrides(root, &ignored_dirs(), &[], "").map_err(io::Error::other)?;
+    for relative in &changed {
+        let inside_other = relative
+            .ancestors()
+            .skip(1)
+            .any(|ancestor| changed.contains(ancestor));
+        let path = root.join(relative);
+        if inside_other || !is_listed(&overrides, root, &path) {
+            continue;
+        }
+        if path.is_dir() {
+            let folder =
+                FileIndex::build(&path, current.respect_gitignore).map_err(io::Error::other)?;
+            if let Some(relative) = relative.to_str() {
+                entries.push(IndexEntry {
+                    relative: relative.to_string(),
+                    size: 0,
+                    is_dir: true,
+                });
+            }
+            entries.extend(folder.entries.into_iter().filter_map(|entry| {
+                let relative = relative.join(&entry.relative).to_str()?.to_string();
+                Some(IndexEntry { relative, ..entry })
+            }));
+        } else if let (Ok(metadata), Some(relative)) = (fs::metadata(&path), relative.to_str()) {
+            if metadata.is_file() {
+                entries.push(IndexEntry {
+                    relative: relative.to_string(),
+                    size: metadata.len(),
+                    is_dir: false,
+                });
+            }
+        }
+    }
+
+    let index = FileIndex {
+        root: root.to_path_buf(),
+        respect_gitignore: current.respect_gitignore,
+        built: SystemTime::now(),
+        entries,
+    };
+    index.save(&file)?;
+    let count = index.file_count();
+    loaded.retain(|cached| cached.root != root);
+    loaded.push(Arc::new(index));
+    Ok(Some(count))
+}
+
+// Whether the walker of an update would list `path`: no hidden (dot) name
+// below the root and no excluded default folder.
+pub fn is_listed(overrides: &Override, root: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return false;
+    };
+    let hidden = relative
+        .components()
+        .any(|component| component.as_os_str().to_string_lossy().starts_with('.'));
+    !hidden && !overrides_exclude(overrides, root, path)
+}
+
+pub fn exists(dir: &Path, root: &Path) -> bool {
+    index_file(dir, root).is_file()
+}
+
+pub fn remove(dir: &Path, root: &Path) -> io::Result<()> {
+    LOADED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|cached| cached.root != root);
+    match fs::remove_file(index_file(dir, root)) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+// .gitignore setting of the saved index of `root`, if there is one.
+pub fn saved_gitignore_setting(dir: &Path, root: &Path) -> Option<bool> {
+    FileIndex::load(&index_file(dir, root))
+        .ok()
+        .map(|index| index.respect_gitignore)
+}
+
+// Index covering `folder`: its own or the one of a parent folder.
+pub fn lookup(dir: &Path, folder: &Path, respect_gitignore: bool) -> Option<Arc<FileIndex>> {
+    let mut loaded = LOADED.lock().unwrap_or_else(|e| e.into_inner());
+    for root in folder.ancestors() {
+        let index = match loaded.iter().find(|cached| cached.root == root) {
+            Some(cached) => cached.clone(),
+            None => {
+                let Ok(index) = FileIndex::load(&index_file(dir, root)) else {
+                    continue;
+                };
+                let index = Arc::new(index);
+                loaded.push(index.clone());
+                index
+            }
+        };
+        if index.respect_gitignore == respect_gitignore {
+            return Some(index);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_root;
+
+    #[test]
+    fn test_update_and_lookup_from_subfolder() {
+        let root = temp_root("index-lookup");
+        let dir = root.join("index");
+        let tree = root.join("tree");
+        fs::create_dir_all(tree.join("docs")).unwrap();
+        fs::create_dir_all(tree.join("node_modules")).unwrap();
+        fs::write(tree.join("readme.md"), "hello").unwrap();
+        fs::write(tree.join("docs").join("guide.md"), "").unwrap();
+        fs::write(tree.join("node_modules").join("dep.js"), "").unwrap();
+
+        assert_eq!(update(&dir, &tree, false).unwrap(), 2);
+        let index = lookup(&dir, &tree.join("docs"), false).unwrap();
+        let entries: Vec<(PathBuf, u64, bool)> = index.entries_under(&tree.join("docs")).collect();
+        assert_eq!(
+            entries,
+            vec![(tree.join("docs").join("guide.md"), 0, false)]
+        );
+        let mut folders: Vec<PathBuf> = index
+            .entries_under(&tree)
+            .filter(|(_, _, is_dir)| *is_dir)
+            .map(|(path, _, _)| path)
+            .collect();
+        folders.sort();
+        assert_eq!(folders, vec![tree.join("docs")]);
+        assert_eq!(saved_gitignore_setting(&dir, &tree), Some(false));
+
+        // Another .gitignore setting, or an unindexed folder: no index.
+        assert!(lookup(&dir, &tree, true).is_none());
+        assert!(lookup(&dir, &root, false).is_none());
+
+        // Incremental changes: a new file, a deleted one, a new folder.
+        fs::write(tree.join("todo.txt"), "").unwrap();
+        fs::remove_file(tree.join("readme.md")).unwrap();
+        fs::create_dir_all(tree.join("notes/2024")).unwrap();
+        fs::write(tree.join("notes/2024/june.md"), "").unwrap();
+        fs::write(tree.join(".hidden"), "").unwrap();
+        let changed = [
+            "todo.txt",
+            "readme.md",
+            "notes",
+            "notes/2024/june.md",
+            ".hidden",
+        ]
+        .map(|relative| tree.join(relative));
+        assert_eq!(apply_changes(&dir, &tree, &changed).unwrap(), Some(3));
+        let index = lookup(&dir, &tree, false).unwrap();
+        let mut paths: Vec<PathBuf> = index
+            .entries_under(&tree)
+            .map(|(path, _, _)| path)
+            .collect();
+        paths.sort();
+        let expected = [
+            "docs",
+            "docs/guide.md",
+            "notes",
+            "notes/2024",
+            "notes/2024/june.md",
+            "todo.txt",
+        ];
+        assert_eq!(paths, expected.map(|relative| tree.join(relative)));
+        assert_eq!(apply_changes(&dir, &root, &changed).unwrap(), None);
+
+        remove(&dir, &tree).unwrap();
+        assert!(lookup(&dir, &tree, false).is_none());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
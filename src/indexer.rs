@@ -0,0 +1,414 @@
+// Opt-in full-text index of favorite folders (tantivy, trigrams of the
+// lowercased text). A content search reads only the indexed files whose text
+// holds every trigram of the query; files changed since the last update, or
+// not indexed (binary, too large, not UTF-8), are read as usual.
+
+use crate::email;
+use crate::engine::{build_overrides, ignored_dirs, is_likely_binary};
+use crate::index;
+use ignore::WalkBuilder;
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tantivy::collector::DocSetCollector;
+use tantivy::directory::MmapDirectory;
+use tantivy::query::{BooleanQuery, Occur, Query, TermQuery};
+use tantivy::schema::{
+    Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, Value, STORED, STRING,
+};
+use tantivy::tokenizer::{LowerCaser, NgramTokenizer, TextAnalyzer};
+use tantivy::{doc, Index, IndexWriter, TantivyDocument, Term};
+
+// Larger files are not indexed (the search reads them).
+const MAX_INDEXED_SIZE: u64 = 16 * 1024 * 1024;
+const WRITER_MEMORY: usize = 64 * 1024 * 1024;
+const TRIGRAM_TOKENIZER: &str = "trigram";
+// Indexed files and their modification stamps, next to the tantivy files.
+const MANIFEST: &str = "files.bin";
+
+struct Fields {
+    path: Field,
+    content: Field,
+}
+
+#[derive(Debug, Default)]
+pub struct UpdateReport {
+    // Files (re)indexed by this update.
+    pub indexed: usize,
+    pub removed: usize,
+    // Files in the index afterwards.
+    pub files: usize,
+}
+
+pub fn index_dir() -> PathBuf {
+    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("quick-findr");
+    path.push("content-index");
+    path
+}
+
+// One folder per root, named after a digest of its path.
+fn folder_dir(dir: &Path, root: &Path) -> PathBuf {
+    let digest = Sha256::digest(root.to_string_lossy().as_bytes());
+    dir.join(
+        digest[..8]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>(),
+    )
+}
+
+fn stamp(modified: SystemTime) -> u64 {
+    modified
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+fn open_index(path: &Path) -> tantivy::Result<(Index, Fields)> {
+    let mut builder = Schema::builder();
+    let path_field = builder.add_text_field("path", STRING | STORED);
+    let indexing = TextFieldIndexing::default()
+        .set_tokenizer(TRIGRAM_TOKENIZER)
+        .set_index_option(IndexRecordOption::Basic);
+    let content = builder.add_text_field(
+        "content",
+        TextOptions::default().set_indexing_options(indexing),
+    );
+
+    fs::create_dir_all(path)?;
+    let index = Index::open_or_create(MmapDirectory::open(path)?, builder.build())?;
+    index.tokenizers().register(
+        TRIGRAM_TOKENIZER,
+        TextAnalyzer::builder(NgramTokenizer::new(3, 3, false)?)
+            .filter(LowerCaser)
+            .build(),
+    );
+    Ok((
+        index,
+        Fields {
+            path: path_field,
+            content,
+        },
+    ))
+}
+
+fn load_manifest(path: &Path) -> HashMap<String, u64> {
+    fs::read(path.join(MANIFEST))
+        .ok()
+        .and_then(|bytes| bincode::deserialize(&bytes).ok())
+        .unwrap_or_default()
+}
+
+// A file the index may hold, as listed by a walk.
+struct ListedFile {
+    path: String,
+    size: u64,
+    modified: u64,
+}
+
+impl ListedFile {
+    fn new(path: &Path, metadata: &fs::Metadata) -> Option<Self> {
+        let extension = path.extension().unwrap_or_default();
+        if !metadata.is_file() || is_likely_binary(&extension.to_string_lossy()) {
+            return None;
+        }
+        Some(Self {
+            path: path.to_str()?.to_string(),
+            size: metadata.len(),
+            modified: stamp(metadata.modified().ok()?),
+        })
+    }
+}
+
+fn list_files(folder: &Path) -> io::Result<Vec<ListedFile>> {
+    let mut builder = WalkBuilder::new(folder);
+    builder
+        .hidden(true)
+        .overrides(build_overrides(folder, &ignored_dirs(), &[], "").map_err(io::Error::other)?);
+    Ok(builder
+        .build()
+        .par_bridge()
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            ListedFile::new(entry.path(), &entry.metadata().ok()?)
+        })
+        .collect())
+}
+
+// Brings the index of `root` up to date: new and modified files are
+// (re)indexed, deleted ones dropped.
+pub fn update(dir: &Path, root: &Path) -> io::Result<UpdateReport> {
+    let path = folder_dir(dir, root);
+    let manifest = load_manifest(&path);
+    let files = list_files(root)?;
+
+    let seen: HashSet<&str> = files.iter().map(|file| file.path.as_str()).collect();
+    let removed: Vec<String> = manifest
+        .keys()
+        .filter(|indexed| !seen.contains(indexed.as_str()))
+        .cloned()
+        .collect();
+    let changed: Vec<&ListedFile> = files
+        .iter()
+        .filter(|file| manifest.get(&file.path) != Some(&file.modified))
+        .collect();
+    write_changes(&path, manifest, &changed, &removed)
+}
+
+// Applies file system changes (created, modified, deleted or renamed paths)
+// to the index of `root`; None when `root` has no index.
+pub fn update_files(
+    dir: &Path,
+    root: &Path,
+    changed: &[PathBuf],
+) -> io::Result<Option<UpdateReport>> {
+    let path = folder_dir(dir, root);
+    if !path.join(MANIFEST).is_file() {
+        return Ok(None);
+    }
+    let manifest = load_manifest(&path);
+    let changed: HashSet<&Path> = changed
+        .iter()
+        .map(PathBuf::as_path)
+        .filter(|changed| changed.starts_with(root) && *changed != root)
+        .collect();
+
+    // What exists now at the changed paths (a changed folder inside another
+    // one is covered by it).
+    let overrides = build_overrides(root, &ignored_dirs(), &[], "").map_err(io::Error::other)?;
+    let mut files = Vec::new();
+    for &changed_path in &changed {
+        let inside_other = changed_path
+            .ancestors()
+            .skip(1)
+            .any(|ancestor| changed.contains(ancestor));
+        if inside_other || !index::is_listed(&overrides, root, changed_path) {
+            continue;
+        }
+        if changed_path.is_dir() {
+            files.extend(list_files(changed_path)?);
+        } else if let Some(file) = fs::metadata(changed_path)
+            .ok()
+            .and_then(|metadata| ListedFile::new(changed_path, &metadata))
+        {
+            files.push(file);
+        }
+    }
+
+    // Indexed files at or under a changed path that are gone.
+    let seen: HashSet<&str> = files.iter().map(|file| file.path.as_str()).collect();
+    let removed: Vec<String> = manifest
+        .keys()
+        .filter(|indexed| {
+            !seen.contains(indexed.as_str())
+                && Path::new(indexed)
+                    .ancestors()
+                    .any(|ancestor| changed.contains(ancestor))
+        })
+        .cloned()
+        .collect();
+    let files: Vec<&ListedFile> = files.iter().collect();
+    write_changes(&path, manifest, &files, &removed).map(Some)
+}
+
+fn write_changes(
+    path: &Path,
+    mut manifest: HashMap<String, u64>,
+    changed: &[&ListedFile],
+    removed: &[String],
+) -> io::Result<UpdateReport> {
+    let (index, fields) = open_index(path).map_err(io::Error::other)?;
+    let mut writer: IndexWriter = index.writer(WRITER_MEMORY).map_err(io::Error::other)?;
+    let mut report = UpdateReport::default();
+
+    for file in removed {
+        writer.delete_term(Term::from_field_text(fields.path, file));
+        if manifest.remove(file).is_some() {
+            report.removed += 1;
+        }
+    }
+
+    let indexed: Vec<(&str, Option<u64>)> = changed
+        .par_iter()
+        .map(|file| {
+            writer.delete_term(Term::from_field_text(fields.path, &file.path));
+            // Legacy encodings and UTF-16 are left to the search; emails are
+            // indexed as the search reads them (decoded).
+            let text = (file.size <= MAX_INDEXED_SIZE)
+                .then(|| {
+                    let path = Path::new(&file.path);
+                    let extension = path.extension().unwrap_or_default();
+                    if email::is_email(&extension.to_string_lossy()) {
+                        email::text(path).ok()
+                    } else {
+                        fs::read_to_string(&file.path).ok()
+                    }
+                })
+                .flatten();
+            let added = text.is_some_and(|text| {
+                writer
+                    .add_document(doc!(fields.path => file.path.as_str(), fields.content => text))
+                    .is_ok()
+            });
+            (file.path.as_str(), added.then_some(file.modified))
+        })
+        .collect();
+    for (file, modified) in indexed {
+        match modified {
+            Some(modified) => {
+                manifest.insert(file.to_string(), modified);
+                report.indexed += 1;
+            }
+            None => {
+                manifest.remove(file);
+            }
+        }
+    }
+
+    writer.commit().map_err(io::Error::other)?;
+    let bytes = bincode::serialize(&manifest).map_err(io::Error::other)?;
+    fs::write(path.join(MANIFEST), bytes)?;
+    report.files = manifest.len();
+    Ok(report)
+}
+
+pub fn exists(dir: &Path, root: &Path) -> bool {
+    folder_dir(dir, root).join(MANIFEST).is_file()
+}
+
+pub fn remove(dir: &Path, root: &Path) -> io::Result<()> {
+    match fs::remove_dir_all(folder_dir(dir, root)) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+// Indexed files that may contain a literal, and the stamps of all indexed files.
+pub struct ContentCandidates {
+    indexed: HashMap<String, u64>,
+    matching: HashSet<String>,
+    // Time of the last update of the index.
+    pub updated: Option<SystemTime>,
+}
+
+impl ContentCandidates {
+    // Whether the file is indexed, unchanged since, and cannot contain the query.
+    pub fn rules_out(&self, path: &Path, modified: Option<SystemTime>) -> bool {
+        let Some(path) = path.to_str() else {
+            return false;
+        };
+        !self.matching.contains(path)
+            && self
+                .indexed
+                .get(path)
+                .is_some_and(|indexed| modified.map(stamp) == Some(*indexed))
+    }
+}
+
+// Candidates for `literal` from the index of `folder` (or of a parent folder).
+// None without an index, or for a literal shorter than a trigram.
+pub fn candidates(dir: &Path, folder: &Path, literal: &str) -> Option<ContentCandidates> {
+    let lowered = literal.to_lowercase();
+    let chars: Vec<char> = lowered.chars().collect();
+    if chars.len() < 3 {
+        return None;
+    }
+    let path = folder
+        .ancestors()
+        .map(|root| folder_dir(dir, root))
+        .find(|path| path.join(MANIFEST).is_file())?;
+    let (index, fields) = open_index(&path).ok()?;
+
+    let trigrams: HashSet<String> = chars.windows(3).map(|w| w.iter().collect()).collect();
+    let query = BooleanQuery::new(
+        trigrams
+            .iter()
+            .map(|trigram| {
+                let term = Term::from_field_text(fields.content, trigram);
+                let query: Box<dyn Query> =
+                    Box::new(TermQuery::new(term, IndexRecordOption::Basic));
+                (Occur::Must, query)
+            })
+            .collect(),
+    );
+
+    let searcher = index.reader().ok()?.searcher();
+    let matching = searcher
+        .search(&query, &DocSetCollector)
+        .ok()?
+        .into_iter()
+        .filter_map(|address| {
+            let doc: TantivyDocument = searcher.doc(address).ok()?;
+            Some(doc.get_first(fields.path)?.as_str()?.to_string())
+        })
+        .collect();
+
+    Some(ContentCandidates {
+        indexed: load_manifest(&path),
+        matching,
+        updated: fs::metadata(path.join(MANIFEST))
+            .and_then(|metadata| metadata.modified())
+            .ok(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_root;
+
+    fn modified(path: &Path) -> Option<SystemTime> {
+        fs::metadata(path).ok()?.modified().ok()
+    }
+
+    #[test]
+    fn test_update_and_candidates() {
+        let root = temp_root("indexer-candidates");
+        let dir = root.join("index");
+        let tree = root.join("tree");
+        fs::create_dir_all(&tree).unwrap();
+        let (todo, other, legacy) = (tree.join("a.txt"), tree.join("b.txt"), tree.join("c.txt"));
+        fs::write(&todo, "line one\n// TODO: fix me\n").unwrap();
+        fs::write(&other, "nothing to see\n").unwrap();
+        fs::write(&legacy, b"caf\xe9 todo\n").unwrap();
+
+        let report = update(&dir, &tree).unwrap();
+        assert_eq!((report.indexed, report.files), (2, 2));
+
+        let found = candidates(&dir, &tree, "todo:").unwrap();
+        assert!(!found.rules_out(&todo, modified(&todo)));
+        assert!(found.rules_out(&other, modified(&other)));
+        // Not UTF-8: not indexed, so never ruled out.
+        assert!(!found.rules_out(&legacy, modified(&legacy)));
+        // Too short for a trigram.
+        assert!(candidates(&dir, &tree, "to").is_none());
+
+        // A deleted file leaves the index; an unchanged one is not reindexed.
+        fs::remove_file(&other).unwrap();
+        let report = update(&dir, &tree).unwrap();
+        assert_eq!((report.indexed, report.removed, report.files), (0, 1, 1));
+
+        // Incremental changes: the TODO is fixed, a new file has one.
+        fs::write(&todo, "line one\n").unwrap();
+        let added = tree.join("sub").join("d.txt");
+        fs::create_dir_all(added.parent().unwrap()).unwrap();
+        fs::write(&added, "todo: test\n").unwrap();
+        let changed = [todo.clone(), other.clone(), tree.join("sub")];
+        let report = update_files(&dir, &tree, &changed).unwrap().unwrap();
+        assert_eq!((report.indexed, report.removed, report.files), (2, 0, 2));
+        let found = candidates(&dir, &tree, "todo:").unwrap();
+        assert!(found.rules_out(&todo, modified(&todo)));
+        assert!(!found.rules_out(&added, modified(&added)));
+        assert!(found.updated.is_some());
+        assert!(update_files(&dir, &root, &changed).unwrap().is_none());
+
+        remove(&dir, &tree).unwrap();
+        assert!(candidates(&dir, &tree, "todo").is_none());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
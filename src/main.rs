@@ -1,18 +1,61 @@
 #![windows_subsystem = "windows"]
 
+mod accents;
+mod archive;
+mod archive_writer;
+mod audit;
+mod colors;
+mod compare;
+mod editor;
+mod empty_folders;
+mod email;
 mod engine;
+mod export;
+mod facets;
 mod favorites;
+mod file_clipboard;
+mod file_icons;
+mod file_id;
+mod filetype;
+mod fuzzy;
+mod git;
+mod hashing;
+mod hexdump;
+mod hotkey;
+mod i18n;
+mod index;
+mod indexer;
+mod markdown;
+mod mft;
+mod network;
+mod ocr;
+mod project;
+mod query_parser;
+mod rename;
+mod replace;
+mod results_model;
+mod session;
+mod settings;
+mod shell;
+mod signature;
+mod similar_images;
+#[cfg(test)]
+mod test_support;
+mod thumbnails;
+mod watcher;
 
 use engine::SearchResult as EngineSearchResult;
 use favorites::FavoritesManager;
-use slint::{ComponentHandle, VecModel};
-use std::cell::RefCell;
+use i18n::tr;
+use results_model::Row;
+use slint::{ComponentHandle, Model, VecModel};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::rc::Rc;
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
-};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 #[cfg(target_os = "windows")]
 use i_slint_backend_winit::WinitWindowAccessor;
@@ -23,41 +66,207 @@ slint::include_modules!();
 
 // UI-thread models: Slint models are not Send/Sync; keep them on the UI thread.
 thread_local! {
-    static RESULTS_MODEL: RefCell<Rc<VecModel<SearchResult>>> = RefCell::new(Rc::new(VecModel::default()));
-    static REMAINING_RESULTS: RefCell<Vec<SearchResult>> = RefCell::new(Vec::new());
+    static SETTINGS: RefCell<settings::Settings> = RefCell::new(settings::Settings::load());
+    // Audit log, which keeps the head of its chain between entries.
+    static AUDIT_LOG: RefCell<audit::AuditLog> = RefCell::new(audit::AuditLog::new(audit::log_path()));
+    // File system watchers of the indexed folders.
+    static WATCHERS: RefCell<HashMap<PathBuf, watcher::FolderWatcher>> = RefCell::new(HashMap::new());
+    // System icon of each extension ("/" for folders), looked up once.
+    static FILE_ICONS: RefCell<HashMap<String, slint::Image>> = RefCell::new(HashMap::new());
+    // Last theme reported by the system (used by the "system" theme setting).
+    static SYSTEM_DARK: Cell<bool> = const { Cell::new(true) };
+    // Open windows by `window-id`, with their state; kept until they are closed.
+    static WINDOWS: RefCell<HashMap<i32, (AppWindow, Rc<WindowState>)>> = RefCell::new(HashMap::new());
+    static NEXT_WINDOW_ID: Cell<i32> = const { Cell::new(0) };
+}
+
+// What belongs to one window: each has its own results, searches and scans.
+#[derive(Default)]
+struct WindowState {
+    results: Rc<results_model::ResultsModel>,
+    // Full report of the last folder comparison (the list shows a filtered view).
+    compare_report: RefCell<Vec<compare::DiffEntry>>,
+    last_search: RefCell<Option<CachedSearch>>,
+    rename_plans: RefCell<Vec<rename::RenamePlan>>,
+    replace_model: Rc<VecModel<ReplacePreviewLine>>,
+    // Audit entry of the running search, written once its result count is known.
+    pending_search_audit: RefCell<Option<audit::AuditEvent>>,
+    // Cancellation token of the running scan (search or tool).
+    scan: RefCell<engine::SearchToken>,
+    // Watcher of the folder of a live search.
+    live_watch: RefCell<Option<(PathBuf, watcher::FolderWatcher)>>,
+    // Set while a live search re-runs itself: the list is kept until the new results are in.
+    live_refresh: Cell<bool>,
+    // Full result list while the refine box narrows the displayed one.
+    result_view: RefCell<Option<ResultView>>,
+}
+
+// Every result of the last completed search, kept so that a narrower time
+// filter can be applied without scanning again.
+struct CachedSearch {
+    modified_since: Option<std::time::SystemTime>,
+    modified_until: Option<std::time::SystemTime>,
+    results: Vec<EngineSearchResult>,
+}
+
+// Every result of the search while only some are displayed, and the indexes
+// of the displayed ones, in display order.
+struct ResultView {
+    all: Vec<Row>,
+    shown: Vec<usize>,
 }
 
 fn main() -> Result<(), slint::PlatformError> {
+    // Favorites/recents persistence, shared by every window.
+    let favorites_manager = Rc::new(RefCell::new(FavoritesManager::load()));
+    open_window(None, &favorites_manager)?.run()
+}
+
+// A window with its callbacks. `opened_from`: the folder of the window "New
+// window" was used in; None for the first window, which restores and saves
+// the session and runs the hotkey, the theme polling and the index refreshes.
+fn open_window(
+    opened_from: Option<PathBuf>,
+    favorites_manager: &Rc<RefCell<FavoritesManager>>,
+) -> Result<AppWindow, slint::PlatformError> {
     let main_window = AppWindow::new()?;
     let window_weak = main_window.as_weak();
+    let window_id = NEXT_WINDOW_ID.with(|next| next.replace(next.get() + 1));
+    main_window.set_window_id(window_id);
+    let state = Rc::new(WindowState::default());
+    WINDOWS.with(|windows| {
+        windows
+            .borrow_mut()
+            .insert(window_id, (main_window.clone_strong(), state.clone()))
+    });
 
-    // System theme detection (Dark/Light).
-    let mode = dark_light::detect();
+    // Language of the labels (Slint catalogs) and of the status messages.
+    let language = SETTINGS.with(|settings| settings.borrow().language);
+    main_window.set_language(language.name().into());
+    apply_language(language);
+    main_window.set_status_text(tr!("Ready").into());
 
-    let is_dark = match mode {
-        dark_light::Mode::Dark => true,
-        dark_light::Mode::Light => false,
-        _ => true, // Default to Dark
-    };
-    main_window.set_dark_mode(is_dark);
+    main_window.on_set_language({
+        let window_weak = window_weak.clone();
+        move |name| {
+            let window = window_weak.unwrap();
+            let language = i18n::Language::from_name(&name);
+            update_settings(&window, |settings| settings.language = language);
+            apply_language(language);
+            for_each_window(|window| window.set_language(language.name().into()));
+        }
+    });
 
-    // Windows 11 Mica effect.
-    #[cfg(target_os = "windows")]
-    {
-        let _ = WinitWindowAccessor::with_winit_window(main_window.window(), |winit_window| {
-            let _ = apply_mica(winit_window, Some(is_dark));
-        });
+    // Theme: the setting, or the system one (Dark/Light), followed while the app runs.
+    if opened_from.is_none() {
+        let system_dark = system_is_dark();
+        SYSTEM_DARK.with(|dark| dark.set(system_dark));
+        watch_system_theme(system_dark);
     }
+    let theme = SETTINGS.with(|settings| settings.borrow().theme);
+    main_window.set_theme(theme.name().into());
+    apply_theme(&main_window);
+
+    main_window.on_set_theme({
+        let window_weak = window_weak.clone();
+        move |name| {
+            let window = window_weak.unwrap();
+            let theme = settings::Theme::from_name(&name);
+            update_settings(&window, |settings| settings.theme = theme);
+            for_each_window(|window| {
+                window.set_theme(theme.name().into());
+                apply_theme(window);
+            });
+        }
+    });
+
+    // Density and text size (settings panel).
+    let (density, font_size_percent) = SETTINGS.with(|settings| {
+        let settings = settings.borrow();
+        (settings.density, settings.font_size_percent)
+    });
+    main_window.set_density(density.name().into());
+    main_window.set_font_size_percent(font_size_percent as f32);
+    apply_density(&main_window);
+
+    main_window.on_set_density({
+        let window_weak = window_weak.clone();
+        move |name| {
+            let window = window_weak.unwrap();
+            let density = settings::Density::from_name(&name);
+            update_settings(&window, |settings| settings.density = density);
+            for_each_window(|window| {
+                window.set_density(density.name().into());
+                apply_density(window);
+            });
+        }
+    });
+
+    main_window.on_set_font_size_percent({
+        let window_weak = window_weak.clone();
+        move |percent| {
+            let window = window_weak.unwrap();
+            let percent = percent.max(0) as u32;
+            update_settings(&window, |settings| settings.font_size_percent = percent);
+            for_each_window(|window| {
+                window.set_font_size_percent(percent as f32);
+                apply_density(window);
+            });
+        }
+    });
+
+    // Custom accent and background colors (settings panel).
+    let custom_colors = SETTINGS.with(|settings| settings.borrow().colors.clone());
+    main_window.set_accent_color(custom_colors.accent.into());
+    main_window.set_background_color(custom_colors.background.into());
+    main_window.set_surface_color(custom_colors.surface.into());
+    main_window.set_panel_color(custom_colors.panel.into());
+
+    main_window.on_set_theme_color({
+        let window_weak = window_weak.clone();
+        move |which, text| {
+            let window = window_weak.unwrap();
+            let text = text.trim().to_string();
+            // Half-typed colors are ignored; an empty field restores the theme color.
+            if !text.is_empty() && colors::parse_hex(&text).is_none() {
+                return;
+            }
+            update_settings(&window, |settings| {
+                let colors = &mut settings.colors;
+                match which.as_str() {
+                    "accent" => colors.accent = text,
+                    "background" => colors.background = text,
+                    "surface" => colors.surface = text,
+                    _ => colors.panel = text,
+                }
+            });
+            for_each_window(apply_theme);
+        }
+    });
+
+    // A window opened by "New window" starts in the folder it was opened from,
+    // and leaves the session to the first window.
+    let session = match opened_from {
+        Some(_) => None,
+        None => session::Session::load(),
+    };
 
-    // Default search directory: user's home.
+    // Default search directory: the folder of the last session, or the user's home.
     let home_dir = dirs::home_dir().unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
-    main_window.set_current_path(home_dir.to_string_lossy().to_string().into());
+    let start_dir = opened_from
+        .clone()
+        .or_else(|| session.as_ref().and_then(|session| session.folder.clone()))
+        // A share that is offline now fails at the first search, not at startup.
+        .filter(|folder| network::is_network_path(folder) || folder.is_dir())
+        .unwrap_or(home_dir);
+    main_window.set_current_path(start_dir.to_string_lossy().to_string().into());
+    main_window.set_network_root(network::is_network_path(&start_dir));
+    if let Some(session) = &session {
+        restore_session(&main_window, session);
+    }
 
     // Shared selected directory (UI updates this when you pick a folder).
-    let search_path = Rc::new(RefCell::new(home_dir.clone()));
-
-    // Favorites/recents persistence.
-    let favorites_manager = Rc::new(RefCell::new(FavoritesManager::load()));
+    let search_path = Rc::new(RefCell::new(start_dir.clone()));
 
     // Populate UI models with persisted favorites/recents.
     {
@@ -70,6 +279,7 @@ fn main() -> Result<(), slint::PlatformError> {
                 path: f.path.clone().into(),
                 name: f.name.clone().into(),
                 is_favorite: true,
+                content_indexed: f.content_indexed,
             })
             .collect();
         main_window.set_favorites(slint::ModelRc::new(slint::VecModel::from(fav_vec)));
@@ -81,6 +291,7 @@ fn main() -> Result<(), slint::PlatformError> {
                 path: f.path.clone().into(),
                 name: f.name.clone().into(),
                 is_favorite: false,
+                content_indexed: false,
             })
             .collect();
         main_window.set_recent_folders(slint::ModelRc::new(slint::VecModel::from(recent_vec)));
@@ -89,15 +300,13 @@ fn main() -> Result<(), slint::PlatformError> {
     // Track app start directory in recents.
     favorites_manager
         .borrow_mut()
-        .add_recent(home_dir.to_string_lossy().to_string());
+        .add_recent(start_dir.to_string_lossy().to_string());
 
     // Attach the results model to the UI.
-    RESULTS_MODEL.with(|model| {
-        main_window.set_results(model.borrow().clone().into());
-    });
+    main_window.set_results(state.results.clone().into());
 
-    // Cancel flag for background search threads.
-    let is_searching = Arc::new(AtomicBool::new(false));
+    // Attach the replace preview model to the UI.
+    main_window.set_replace_preview(state.replace_model.clone().into());
 
     // Folder picker.
     main_window.on_select_directory({
@@ -108,8 +317,13 @@ fn main() -> Result<(), slint::PlatformError> {
             let window = window_weak.unwrap();
             // Open native folder picker.
             if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                // `\\wsl$\Ubuntu` becomes `\\wsl$\Ubuntu\`: relative paths below it
+                // then start without a separator.
+                let folder = network::normalize_root(&folder);
                 let path_str = folder.to_string_lossy().to_string();
                 window.set_current_path(path_str.clone().into());
+                window.set_network_root(network::is_network_path(&folder));
+                apply_project_config(&window, &folder);
                 *search_path.borrow_mut() = folder;
 
                 // Persist in recents.
@@ -125,6 +339,7 @@ fn main() -> Result<(), slint::PlatformError> {
                         path: f.path.clone().into(),
                         name: f.name.clone().into(),
                         is_favorite: false,
+                        content_indexed: false,
                     })
                     .collect();
                 window.set_recent_folders(slint::ModelRc::new(slint::VecModel::from(recent_vec)));
@@ -135,7 +350,7 @@ fn main() -> Result<(), slint::PlatformError> {
     // Start search.
     main_window.on_request_search({
         let window_weak = window_weak.clone();
-        let is_searching = is_searching.clone();
+        let state = state.clone();
         let search_path = search_path.clone();
 
         move |query,
@@ -146,24 +361,96 @@ fn main() -> Result<(), slint::PlatformError> {
               exclude_extensions,
               language_filter| {
             let window = window_weak.unwrap();
+            let refresh = state.live_refresh.replace(false);
+
+            let size_filter = match engine::SizeFilter::parse(&window.get_size_filter()) {
+                Ok(filter) => filter,
+                Err(err) => {
+                    window.set_status_text(tr!("Error: Invalid size filter: {}", err).into());
+                    return;
+                }
+            };
+            let max_content_size = match engine::parse_megabytes(&window.get_content_max_mb()) {
+                Ok(limit) => limit,
+                Err(err) => {
+                    window.set_status_text(
+                        tr!("Error: Invalid content size limit: {}", err).into(),
+                    );
+                    return;
+                }
+            };
+            let max_depth = match engine::parse_depth(&window.get_max_depth()) {
+                Ok(depth) => depth,
+                Err(err) => {
+                    window.set_status_text(tr!("Error: Invalid depth: {}", err).into());
+                    return;
+                }
+            };
+            // A git revision searches its files instead of the working tree.
+            let revision = window.get_git_revision().trim().to_string();
+            let changed_since = window.get_changed_since().trim().to_string();
+            let (modified_since, modified_until) = match modified_bounds(&window) {
+                Ok(bounds) => bounds,
+                Err(err) => {
+                    window.set_status_text(tr!("Error: Invalid date range: {}", err).into());
+                    return;
+                }
+            };
 
-            // Clear UI state for a new scan.
-            RESULTS_MODEL.with(|model| model.borrow().set_vec(vec![]));
+            // Clear UI state for a new scan; a live re-run keeps the list until
+            // its results replace it.
+            if !refresh {
+                state.results.clear();
+                state.result_view.borrow_mut().take();
+                window.set_refine_text("".into());
+                window.set_extension_filter("".into());
+                window.set_total_results(0);
+            }
+            *state.last_search.borrow_mut() = None;
 
-            window.set_total_results(0);
-            window.set_status_text("Scanning...".into());
-            window.set_active_threads(num_cpus::get() as i32);
+            window.set_results_mode("search".into());
+            window.set_status_text(if refresh { tr!("Refreshing...") } else { tr!("Scanning...") }.into());
+            let threads = SETTINGS.with(|settings| settings.borrow().search_threads());
+            window.set_active_threads(if window.get_low_priority() {
+                (threads / 2).max(1) as i32
+            } else {
+                threads as i32
+            });
 
-            // Mark search as active (used by worker threads to stop early).
-            is_searching.store(true, Ordering::Relaxed);
+            // Stop the previous scan; its late UI updates are dropped.
+            let token = start_scan(&window);
 
             // Spawn the search worker.
             let path = search_path.borrow().clone();
+            watch_live_search(&window, &path, window.get_live());
+            // Re-runs are not audited (only the search that started them).
+            if !refresh {
+                *state.pending_search_audit.borrow_mut() = Some(audit::AuditEvent {
+                    action: "search",
+                    scope: path.display().to_string(),
+                    details: format!(
+                        "query=\"{}\" case={} regex={} content={} archives={} gitignore={} hidden={} exclude=\"{}\" language=\"{}\" time={} period={} recent_first={}",
+                        query,
+                        case_sensitive,
+                        use_regex,
+                        search_content,
+                        window.get_search_archives(),
+                        respect_gitignore,
+                        window.get_include_hidden(),
+                        exclude_extensions,
+                        language_filter,
+                        window.get_time_filter(),
+                        window.get_date_range_mode(),
+                        window.get_sort_recent()
+                    ),
+                    result_count: 0,
+                });
+            }
             engine::spawn_search(
                 query.into(),
                 path,
                 window_weak.clone(),
-                is_searching.clone(),
+                token,
                 case_sensitive,
                 use_regex,
                 search_content,
@@ -174,237 +461,2796 @@ fn main() -> Result<(), slint::PlatformError> {
                 } else {
                     Some(language_filter.to_string())
                 },
+                engine::SearchOptions {
+                    order: if window.get_sort_recent() {
+                        engine::ResultOrder::RecentFirst
+                    } else if window.get_sort_shallow() {
+                        engine::ResultOrder::ShallowFirst
+                    } else if window.get_fuzzy() {
+                        engine::ResultOrder::Relevance
+                    } else {
+                        engine::ResultOrder::Unsorted
+                    },
+                    fuzzy: window.get_fuzzy(),
+                    whole_word: window.get_whole_word(),
+                    include_hidden: window.get_include_hidden(),
+                    follow_links: window.get_follow_links(),
+                    threads: Some(threads),
+                    low_priority: window.get_low_priority(),
+                    tracked_only: window.get_tracked_only(),
+                    revision: (!revision.is_empty()).then_some(revision),
+                    changed_since: (!changed_since.is_empty()).then_some(changed_since),
+                    max_depth,
+                    walk_ignored_dirs: window.get_walk_ignored_dirs(),
+                    match_path: window.get_match_path(),
+                    ignore_accents: window.get_ignore_accents(),
+                    max_content_size,
+                    max_results: SETTINGS.with(|settings| {
+                        let max = settings.borrow().max_results;
+                        (max > 0).then_some(max)
+                    }),
+                    max_content_lines: SETTINGS.with(|settings| {
+                        let max = settings.borrow().max_content_lines;
+                        (max > 0).then_some(max)
+                    }),
+                    search_archives: window.get_search_archives(),
+                    ocr: ocr::AVAILABLE && SETTINGS.with(|settings| settings.borrow().ocr_enabled),
+                    read_cloud_files: SETTINGS.with(|settings| settings.borrow().read_cloud_files),
+                    include_globs: window.get_include_globs().to_string(),
+                    exclude_globs: window.get_exclude_globs().to_string(),
+                    size_filter,
+                    modified_since,
+                    modified_until,
+                    refresh,
+                },
             );
         }
     });
 
-    // Open a file.
-    main_window.on_open_item(|item| {
-        let _ = Command::new("cmd")
-            .args(["/C", "start", "", &item.file_path])
-            .spawn();
+    // Time filter chips: narrow the cached results when they cover the new period.
+    // Returns false when a new scan is needed.
+    main_window.on_filter_cached_results({
+        let window_weak = window_weak.clone();
+        let state = state.clone();
+        move || {
+            let window = window_weak.unwrap();
+            if window.get_results_mode() != "search" {
+                return false;
+            }
+            let Ok((since, until)) = modified_bounds(&window) else {
+                return false;
+            };
+
+            let filtered = state.last_search.borrow().as_ref().and_then(|cached| {
+                let covered_since = match (cached.modified_since, since) {
+                    (None, _) => true,
+                    (Some(cached_since), Some(since)) => cached_since <= since,
+                    (Some(_), None) => false,
+                };
+                let covered_until = match (cached.modified_until, until) {
+                    (None, _) => true,
+                    (Some(cached_until), Some(until)) => cached_until >= until,
+                    (Some(_), None) => false,
+                };
+                (covered_since && covered_until).then(|| {
+                    cached
+                        .results
+                        .iter()
+                        .filter(|r| {
+                            since.is_none_or(|s| r.modified.is_some_and(|m| m >= s))
+                                && until.is_none_or(|u| r.modified.is_some_and(|m| m < u))
+                        })
+                        .cloned()
+                        .collect::<Vec<_>>()
+                })
+            });
+
+            match filtered {
+                Some(results) => {
+                    let count = results.len();
+                    show_results(&window, results);
+                    window.set_status_text(tr!("Filtered: {} results", count).into());
+                    true
+                }
+                None => false,
+            }
+        }
     });
 
-    // Reveal in Explorer.
-    main_window.on_open_item_folder(|item| {
-        let _ = Command::new("explorer")
-            .args(["/select,", &item.file_path])
-            .spawn();
+    // Refine box: narrows the loaded results without scanning again.
+    main_window.on_refine_results({
+        let window_weak = window_weak.clone();
+        move || apply_result_view(&window_weak.unwrap())
     });
 
-    // Copy absolute path.
-    main_window.on_copy_item_path(|item| {
-        if let Ok(mut clipboard) = arboard::Clipboard::new() {
-            let _ = clipboard.set_text(item.file_path.to_string());
+    // New window (Ctrl+N): its search, results and scans are its own; the
+    // settings, favorites and indexes are shared.
+    main_window.on_new_window({
+        let window_weak = window_weak.clone();
+        let search_path = search_path.clone();
+        let favorites_manager = favorites_manager.clone();
+        move || {
+            let window = window_weak.unwrap();
+            let opened = open_window(Some(search_path.borrow().clone()), &favorites_manager)
+                .and_then(|new_window| {
+                    new_window
+                        .show()
+                        .inspect_err(|_| forget_window(&new_window))
+                });
+            if let Err(err) = opened {
+                window.set_status_text(tr!("Error: Unable to open a new window: {}", err).into());
+            }
         }
     });
 
-    // Context menu actions.
-    main_window.on_copy_absolute_path(|item| {
-        if let Ok(mut clipboard) = arboard::Clipboard::new() {
-            let _ = clipboard.set_text(item.file_path.to_string());
+    // Tools: list folders that contain no files.
+    main_window.on_find_empty_folders({
+        let window_weak = window_weak.clone();
+        let state = state.clone();
+        let search_path = search_path.clone();
+        move || {
+            let window = window_weak.unwrap();
+
+            state.results.clear();
+
+            window.set_results_mode("empty-folders".into());
+            window.set_total_results(0);
+            window.set_checked_count(0);
+            window.set_status_text(tr!("Looking for empty folders...").into());
+            window.set_active_threads(1);
+
+            let token = start_scan(&window);
+            empty_folders::spawn_empty_folder_scan(
+                search_path.borrow().clone(),
+                window_weak.clone(),
+                token,
+            );
         }
     });
 
-    main_window.on_copy_relative_path(|item| {
-        if let Ok(mut clipboard) = arboard::Clipboard::new() {
-            let _ = clipboard.set_text(item.relative_path.to_string());
+    // Tools: locate files by MD5/SHA-1/SHA-256 digest.
+    main_window.on_find_by_hash({
+        let window_weak = window_weak.clone();
+        let state = state.clone();
+        let search_path = search_path.clone();
+        move |hash, size_text| {
+            let window = window_weak.unwrap();
+
+            // Optional size prefilter, in bytes.
+            let size = if size_text.trim().is_empty() {
+                None
+            } else {
+                match size_text.trim().parse::<u64>() {
+                    Ok(size) => Some(size),
+                    Err(_) => {
+                        window.set_status_text(tr!("Error: Invalid file size").into());
+                        return;
+                    }
+                }
+            };
+
+            state.results.clear();
+
+            window.set_results_mode("hash".into());
+            window.set_total_results(0);
+            window.set_status_text(tr!("Hashing files...").into());
+            window.set_active_threads(num_cpus::get() as i32);
+
+            let token = start_scan(&window);
+            hashing::spawn_hash_search(
+                hash.into(),
+                size,
+                search_path.borrow().clone(),
+                window_weak.clone(),
+                token,
+            );
         }
     });
 
-    main_window.on_copy_filename(|item| {
-        if let Ok(mut clipboard) = arboard::Clipboard::new() {
-            let _ = clipboard.set_text(item.file_name.to_string());
+    // Tools: pick the reference picture for the similar-image search.
+    main_window.on_pick_reference_image({
+        let window_weak = window_weak.clone();
+        move || {
+            let window = window_weak.unwrap();
+            if let Some(file) = rfd::FileDialog::new()
+                .add_filter(
+                    "Images",
+                    &["png", "jpg", "jpeg", "gif", "bmp", "webp", "tif", "tiff"],
+                )
+                .pick_file()
+            {
+                window.set_similar_reference(file.to_string_lossy().to_string().into());
+            }
         }
     });
 
-    // Reset UI.
-    main_window.on_clear_search({
+    // Tools: perceptual-hash search for visually similar images.
+    main_window.on_find_similar_images({
         let window_weak = window_weak.clone();
-        move || {
-            RESULTS_MODEL.with(|model| model.borrow().set_vec(vec![]));
-            REMAINING_RESULTS.with(|remaining| *remaining.borrow_mut() = vec![]);
-            if let Some(window) = window_weak.upgrade() {
-                window.set_total_results(0);
-                window.set_status_text("Ready".into());
-                window.set_active_threads(0);
+        let state = state.clone();
+        let search_path = search_path.clone();
+        move |reference, threshold| {
+            let window = window_weak.unwrap();
+            if reference.is_empty() {
+                window.set_status_text(tr!("Error: Choose a reference image first").into());
+                return;
             }
+
+            state.results.clear();
+
+            window.set_results_mode("similar-images".into());
+            window.set_total_results(0);
+            window.set_status_text(tr!("Comparing images...").into());
+            window.set_active_threads(num_cpus::get() as i32);
+
+            let token = start_scan(&window);
+            similar_images::spawn_similar_search(
+                reference.as_str().into(),
+                threshold.round().max(0.0) as u32,
+                search_path.borrow().clone(),
+                window_weak.clone(),
+                token,
+            );
         }
     });
 
-    // Pagination: append a batch of results.
-    main_window.on_load_more_results({
+    // Tools: pick the second folder of a comparison.
+    main_window.on_pick_compare_folder({
+        let window_weak = window_weak.clone();
         move || {
-            REMAINING_RESULTS.with(|remaining| {
-                let mut remaining_vec = remaining.borrow_mut();
-                if remaining_vec.is_empty() {
-                    return;
-                }
-                let batch_size = 50;
-                let min_count = std::cmp::min(batch_size, remaining_vec.len());
-                let batch: Vec<SearchResult> = remaining_vec.drain(0..min_count).collect();
-                RESULTS_MODEL.with(|model| {
-                    let model_ref = model.borrow_mut();
-                    for result in batch {
-                        model_ref.push(result);
-                    }
-                });
-            });
+            let window = window_weak.unwrap();
+            if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                window.set_compare_folder(folder.to_string_lossy().to_string().into());
+            }
         }
     });
 
-    // Favorites: selecting an entry updates the current search path.
-    main_window.on_select_favorite({
+    // Tools: compare the current folder (A) with another one (B).
+    main_window.on_compare_folders({
         let window_weak = window_weak.clone();
+        let state = state.clone();
         let search_path = search_path.clone();
-        let favorites_manager = favorites_manager.clone();
-        move |path_str| {
+        move |folder_b, by_hash| {
             let window = window_weak.unwrap();
-            let path = std::path::PathBuf::from(path_str.as_str());
-            window.set_current_path(path_str.clone());
-            *search_path.borrow_mut() = path;
+            if folder_b.is_empty() {
+                window.set_status_text(tr!("Error: Choose a folder to compare with").into());
+                return;
+            }
 
-            // Persist last_used for sorting/recents.
-            favorites_manager
-                .borrow_mut()
-                .update_last_used(path_str.as_str());
+            state.results.clear();
+            state.compare_report.borrow_mut().clear();
+
+            window.set_results_mode("compare".into());
+            window.set_total_results(0);
+            window.set_status_text(tr!("Comparing folders...").into());
+            window.set_active_threads(num_cpus::get() as i32);
+
+            let method = if by_hash {
+                compare::CompareMethod::Hash
+            } else {
+                compare::CompareMethod::SizeAndTime
+            };
+
+            let token = start_scan(&window);
+            compare::spawn_compare(
+                search_path.borrow().clone(),
+                folder_b.as_str().into(),
+                method,
+                window.get_respect_gitignore(),
+                window_weak.clone(),
+                token,
+            );
         }
     });
 
-    // Favorites: add current folder.
-    main_window.on_add_to_favorites({
+    // Comparison filters (only in A / only in B / different).
+    main_window.on_refilter_compare({
+        let window_weak = window_weak.clone();
+        move || show_compare_results(&window_weak.unwrap())
+    });
+
+    main_window.on_export_compare_report({
         let window_weak = window_weak.clone();
-        let search_path = search_path.clone();
-        let favorites_manager = favorites_manager.clone();
         move || {
             let window = window_weak.unwrap();
-            let path = search_path.borrow().clone();
-            let path_str = path.to_string_lossy().to_string();
-            let name = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or(&path_str)
-                .to_string();
+            let Some(file) = rfd::FileDialog::new()
+                .add_filter("CSV", &["csv"])
+                .set_file_name("comparison.csv")
+                .save_file()
+            else {
+                return;
+            };
 
-            favorites_manager.borrow_mut().add_favorite(path_str, name);
+            let entries = visible_compare_entries(&window);
+            let written = std::fs::File::create(&file)
+                .and_then(|mut out| compare::write_csv(&entries, &mut out));
+            match written {
+                Ok(()) => {
+                    record_audit(
+                        &window,
+                        audit::AuditEvent {
+                            action: "export",
+                            scope: file.display().to_string(),
+                            details: "folder comparison report (CSV)".to_string(),
+                            result_count: entries.len(),
+                        },
+                    );
+                    window.set_status_text(
+                        tr!("Exported {} rows to {}", entries.len(), file.display()).into(),
+                    )
+                }
+                Err(err) => window.set_status_text(tr!("Error: Export failed: {}", err).into()),
+            }
+        }
+    });
 
-            // Refresh favorites model in the UI.
-            let manager = favorites_manager.borrow();
-            let fav_vec: Vec<FavoriteFolder> = manager
-                .favorites
-                .iter()
-                .map(|f| FavoriteFolder {
-                    path: f.path.clone().into(),
-                    name: f.name.clone().into(),
-                    is_favorite: true,
-                })
-                .collect();
+    // Export every result to CSV or JSON.
+    main_window.on_export_results({
+        let window_weak = window_weak.clone();
+        let search_path = search_path.clone();
+        move || {
+            let window = window_weak.unwrap();
+            let Some(file) = rfd::FileDialog::new()
+                .add_filter("CSV", &["csv"])
+                .add_filter("JSON", &["json"])
+                .set_file_name("results.csv")
+                .save_file()
+            else {
+                return;
+            };
 
-            window.set_favorites(slint::ModelRc::new(slint::VecModel::from(fav_vec)));
+            let results = exported_results(&window);
+            let format = export::Format::from_path(&file);
+            let written = std::fs::File::create(&file).and_then(|out| {
+                let mut out = std::io::BufWriter::new(out);
+                export::write(&results, format, &mut out)?;
+                std::io::Write::flush(&mut out)
+            });
+            match written {
+                Ok(()) => {
+                    record_audit(
+                        &window,
+                        audit::AuditEvent {
+                            action: "export",
+                            scope: search_path.borrow().display().to_string(),
+                            details: format!(
+                                "results of \"{}\" to {}",
+                                window.get_search_query(),
+                                file.display()
+                            ),
+                            result_count: results.len(),
+                        },
+                    );
+                    window.set_status_text(
+                        tr!("Exported {} results to {}", results.len(), file.display()).into(),
+                    )
+                }
+                Err(err) => window.set_status_text(tr!("Error: Export failed: {}", err).into()),
+            }
         }
     });
 
-    // Favorites: remove selected entry.
-    main_window.on_remove_from_favorites({
+    // Standalone HTML report of the search, kept as a record of it.
+    main_window.on_export_html_report({
         let window_weak = window_weak.clone();
-        let favorites_manager = favorites_manager.clone();
-        move |path_str| {
+        let search_path = search_path.clone();
+        move || {
             let window = window_weak.unwrap();
-            favorites_manager
-                .borrow_mut()
-                .remove_favorite(path_str.as_str());
+            let Some(file) = rfd::FileDialog::new()
+                .add_filter("HTML", &["html", "htm"])
+                .set_file_name("report.html")
+                .save_file()
+            else {
+                return;
+            };
 
-            // Refresh favorites model in the UI.
-            let manager = favorites_manager.borrow();
-            let fav_vec: Vec<FavoriteFolder> = manager
-                .favorites
-                .iter()
-                .map(|f| FavoriteFolder {
-                    path: f.path.clone().into(),
-                    name: f.name.clone().into(),
-                    is_favorite: true,
-                })
-                .collect();
-            window.set_favorites(slint::ModelRc::new(slint::VecModel::from(fav_vec)));
+            let results = exported_results(&window);
+            let info = export::ReportInfo {
+                query: window.get_search_query().to_string(),
+                folder: search_path.borrow().display().to_string(),
+                options: search_options(&window),
+                generated: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            };
+            let written = std::fs::File::create(&file).and_then(|out| {
+                let mut out = std::io::BufWriter::new(out);
+                export::write_html(&results, &info, &mut out)?;
+                std::io::Write::flush(&mut out)
+            });
+            match written {
+                Ok(()) => {
+                    record_audit(
+                        &window,
+                        audit::AuditEvent {
+                            action: "export",
+                            scope: info.folder.clone(),
+                            details: format!(
+                                "HTML report of \"{}\" to {}",
+                                info.query,
+                                file.display()
+                            ),
+                            result_count: results.len(),
+                        },
+                    );
+                    window.set_status_text(tr!("Report saved to {}", file.display()).into())
+                }
+                Err(err) => window.set_status_text(tr!("Error: Report failed: {}", err).into()),
+            }
         }
     });
 
-    main_window.run()
-}
+    // Replace: preview the substitutions in the files of the current results.
+    main_window.on_preview_replace({
+        let window_weak = window_weak.clone();
+        let state = state.clone();
+        move |replacement| {
+            let window = window_weak.unwrap();
+            let Some(pattern) = replace::ReplacePattern::new(
+                &window.get_search_query(),
+                &replacement,
+                window.get_use_regex(),
+                window.get_case_sensitive(),
+            ) else {
+                window.set_replace_status(tr!("Error: Invalid search pattern").into());
+                return;
+            };
 
-// Helpers called by `engine.rs` via `slint::invoke_from_event_loop`.
-pub fn add_result_to_ui(_window: &AppWindow, result: EngineSearchResult) {
-    let color = get_icon_color(&result.extension);
+            let paths = result_file_paths(&window);
+            state.replace_model.set_vec(vec![]);
+            window.set_replace_checked_count(0);
+            window.set_replace_status(tr!("Analysing {} files...", paths.len()).into());
 
-    // Convert engine result to the Slint struct.
-    let ui_result = SearchResult {
-        file_name: result.file_name.into(),
+            let window_weak = window_weak.clone();
+            std::thread::spawn(move || {
+                let changes = replace::preview(&paths, &pattern);
+                let _ = slint::invoke_from_event_loop(move || {
+                    if let Some(window) = window_weak.upgrade() {
+                        set_replace_preview(&window, changes);
+                    }
+                });
+            });
+        }
+    });
+
+    main_window.on_toggle_replace_line({
+        let window_weak = window_weak.clone();
+        let state = state.clone();
+        move |index| {
+            if let Some(mut line) = state.replace_model.row_data(index as usize) {
+                line.checked = !line.checked;
+                state.replace_model.set_row_data(index as usize, line);
+            }
+            update_replace_checked_count(&window_weak.unwrap());
+        }
+    });
+
+    main_window.on_set_all_replace_lines({
+        let window_weak = window_weak.clone();
+        let state = state.clone();
+        move |checked| {
+            let model = &state.replace_model;
+            for index in 0..model.row_count() {
+                if let Some(mut line) = model.row_data(index) {
+                    line.checked = checked;
+                    model.set_row_data(index, line);
+                }
+            }
+            update_replace_checked_count(&window_weak.unwrap());
+        }
+    });
+
+    // Replace: write the ticked substitutions (originals are backed up first).
+    main_window.on_apply_replace({
+        let window_weak = window_weak.clone();
+        let state = state.clone();
+        let search_path = search_path.clone();
+        move || {
+            let window = window_weak.unwrap();
+            let changes: Vec<replace::LineChange> = state
+                .replace_model
+                .iter()
+                .filter(|line| line.checked)
+                .map(|line| replace::LineChange {
+                    file_path: line.file_path.as_str().into(),
+                    line_number: line.line_number as usize,
+                    before: line.before.to_string(),
+                    after: line.after.to_string(),
+                })
+                .collect();
+            if changes.is_empty() {
+                return;
+            }
+            // Cleared first: the preview cannot be applied twice.
+            state.replace_model.set_vec(vec![]);
+            window.set_replace_checked_count(0);
+            window.set_replace_status(tr!("Replacing {} lines...", changes.len()).into());
+
+            // Files are read, backed up and written off the UI thread.
+            let window_weak = window_weak.clone();
+            let scope = search_path.borrow().display().to_string();
+            std::thread::spawn(move || {
+                let applied = replace::apply_changes(&changes, &replace::backups_dir());
+                let _ = slint::invoke_from_event_loop(move || {
+                    let Some(window) = window_weak.upgrade() else {
+                        return;
+                    };
+                    match applied {
+                        Ok(report) => {
+                            show_failed_paths(&window, &report.skipped);
+                            record_audit(
+                                &window,
+                                audit::AuditEvent {
+                                    action: "replace",
+                                    scope,
+                                    details: format!(
+                                        "{} lines in {} files ({} files skipped)",
+                                        report.lines_changed,
+                                        report.files_changed,
+                                        report.skipped.len()
+                                    ),
+                                    result_count: report.files_changed,
+                                },
+                            );
+                            window.set_replace_status(
+                                tr!(
+                                    "Replaced {} lines in {} files ({} files skipped)",
+                                    report.lines_changed,
+                                    report.files_changed,
+                                    report.skipped.len()
+                                )
+                                .into(),
+                            );
+                        }
+                        Err(err) => {
+                            window.set_replace_status(tr!("Error: Replace failed: {}", err).into())
+                        }
+                    }
+                });
+            });
+        }
+    });
+
+    main_window.on_undo_replace({
+        let window_weak = window_weak.clone();
+        move || {
+            let window = window_weak.unwrap();
+            let status = match replace::undo_last(&replace::backups_dir()) {
+                Ok(report) if report.skipped.is_empty() && report.restored == 0 => {
+                    tr!("Nothing to undo").to_string()
+                }
+                Ok(report) if report.skipped.is_empty() => {
+                    tr!("Restored {} files", report.restored)
+                }
+                Ok(report) => tr!(
+                    "Restored {} files ({} files edited since the replace were kept)",
+                    report.restored,
+                    report.skipped.len()
+                ),
+                Err(err) => tr!("Error: Undo failed: {}", err),
+            };
+            window.set_replace_status(status.into());
+        }
+    });
+
+    // Rename: compute the new names of the result files without touching them.
+    main_window.on_preview_rename({
+        let window_weak = window_weak.clone();
+        move || {
+            let window = window_weak.unwrap();
+            let rule = rename::RenameRule {
+                template: window.get_rename_template().to_string(),
+                find: window.get_rename_find().to_string(),
+                replace: window.get_rename_replace().to_string(),
+                use_regex: window.get_rename_use_regex(),
+                case: rename::CaseTransform::from_index(window.get_rename_case()),
+                counter_start: window.get_rename_counter_start().trim().parse().unwrap_or(1),
+            };
+
+            match rename::plan_renames(&rename_targets(&window), &rule) {
+                Ok(plans) => set_rename_preview(&window, plans),
+                Err(err) => {
+                    set_rename_preview(&window, vec![]);
+                    window.set_rename_status(tr!("Error: Invalid regex: {}", err).into());
+                }
+            }
+        }
+    });
+
+    main_window.on_apply_rename({
+        let window_weak = window_weak.clone();
+        let state = state.clone();
+        let search_path = search_path.clone();
+        move || {
+            let window = window_weak.unwrap();
+            let plans = state.rename_plans.borrow().clone();
+            if plans.is_empty() || plans.iter().any(|plan| plan.conflict.is_some()) {
+                return;
+            }
+
+            let report = rename::execute_renames(&plans);
+            show_failed_paths(&window, &report.failed);
+            record_audit(
+                &window,
+                audit::AuditEvent {
+                    action: "rename",
+                    scope: search_path.borrow().display().to_string(),
+                    details: format!(
+                        "template=\"{}\" ({} failed)",
+                        window.get_rename_template(),
+                        report.failed.len()
+                    ),
+                    result_count: report.renamed.len(),
+                },
+            );
+            update_renamed_results(&window, &report.renamed);
+            set_rename_preview(&window, vec![]);
+            window.set_rename_status(
+                tr!(
+                    "Renamed {} files ({} failed)",
+                    report.renamed.len(),
+                    report.failed.len()
+                )
+                .into(),
+            );
+        }
+    });
+
+    // Inline rename (F2) of one result; a failure is shown under the name.
+    main_window.on_rename_result({
+        let window_weak = window_weak.clone();
+        let state = state.clone();
+        let search_path = search_path.clone();
+        move |index, name| {
+            let window = window_weak.unwrap();
+            let Some(item) = state.results.row_data(index as usize) else {
+                return;
+            };
+            if archive::is_entry_path(&item.file_path) {
+                window.set_inline_rename_error(
+                    tr!("Error: files inside an archive cannot be renamed").into(),
+                );
+                return;
+            }
+
+            let from = PathBuf::from(item.file_path.as_str());
+            match rename::rename_one(&from, name.trim()) {
+                Ok(to) => {
+                    if to != from {
+                        record_audit(
+                            &window,
+                            audit::AuditEvent {
+                                action: "rename",
+                                scope: search_path.borrow().display().to_string(),
+                                details: format!("\"{}\" -> \"{}\"", item.file_name, name.trim()),
+                                result_count: 1,
+                            },
+                        );
+                        update_renamed_results(&window, &[(from, to)]);
+                    }
+                    window.invoke_stop_rename();
+                    if window.get_detail_item().file_path == item.file_path {
+                        if let Some(renamed) = state.results.row_data(index as usize) {
+                            window.set_detail_item(renamed);
+                        }
+                    }
+                }
+                Err(reason) => window.set_inline_rename_error(tr!("Error: {}", reason).into()),
+            }
+        }
+    });
+
+    // Batch selection (checkboxes shown by tools such as the empty folder finder).
+    main_window.on_toggle_result_checked({
+        let window_weak = window_weak.clone();
+        let state = state.clone();
+        move |index| {
+            if let Some(mut item) = state.results.row_data(index as usize) {
+                item.checked = !item.checked;
+                state.results.set_row_data(index as usize, item);
+            }
+            update_checked_count(&window_weak.unwrap());
+        }
+    });
+
+    // Dismiss: drop a row from the list while triaging (the file is untouched).
+    main_window.on_dismiss_result({
+        let window_weak = window_weak.clone();
+        move |index| {
+            let window = window_weak.unwrap();
+            if index < 0 {
+                return;
+            }
+            if let Some(item) = dismiss_result(&window, index as usize) {
+                window.set_total_results(window.get_total_results() - 1);
+                update_checked_count(&window);
+                update_extension_facets(&window);
+                window.set_status_text(tr!("Removed {} from the list", item.file_name).into());
+            }
+        }
+    });
+
+    // Show or hide every matched line of a content result.
+    main_window.on_toggle_result_expanded({
+        let state = state.clone();
+        move |index| {
+            if let Some(mut item) = state.results.row_data(index as usize) {
+                item.expanded = !item.expanded;
+                state.results.set_row_data(index as usize, item);
+            }
+        }
+    });
+
+    main_window.on_set_all_checked({
+        let window_weak = window_weak.clone();
+        let state = state.clone();
+        move |checked| {
+            let model = &state.results;
+            for index in 0..model.row_count() {
+                if let Some(mut item) = model.row_data(index) {
+                    item.checked = checked;
+                    model.set_row_data(index, item);
+                }
+            }
+            update_checked_count(&window_weak.unwrap());
+        }
+    });
+
+    // Zip the checked results in the background, with a progress dialog.
+    let zip_cancelled = Arc::new(AtomicBool::new(false));
+    main_window.on_zip_checked_results({
+        let window_weak = window_weak.clone();
+        let state = state.clone();
+        let search_path = search_path.clone();
+        let zip_cancelled = zip_cancelled.clone();
+        move || {
+            let window = window_weak.unwrap();
+            let mut paths: Vec<PathBuf> = Vec::new();
+            state.results.for_each(|_, row| {
+                if row.checked && !archive::is_entry_path(row.file_path) {
+                    paths.push(PathBuf::from(row.file_path));
+                }
+            });
+            if paths.is_empty() {
+                return;
+            }
+            let Some(destination) = rfd::FileDialog::new()
+                .add_filter("Zip", &["zip"])
+                .set_file_name("results.zip")
+                .save_file()
+            else {
+                return;
+            };
+
+            zip_cancelled.store(false, Ordering::Relaxed);
+            window.set_zip_done(0);
+            window.set_zip_total(0);
+            window.set_zip_dialog_visible(true);
+            let root = search_path.borrow().clone();
+            let window_weak = window_weak.clone();
+            let cancelled = zip_cancelled.clone();
+            std::thread::spawn(move || {
+                let files = archive_writer::collect_files(&paths);
+                let total = files.len() as i32;
+                let progress = |done: usize| {
+                    let window_weak = window_weak.clone();
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(window) = window_weak.upgrade() {
+                            window.set_zip_total(total);
+                            window.set_zip_done(done as i32);
+                        }
+                    });
+                };
+                let result =
+                    archive_writer::write_zip(&destination, &root, &files, progress, || {
+                        cancelled.load(Ordering::Relaxed)
+                    });
+
+                let _ = slint::invoke_from_event_loop(move || {
+                    let Some(window) = window_weak.upgrade() else {
+                        return;
+                    };
+                    window.set_zip_dialog_visible(false);
+                    let status = match result {
+                        Ok(report) => {
+                            show_failed_paths(&window, &report.failed);
+                            record_audit(
+                                &window,
+                                audit::AuditEvent {
+                                    action: "export",
+                                    scope: destination.display().to_string(),
+                                    details: format!(
+                                        "zip of checked results ({} failed)",
+                                        report.failed.len()
+                                    ),
+                                    result_count: report.added,
+                                },
+                            );
+                            tr!(
+                                "Zipped {} files into {} ({} failed)",
+                                report.added,
+                                destination.display(),
+                                report.failed.len()
+                            )
+                        }
+                        Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {
+                            tr!("Zip cancelled").to_string()
+                        }
+                        Err(err) => tr!("Error: Zip failed: {}", err),
+                    };
+                    window.set_status_text(status.into());
+                });
+            });
+        }
+    });
+
+    main_window.on_cancel_zip(move || zip_cancelled.store(true, Ordering::Relaxed));
+
+    // Delete the checked empty folders (only if they are still empty).
+    main_window.on_delete_checked_folders({
+        let window_weak = window_weak.clone();
+        let state = state.clone();
+        let search_path = search_path.clone();
+        move || {
+            let window = window_weak.unwrap();
+            let positions = state.results.positions(|row| row.checked && row.is_dir);
+            let checked: Vec<SearchResult> = positions
+                .into_iter()
+                .filter_map(|index| state.results.row_data(index))
+                .collect();
+
+            let mut deleted = 0;
+            let mut deleted_paths = Vec::new();
+            let mut failed = Vec::new();
+            for item in &checked {
+                let path = PathBuf::from(item.file_path.as_str());
+                match empty_folders::delete_empty_dir(&path) {
+                    Ok(()) => {
+                        deleted += 1;
+                        deleted_paths.push(item.file_path.to_string());
+                    }
+                    Err(err) => failed.push((path, err.to_string())),
+                }
+            }
+            show_failed_paths(&window, &failed);
+            record_audit(
+                &window,
+                audit::AuditEvent {
+                    action: "delete",
+                    scope: search_path.borrow().display().to_string(),
+                    details: deleted_paths.join("; "),
+                    result_count: deleted_paths.len(),
+                },
+            );
+
+            // Drop the rows whose folder is gone.
+            state
+                .results
+                .retain(|row| !row.is_dir || Path::new(row.file_path).exists());
+
+            window.set_total_results(window.get_total_results() - deleted);
+            update_checked_count(&window);
+            window.set_status_text(
+                tr!(
+                    "Deleted {} empty folders ({} failed)",
+                    deleted,
+                    checked.len() as i32 - deleted
+                )
+                .into(),
+            );
+        }
+    });
+
+    // Audit log: opt-in switch (persisted), viewer and CSV export.
+    main_window.set_audit_log_enabled(SETTINGS.with(|settings| settings.borrow().audit_log_enabled));
+
+    main_window.on_set_audit_log_enabled({
+        let window_weak = window_weak.clone();
+        move |enabled| {
+            let window = window_weak.unwrap();
+            update_settings(&window, |settings| settings.audit_log_enabled = enabled);
+            window.set_audit_log_enabled(enabled);
+        }
+    });
+
+    // OCR of images and scanned PDFs: the switch only shows in builds with the feature.
+    main_window.set_ocr_available(ocr::AVAILABLE);
+    main_window.set_ocr_enabled(SETTINGS.with(|settings| settings.borrow().ocr_enabled));
+
+    main_window.on_set_ocr_enabled({
+        let window_weak = window_weak.clone();
+        move |enabled| {
+            let window = window_weak.unwrap();
+            update_settings(&window, |settings| settings.ocr_enabled = enabled);
+            window.set_ocr_enabled(enabled);
+        }
+    });
+
+    // Cloud files that are not on disk are only read (downloaded) on request.
+    main_window.set_read_cloud_files(SETTINGS.with(|settings| settings.borrow().read_cloud_files));
+
+    main_window.on_set_read_cloud_files({
+        let window_weak = window_weak.clone();
+        move |enabled| {
+            let window = window_weak.unwrap();
+            update_settings(&window, |settings| settings.read_cloud_files = enabled);
+            window.set_read_cloud_files(enabled);
+        }
+    });
+
+    // Ignored folders: comma-separated in the settings, applied to the next scans.
+    let ignored_dirs = SETTINGS.with(|settings| settings.borrow().ignored_dirs.clone());
+    main_window.set_ignored_dirs(ignored_dirs.join(", ").into());
+    engine::set_ignored_dirs(ignored_dirs);
+
+    main_window.on_set_ignored_dirs({
+        let window_weak = window_weak.clone();
+        move |text| {
+            let window = window_weak.unwrap();
+            let dirs: Vec<String> = text
+                .split(',')
+                .map(|dir| dir.trim().trim_end_matches(['/', '\\']).to_string())
+                .filter(|dir| !dir.is_empty())
+                .collect();
+            engine::set_ignored_dirs(dirs.clone());
+            update_settings(&window, |settings| settings.ignored_dirs = dirs);
+        }
+    });
+
+    // Custom ignore file (empty: none), applied to the next scans.
+    let ignore_file = SETTINGS.with(|settings| settings.borrow().ignore_file.clone());
+    if let Some(file) = &ignore_file {
+        main_window.set_ignore_file(file.to_string_lossy().to_string().into());
+    }
+    engine::set_ignore_file(ignore_file);
+
+    main_window.on_set_ignore_file({
+        let window_weak = window_weak.clone();
+        move |text| {
+            let window = window_weak.unwrap();
+            let file = Some(text.trim())
+                .filter(|text| !text.is_empty())
+                .map(PathBuf::from);
+            engine::set_ignore_file(file.clone());
+            update_settings(&window, |settings| settings.ignore_file = file);
+        }
+    });
+
+    main_window.set_max_results(
+        SETTINGS
+            .with(|settings| settings.borrow().max_results)
+            .to_string()
+            .into(),
+    );
+
+    main_window.on_set_max_results({
+        let window_weak = window_weak.clone();
+        move |text| {
+            let window = window_weak.unwrap();
+            let Ok(max) = text.trim().parse::<usize>() else {
+                window.set_status_text(
+                    tr!("Error: The maximum result count must be a whole number (0: no limit)")
+                        .into(),
+                );
+                return;
+            };
+            update_settings(&window, |settings| settings.max_results = max);
+        }
+    });
+
+    main_window.set_editor_command(
+        SETTINGS
+            .with(|settings| settings.borrow().editor_command.clone())
+            .into(),
+    );
+
+    main_window.on_set_editor_command({
+        let window_weak = window_weak.clone();
+        move |text| {
+            let window = window_weak.unwrap();
+            update_settings(&window, |settings| {
+                settings.editor_command = text.to_string()
+            });
+        }
+    });
+
+    // Global hotkey (settings panel): brings the window to the front.
+    let global_hotkey = SETTINGS.with(|settings| settings.borrow().global_hotkey.clone());
+    main_window.set_global_hotkey(global_hotkey.clone().into());
+    if opened_from.is_none() {
+        register_hotkey(&main_window, &global_hotkey);
+    }
+
+    main_window.on_set_global_hotkey({
+        let window_weak = window_weak.clone();
+        move |text| {
+            let window = window_weak.unwrap();
+            let text = text.trim().to_string();
+            if register_hotkey(&window, &text) {
+                update_settings(&window, |settings| settings.global_hotkey = text);
+            }
+        }
+    });
+
+    main_window.set_max_content_lines(
+        SETTINGS
+            .with(|settings| settings.borrow().max_content_lines)
+            .to_string()
+            .into(),
+    );
+
+    main_window.on_set_max_content_lines({
+        let window_weak = window_weak.clone();
+        move |text| {
+            let window = window_weak.unwrap();
+            let Ok(max) = text.trim().parse::<usize>() else {
+                window.set_status_text(
+                    tr!("Error: The line limit must be a whole number (0: no limit)").into(),
+                );
+                return;
+            };
+            update_settings(&window, |settings| settings.max_content_lines = max);
+        }
+    });
+
+    main_window.set_cpu_count(num_cpus::get() as i32);
+    main_window
+        .set_worker_threads(SETTINGS.with(|settings| settings.borrow().search_threads()) as f32);
+
+    main_window.on_set_worker_threads({
+        let window_weak = window_weak.clone();
+        move |count| {
+            let window = window_weak.unwrap();
+            let count = count.max(1) as usize;
+            update_settings(&window, |settings| settings.worker_threads = count);
+        }
+    });
+
+    // File-name index: folders indexed on request, refreshed at startup.
+    main_window.set_indexed_folders(indexed_folder_count());
+    let indexed_roots = match opened_from {
+        Some(_) => Vec::new(),
+        None => SETTINGS.with(|settings| settings.borrow().indexed_roots.clone()),
+    };
+    for root in indexed_roots {
+        let respect_gitignore = index::saved_gitignore_setting(&index::index_dir(), &root)
+            .unwrap_or_else(|| main_window.get_respect_gitignore());
+        watch_indexed_folder(&window_weak, root.clone());
+        spawn_index_update(&window_weak, root, respect_gitignore);
+    }
+
+    main_window.on_index_current_folder({
+        let window_weak = window_weak.clone();
+        let search_path = search_path.clone();
+        move || {
+            let window = window_weak.unwrap();
+            let root = search_path.borrow().clone();
+            update_settings(&window, |settings| {
+                if !settings.indexed_roots.contains(&root) {
+                    settings.indexed_roots.push(root.clone());
+                }
+            });
+            window.set_indexed_folders(indexed_folder_count());
+            window.set_status_text(tr!("Indexing {}...", root.display()).into());
+            watch_indexed_folder(&window_weak, root.clone());
+            spawn_index_update(&window_weak, root, window.get_respect_gitignore());
+        }
+    });
+
+    main_window.on_remove_folder_index({
+        let window_weak = window_weak.clone();
+        let search_path = search_path.clone();
+        move || {
+            let window = window_weak.unwrap();
+            let root = search_path.borrow().clone();
+            update_settings(&window, |settings| {
+                settings.indexed_roots.retain(|indexed| *indexed != root)
+            });
+            window.set_indexed_folders(indexed_folder_count());
+            let status = match index::remove(&index::index_dir(), &root) {
+                Ok(()) => tr!("Index of {} removed", root.display()),
+                Err(err) => tr!("Error: Unable to remove the index: {}", err),
+            };
+            unwatch_folder(&root);
+            window.set_status_text(status.into());
+        }
+    });
+
+    main_window.on_open_audit_log({
+        let window_weak = window_weak.clone();
+        move || show_audit_log(&window_weak.unwrap())
+    });
+
+    main_window.on_export_audit_log({
+        let window_weak = window_weak.clone();
+        move || {
+            let window = window_weak.unwrap();
+            let Some(file) = rfd::FileDialog::new()
+                .add_filter("CSV", &["csv"])
+                .set_file_name("audit-log.csv")
+                .save_file()
+            else {
+                return;
+            };
+
+            let written = audit::read_entries(&audit::log_path()).and_then(|content| {
+                let mut out = std::fs::File::create(&file)?;
+                audit::write_csv(&content.entries, &mut out)?;
+                Ok(content.entries.len())
+            });
+            let status = match written {
+                Ok(count) => tr!("Exported {} entries to {}", count, file.display()),
+                Err(err) => tr!("Error: Export failed: {}", err),
+            };
+            window.set_audit_log_status(status.into());
+        }
+    });
+
+    // Open a file.
+    // An archive entry opens (or reveals) the archive itself; a folder opens in Explorer.
+    main_window.on_open_item(|item| {
+        let path = on_disk_path(&item.file_path);
+        if item.is_dir {
+            let _ = Command::new("explorer").arg(path).spawn();
+        } else {
+            let _ = Command::new("cmd").args(["/C", "start", "", path]).spawn();
+        }
+    });
+
+    // Open in the editor of the settings, at the matched line.
+    main_window.on_open_in_editor({
+        let window_weak = window_weak.clone();
+        move |path, line, column| {
+            let window = window_weak.unwrap();
+            if archive::is_entry_path(&path) {
+                window
+                    .set_status_text(tr!("Error: files inside an archive cannot be edited").into());
+                return;
+            }
+            let command = SETTINGS.with(|settings| settings.borrow().editor_command.clone());
+            let (line, column) = (line.max(0) as usize, column.max(0) as usize);
+            if let Err(reason) = editor::open(&command, &path, line, column) {
+                window.set_status_text(tr!("Error: {}", reason).into());
+            }
+        }
+    });
+
+    // "Open with": the Windows application picker, shown off the UI thread.
+    main_window.on_open_item_with({
+        let window_weak = window_weak.clone();
+        move |item| {
+            let path = PathBuf::from(on_disk_path(&item.file_path));
+            let window_weak = window_weak.clone();
+            std::thread::spawn(move || {
+                if let Err(reason) = shell::open_with(&path) {
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(window) = window_weak.upgrade() {
+                            window.set_status_text(tr!("Error: {}", reason).into());
+                        }
+                    });
+                }
+            });
+        }
+    });
+
+    // Properties: the Windows sheet, or a dialog listing size, dates and
+    // permissions.
+    main_window.on_show_properties({
+        let window_weak = window_weak.clone();
+        move |item| {
+            let window = window_weak.unwrap();
+            let path = Path::new(on_disk_path(&item.file_path));
+            if shell::show_native_properties(path) {
+                return;
+            }
+            match shell::file_properties(path) {
+                Ok(properties) => {
+                    let rows: Vec<FileProperty> = properties
+                        .into_iter()
+                        .map(|(label, value)| FileProperty {
+                            label: label.into(),
+                            value: value.into(),
+                        })
+                        .collect();
+                    window.set_properties_item(item);
+                    window.set_properties(Rc::new(VecModel::from(rows)).into());
+                    window.set_properties_visible(true);
+                }
+                Err(err) => window.set_status_text(tr!("Error: {}", err).into()),
+            }
+        }
+    });
+
+    // Reveal in Explorer.
+    main_window.on_open_item_folder(|item| {
+        let path = on_disk_path(&item.file_path);
+        let _ = Command::new("explorer").args(["/select,", path]).spawn();
+    });
+
+    // Copy absolute path.
+    main_window.on_copy_item_path(|item| {
+        if let Ok(mut clipboard) = arboard::Clipboard::new() {
+            let _ = clipboard.set_text(item.file_path.to_string());
+        }
+    });
+
+    // Context menu actions.
+    main_window.on_copy_absolute_path(|item| {
+        if let Ok(mut clipboard) = arboard::Clipboard::new() {
+            let _ = clipboard.set_text(item.file_path.to_string());
+        }
+    });
+
+    // The file itself, to paste in Explorer or attach to a mail (Windows); its
+    // path as text elsewhere.
+    main_window.on_copy_file({
+        let window_weak = window_weak.clone();
+        move |item| {
+            let window = window_weak.unwrap();
+            if archive::is_entry_path(&item.file_path) {
+                window
+                    .set_status_text(tr!("Error: files inside an archive cannot be copied").into());
+                return;
+            }
+            let path = PathBuf::from(item.file_path.as_str());
+            let status = match file_clipboard::copy_files(&[path]) {
+                Ok(()) => tr!("Copied {} to the clipboard", item.file_name),
+                Err(reason) => {
+                    if let Ok(mut clipboard) = arboard::Clipboard::new() {
+                        let _ = clipboard.set_text(item.file_path.to_string());
+                    }
+                    tr!("Copied the path only: {}", reason)
+                }
+            };
+            window.set_status_text(status.into());
+        }
+    });
+
+    // Paths inside a WSL distribution are copied the Linux way ("src/main.rs").
+    main_window.on_copy_relative_path(|item| {
+        if let Ok(mut clipboard) = arboard::Clipboard::new() {
+            let relative = if network::is_wsl_path(Path::new(item.file_path.as_str())) {
+                item.relative_path.replace('\\', "/")
+            } else {
+                item.relative_path.to_string()
+            };
+            let _ = clipboard.set_text(relative);
+        }
+    });
+
+    // Every result path, one per line.
+    main_window.on_copy_all_paths({
+        let window_weak = window_weak.clone();
+        let search_path = search_path.clone();
+        move |relative| {
+            let window = window_weak.unwrap();
+            let paths: Vec<String> = all_rows(&window, |rows| {
+                rows.iter()
+                    .map(|row| {
+                        let item = row.info();
+                        if !relative {
+                            item.file_path.to_string()
+                        } else if network::is_wsl_path(Path::new(item.file_path)) {
+                            item.relative_path.replace('\\', "/")
+                        } else {
+                            item.relative_path.to_string()
+                        }
+                    })
+                    .collect()
+            });
+            let copied = arboard::Clipboard::new()
+                .and_then(|mut clipboard| clipboard.set_text(paths.join("\n")));
+            if copied.is_ok() {
+                record_audit(
+                    &window,
+                    audit::AuditEvent {
+                        action: "export",
+                        scope: search_path.borrow().display().to_string(),
+                        details: format!(
+                            "paths of \"{}\" to the clipboard",
+                            window.get_search_query()
+                        ),
+                        result_count: paths.len(),
+                    },
+                );
+            }
+            window.set_status_text(
+                match copied {
+                    Ok(()) => tr!("Copied {} paths to the clipboard", paths.len()),
+                    Err(err) => tr!("Error: Cannot copy the paths: {}", err),
+                }
+                .into(),
+            );
+        }
+    });
+
+    // Results as a Markdown table (name, relative path, matched line).
+    main_window.on_copy_as_markdown({
+        let window_weak = window_weak.clone();
+        let search_path = search_path.clone();
+        move || {
+            let window = window_weak.unwrap();
+            let results = exported_results(&window);
+            let copied = arboard::Clipboard::new()
+                .and_then(|mut clipboard| clipboard.set_text(export::markdown_table(&results)));
+            if copied.is_ok() {
+                record_audit(
+                    &window,
+                    audit::AuditEvent {
+                        action: "export",
+                        scope: search_path.borrow().display().to_string(),
+                        details: format!(
+                            "Markdown table of \"{}\" to the clipboard",
+                            window.get_search_query()
+                        ),
+                        result_count: results.len(),
+                    },
+                );
+            }
+            window.set_status_text(
+                match copied {
+                    Ok(()) => tr!("Copied {} results as Markdown", results.len()),
+                    Err(err) => tr!("Error: Cannot copy the table: {}", err),
+                }
+                .into(),
+            );
+        }
+    });
+
+    main_window.on_copy_filename(|item| {
+        if let Ok(mut clipboard) = arboard::Clipboard::new() {
+            let _ = clipboard.set_text(item.file_name.to_string());
+        }
+    });
+
+    main_window.on_copy_text(|text| {
+        if let Ok(mut clipboard) = arboard::Clipboard::new() {
+            let _ = clipboard.set_text(text.to_string());
+        }
+    });
+
+    // Detail pane: hex dump of binary files, rendered Markdown and signature of
+    // executables, all read off the UI thread.
+    main_window.on_load_details({
+        let window_weak = window_weak.clone();
+        move |item| {
+            let window = window_weak.unwrap();
+            if !item.is_dir && !archive::is_entry_path(&item.file_path) {
+                load_hex_preview(window_weak.clone(), item.file_path.to_string());
+                if thumbnails::is_supported(&item.extension) {
+                    load_image_preview(window_weak.clone(), item.file_path.to_string());
+                }
+                if markdown::is_markdown(&item.extension) {
+                    load_markdown_preview(window_weak.clone(), item.file_path.to_string());
+                }
+            }
+            window.set_detail_signature("".into());
+            if item.is_dir
+                || !signature::is_signable(&item.extension)
+                || archive::is_entry_path(&item.file_path)
+            {
+                return;
+            }
+            window.set_detail_signature(tr!("Checking signature...").into());
+
+            let path = item.file_path.to_string();
+            let window_weak = window_weak.clone();
+            std::thread::spawn(move || {
+                let status = signature::verify(Path::new(&path));
+                let _ = slint::invoke_from_event_loop(move || {
+                    if let Some(window) = window_weak.upgrade() {
+                        if window.get_detail_item().file_path == path.as_str() {
+                            window.set_detail_signature(status.label().into());
+                            window.set_detail_signature_trusted(status.is_trusted());
+                        }
+                    }
+                });
+            });
+        }
+    });
+
+    // Detail pane: MD5 / SHA-256 of the shown file, computed off the UI thread.
+    main_window.on_compute_checksums({
+        let window_weak = window_weak.clone();
+        move |item| {
+            let window = window_weak.unwrap();
+            window.set_detail_md5("".into());
+            window.set_detail_sha256("".into());
+            window.set_detail_hash_status(tr!("Calculating...").into());
+
+            let path = item.file_path.to_string();
+            let window_weak = window_weak.clone();
+            std::thread::spawn(move || {
+                let result = hashing::checksums(Path::new(&path));
+                let _ = slint::invoke_from_event_loop(move || {
+                    let Some(window) = window_weak.upgrade() else {
+                        return;
+                    };
+                    // The pane may show another file by now.
+                    if window.get_detail_item().file_path != path.as_str() {
+                        return;
+                    }
+                    match result {
+                        Ok(checksums) => {
+                            window.set_detail_md5(checksums.md5.into());
+                            window.set_detail_sha256(checksums.sha256.into());
+                            window.set_detail_hash_status("".into());
+                        }
+                        Err(err) => window.set_detail_hash_status(tr!("Error: {}", err).into()),
+                    }
+                });
+            });
+        }
+    });
+
+    // Parks the workers of the running search, or lets them continue.
+    main_window.on_toggle_pause({
+        let window_weak = window_weak.clone();
+        let state = state.clone();
+        move || {
+            let scan = state.scan.borrow();
+            if scan.is_paused() {
+                scan.resume();
+            } else {
+                scan.pause();
+            }
+            let paused = scan.is_paused();
+            if let Some(window) = window_weak.upgrade() {
+                window.set_scan_paused(paused);
+            }
+        }
+    });
+
+    // Reset UI.
+    main_window.on_clear_search({
+        let window_weak = window_weak.clone();
+        let state = state.clone();
+        move || {
+            state.scan.borrow().cancel();
+            state.results.clear();
+            if let Some(window) = window_weak.upgrade() {
+                window.set_total_results(0);
+                window.set_status_text(tr!("Ready").into());
+                window.set_active_threads(0);
+                window.set_scan_paused(false);
+            }
+        }
+    });
+
+    // Favorites: selecting an entry updates the current search path.
+    main_window.on_select_favorite({
+        let window_weak = window_weak.clone();
+        let search_path = search_path.clone();
+        let favorites_manager = favorites_manager.clone();
+        move |path_str| {
+            let window = window_weak.unwrap();
+            let path = network::normalize_root(Path::new(path_str.as_str()));
+            window.set_current_path(path.to_string_lossy().to_string().into());
+            window.set_network_root(network::is_network_path(&path));
+            apply_project_config(&window, &path);
+            *search_path.borrow_mut() = path;
+
+            // Persist last_used for sorting/recents.
+            favorites_manager
+                .borrow_mut()
+                .update_last_used(path_str.as_str());
+        }
+    });
+
+    // Favorites: add current folder.
+    main_window.on_add_to_favorites({
+        let window_weak = window_weak.clone();
+        let search_path = search_path.clone();
+        let favorites_manager = favorites_manager.clone();
+        move || {
+            let window = window_weak.unwrap();
+            let path = search_path.borrow().clone();
+            let path_str = path.to_string_lossy().to_string();
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or(&path_str)
+                .to_string();
+
+            favorites_manager.borrow_mut().add_favorite(path_str, name);
+
+            // Refresh favorites model in the UI.
+            let manager = favorites_manager.borrow();
+            let fav_vec: Vec<FavoriteFolder> = manager
+                .favorites
+                .iter()
+                .map(|f| FavoriteFolder {
+                    path: f.path.clone().into(),
+                    name: f.name.clone().into(),
+                    is_favorite: true,
+                    content_indexed: f.content_indexed,
+                })
+                .collect();
+
+            window.set_favorites(slint::ModelRc::new(slint::VecModel::from(fav_vec)));
+        }
+    });
+
+    // Favorites: opt-in content index, refreshed at startup.
+    if opened_from.is_none() {
+        for favorite in &favorites_manager.borrow().favorites {
+            if favorite.content_indexed {
+                watch_indexed_folder(&window_weak, favorite.path.clone().into());
+                spawn_content_index_update(&window_weak, favorite.path.clone().into());
+            }
+        }
+    }
+
+    main_window.on_toggle_content_index({
+        let window_weak = window_weak.clone();
+        let favorites_manager = favorites_manager.clone();
+        move |path_str| {
+            let window = window_weak.unwrap();
+            let enabled = favorites_manager
+                .borrow_mut()
+                .toggle_content_index(path_str.as_str());
+            let root = std::path::PathBuf::from(path_str.as_str());
+            if enabled {
+                window.set_status_text(tr!("Indexing the content of {}...", root.display()).into());
+                watch_indexed_folder(&window_weak, root.clone());
+                spawn_content_index_update(&window_weak, root);
+            } else if let Err(err) = indexer::remove(&indexer::index_dir(), &root) {
+                window.set_status_text(
+                    tr!("Error: Unable to remove the content index: {}", err).into(),
+                );
+            } else {
+                unwatch_folder(&root);
+            }
+
+            // Refresh favorites model in the UI.
+            let manager = favorites_manager.borrow();
+            let fav_vec: Vec<FavoriteFolder> = manager
+                .favorites
+                .iter()
+                .map(|f| FavoriteFolder {
+                    path: f.path.clone().into(),
+                    name: f.name.clone().into(),
+                    is_favorite: true,
+                    content_indexed: f.content_indexed,
+                })
+                .collect();
+            window.set_favorites(slint::ModelRc::new(slint::VecModel::from(fav_vec)));
+        }
+    });
+
+    // Favorites: remove selected entry.
+    main_window.on_remove_from_favorites({
+        let window_weak = window_weak.clone();
+        let favorites_manager = favorites_manager.clone();
+        move |path_str| {
+            let window = window_weak.unwrap();
+            favorites_manager
+                .borrow_mut()
+                .remove_favorite(path_str.as_str());
+            let _ = indexer::remove(&indexer::index_dir(), Path::new(path_str.as_str()));
+            unwatch_folder(Path::new(path_str.as_str()));
+
+            // Refresh favorites model in the UI.
+            let manager = favorites_manager.borrow();
+            let fav_vec: Vec<FavoriteFolder> = manager
+                .favorites
+                .iter()
+                .map(|f| FavoriteFolder {
+                    path: f.path.clone().into(),
+                    name: f.name.clone().into(),
+                    is_favorite: true,
+                    content_indexed: f.content_indexed,
+                })
+                .collect();
+            window.set_favorites(slint::ModelRc::new(slint::VecModel::from(fav_vec)));
+        }
+    });
+
+    // Remember the search and the window for the next launch.
+    let restored_geometry = session.as_ref().and_then(|session| session.window);
+    let saves_session = opened_from.is_none();
+    main_window.window().on_close_requested({
+        let window_weak = window_weak.clone();
+        let search_path = search_path.clone();
+        move || {
+            let window = window_weak.unwrap();
+            forget_window(&window);
+            if !saves_session {
+                return slint::CloseRequestResponse::HideWindow;
+            }
+            let session = current_session(&window, &search_path.borrow(), restored_geometry);
+            // The window is about to close: the status bar would not be seen.
+            if let Err(err) = session.save() {
+                rfd::MessageDialog::new()
+                    .set_level(rfd::MessageLevel::Warning)
+                    .set_title("QuickFindr")
+                    .set_description(tr!("Unable to save the session: {}", err))
+                    .show();
+            }
+            slint::CloseRequestResponse::HideWindow
+        }
+    });
+
+    Ok(main_window)
+}
+
+// State of an open window (a fresh one for a window already closed).
+fn window_state(window: &AppWindow) -> Rc<WindowState> {
+    let window_id = window.get_window_id();
+    WINDOWS.with(|windows| {
+        windows
+            .borrow()
+            .get(&window_id)
+            .map(|(_, state)| state.clone())
+            .unwrap_or_default()
+    })
+}
+
+// Runs `apply` on every open window (settings shared by all of them).
+fn for_each_window(apply: impl Fn(&AppWindow)) {
+    let windows: Vec<AppWindow> = WINDOWS.with(|windows| {
+        windows
+            .borrow()
+            .values()
+            .map(|(window, _)| window.clone_strong())
+            .collect()
+    });
+    windows.iter().for_each(apply);
+}
+
+// Stops the scan of a closed window and drops the window with its state once
+// the callback that closed it has returned.
+fn forget_window(window: &AppWindow) {
+    window_state(window).scan.borrow().cancel();
+    let window_id = window.get_window_id();
+    let _ = slint::invoke_from_event_loop(move || {
+        let closed = WINDOWS.with(|windows| windows.borrow_mut().remove(&window_id));
+        drop(closed);
+    });
+}
+
+// How often the system theme is checked (dark-light has no change notification).
+const THEME_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+fn system_is_dark() -> bool {
+    match dark_light::detect() {
+        dark_light::Mode::Light => false,
+        _ => true, // Default to Dark
+    }
+}
+
+// Dark mode and colors of the UI, and the Mica tint on Windows 11.
+fn apply_theme(window: &AppWindow) {
+    let is_dark = match SETTINGS.with(|settings| settings.borrow().theme) {
+        settings::Theme::Dark => true,
+        settings::Theme::Light => false,
+        settings::Theme::System => SYSTEM_DARK.with(Cell::get),
+    };
+    window.set_dark_mode(is_dark);
+
+    let palette =
+        SETTINGS.with(|settings| colors::Palette::resolve(&settings.borrow().colors, is_dark));
+    let rgb = |(r, g, b): colors::Rgb| slint::Color::from_rgb_u8(r, g, b);
+    let theme_colors = window.global::<ThemeColors>();
+    theme_colors.set_accent(rgb(palette.accent));
+    theme_colors.set_background(rgb(palette.background));
+    theme_colors.set_surface(rgb(palette.surface));
+    theme_colors.set_panel(rgb(palette.panel));
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = WinitWindowAccessor::with_winit_window(window.window(), |winit_window| {
+            let _ = apply_mica(winit_window, Some(is_dark));
+        });
+    }
+}
+
+// Registers the global hotkey `text` (empty: none); false, with the reason in
+// the status bar, when it cannot be used.
+fn register_hotkey(window: &AppWindow, text: &str) -> bool {
+    let hotkey = match hotkey::Hotkey::parse(text) {
+        Some(hotkey) => Some(hotkey),
+        None if text.is_empty() => None,
+        None => {
+            window.set_status_text(tr!("Error: Unknown hotkey: {}", text).into());
+            return false;
+        }
+    };
+    let window_weak = window.as_weak();
+    let registered = hotkey::set(hotkey, move || {
+        let _ = window_weak.upgrade_in_event_loop(|window| summon_window(&window));
+    });
+    if let Err(reason) = registered {
+        window.set_status_text(tr!("Error: {}", reason).into());
+        return false;
+    }
+    true
+}
+
+// Brings the window to the front, restored if minimized, ready for a new query.
+fn summon_window(window: &AppWindow) {
+    window.window().set_minimized(false);
+    #[cfg(target_os = "windows")]
+    {
+        let _ = WinitWindowAccessor::with_winit_window(window.window(), |winit_window| {
+            winit_window.focus_window();
+        });
+    }
+    window.invoke_focus_search();
+}
+
+// Translates the labels and the next status messages.
+fn apply_language(language: i18n::Language) {
+    let language = language.resolve();
+    i18n::set_language(language);
+    // Fails only when the catalog is missing: the labels then stay in French.
+    let _ = slint::select_bundled_translation(language.name());
+}
+
+// Scales the fonts and paddings of the whole UI.
+fn apply_density(window: &AppWindow) {
+    let (font_scale, spacing) = SETTINGS.with(|settings| {
+        let settings = settings.borrow();
+        (settings.font_scale(), settings.density.spacing_scale())
+    });
+    let ui_density = window.global::<UiDensity>();
+    ui_density.set_font_scale(font_scale);
+    ui_density.set_spacing(spacing);
+}
+
+// Re-applies the theme of every window when the system switches between
+// light and dark.
+fn watch_system_theme(mut is_dark: bool) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(THEME_POLL_INTERVAL);
+        let now_dark = system_is_dark();
+        if now_dark == is_dark {
+            continue;
+        }
+        is_dark = now_dark;
+        let sent = slint::invoke_from_event_loop(move || {
+            SYSTEM_DARK.with(|dark| dark.set(now_dark));
+            for_each_window(apply_theme);
+        });
+        // The event loop is gone: the app is closing.
+        if sent.is_err() {
+            break;
+        }
+    });
+}
+
+// Puts back the query and search bar options of the last session, and the
+// window where it was.
+fn restore_session(window: &AppWindow, session: &session::Session) {
+    window.set_search_query(session.query.clone().into());
+    window.set_search_content(session.search_content);
+    window.set_case_sensitive(session.case_sensitive);
+    window.set_whole_word(session.whole_word);
+    window.set_match_path(session.match_path);
+    window.set_ignore_accents(session.ignore_accents);
+    window.set_walk_ignored_dirs(session.walk_ignored_dirs);
+    window.set_tracked_only(session.tracked_only);
+    window.set_low_priority(session.low_priority);
+    window.set_search_archives(session.search_archives);
+    window.set_use_regex(session.use_regex);
+    window.set_fuzzy(session.fuzzy);
+    window.set_sort_recent(session.sort_recent);
+    window.set_sort_shallow(session.sort_shallow);
+    window.set_include_hidden(session.include_hidden);
+    window.set_follow_links(session.follow_links);
+    window.set_respect_gitignore(session.respect_gitignore);
+    window.set_include_logs(session.include_logs);
+    window.set_include_binaries(session.include_binaries);
+    window.set_include_temp(session.include_temp);
+    window.set_include_build(session.include_build);
+    window.set_include_archives(session.include_archives);
+    window.set_language_filter(session.language_filter.clone().into());
+    window.set_include_globs(session.include_globs.clone().into());
+    window.set_exclude_globs(session.exclude_globs.clone().into());
+    window.set_size_filter(session.size_filter.clone().into());
+    window.set_max_depth(session.max_depth.clone().into());
+    if let Some(geometry) = session.window {
+        let window = window.window();
+        // Off screen (monitor unplugged), the system places the window.
+        if geometry.is_on_screen() {
+            window.set_position(slint::PhysicalPosition::new(geometry.x, geometry.y));
+        }
+        window.set_size(slint::PhysicalSize::new(geometry.width, geometry.height));
+        window.set_maximized(geometry.maximized);
+    }
+}
+
+// `previous`: the geometry restored at launch, kept when the window is closed
+// maximized (the size to go back to) or minimized (no usable position).
+fn current_session(
+    window: &AppWindow,
+    folder: &Path,
+    previous: Option<session::WindowGeometry>,
+) -> session::Session {
+    let position = window.window().position();
+    let size = window.window().size();
+    let current = session::WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized: false,
+    };
+    let geometry = if window.window().is_minimized() {
+        previous.or(Some(current))
+    } else if window.window().is_maximized() {
+        Some(session::WindowGeometry {
+            maximized: true,
+            ..previous.unwrap_or(current)
+        })
+    } else {
+        Some(current)
+    };
+    session::Session {
+        folder: Some(folder.to_path_buf()),
+        query: window.get_search_query().to_string(),
+        search_content: window.get_search_content(),
+        case_sensitive: window.get_case_sensitive(),
+        whole_word: window.get_whole_word(),
+        match_path: window.get_match_path(),
+        ignore_accents: window.get_ignore_accents(),
+        walk_ignored_dirs: window.get_walk_ignored_dirs(),
+        tracked_only: window.get_tracked_only(),
+        low_priority: window.get_low_priority(),
+        search_archives: window.get_search_archives(),
+        use_regex: window.get_use_regex(),
+        fuzzy: window.get_fuzzy(),
+        sort_recent: window.get_sort_recent(),
+        sort_shallow: window.get_sort_shallow(),
+        include_hidden: window.get_include_hidden(),
+        follow_links: window.get_follow_links(),
+        respect_gitignore: window.get_respect_gitignore(),
+        include_logs: window.get_include_logs(),
+        include_binaries: window.get_include_binaries(),
+        include_temp: window.get_include_temp(),
+        include_build: window.get_include_build(),
+        include_archives: window.get_include_archives(),
+        language_filter: window.get_language_filter().to_string(),
+        include_globs: window.get_include_globs().to_string(),
+        exclude_globs: window.get_exclude_globs().to_string(),
+        size_filter: window.get_size_filter().to_string(),
+        max_depth: window.get_max_depth().to_string(),
+        window: geometry,
+    }
+}
+
+// Helpers called by `engine.rs` via `slint::invoke_from_event_loop`.
+pub fn add_result_to_ui(window: &AppWindow, result: EngineSearchResult) {
+    // Push into the UI-thread model.
+    window_state(window).results.extend(vec![result]);
+}
+
+// Batch insert to reduce event-loop calls; rows are converted once displayed.
+pub fn add_results_batch_to_ui(window: &AppWindow, results: Vec<EngineSearchResult>) {
+    window_state(window).results.extend(results);
+}
+
+// Image results get their thumbnail once the loader thread has decoded it.
+pub(crate) fn request_thumbnail(item: &SearchResult) {
+    if item.is_dir
+        || !thumbnails::is_supported(&item.extension)
+        || archive::is_entry_path(&item.file_path)
+    {
+        return;
+    }
+    let path = item.file_path.to_string();
+    thumbnails::request(
+        PathBuf::from(&path),
+        thumbnails::ICON_SIZE,
+        move |thumbnail| {
+            if let Some(pixels) = thumbnail.as_deref().map(pixel_buffer) {
+                let _ = slint::invoke_from_event_loop(move || show_thumbnail(&path, pixels));
+            }
+        },
+    );
+}
+
+// Sets the thumbnail of the rows of a file in every window, including the
+// ones the refine box hides.
+fn show_thumbnail(path: &str, pixels: slint::SharedPixelBuffer<slint::Rgba8Pixel>) {
+    let thumbnail = slint::Image::from_rgba8(pixels);
+    for state in WINDOWS.with(|windows| {
+        windows
+            .borrow()
+            .values()
+            .map(|(_, state)| state.clone())
+            .collect::<Vec<_>>()
+    }) {
+        let model = &state.results;
+        for index in model.positions(|row| row.file_path == path) {
+            if let Some(mut item) = model.row_data(index) {
+                item.thumbnail = thumbnail.clone();
+                model.set_row_data(index, item);
+            }
+        }
+        if let Some(view) = state.result_view.borrow_mut().as_mut() {
+            view.all
+                .iter_mut()
+                .filter(|row| row.info().file_path == path)
+                .for_each(|row| row.set_thumbnail(&thumbnail));
+        }
+    }
+}
+
+// Pixels of a thumbnail, built off the UI thread (the image itself is not Send).
+fn pixel_buffer(thumbnail: &thumbnails::Thumbnail) -> slint::SharedPixelBuffer<slint::Rgba8Pixel> {
+    slint::SharedPixelBuffer::clone_from_slice(&thumbnail.rgba, thumbnail.width, thumbnail.height)
+}
+
+// Reads every row of the list, as the engine found it or as displayed.
+fn all_rows<T>(window: &AppWindow, read: impl FnOnce(&[Row]) -> T) -> T {
+    window_state(window).results.with_rows(read)
+}
+
+// Shows the results matching every word of the refine box (in the name, the
+// relative path or a matched line) and the extension chip, out of all the
+// results of the search; with neither set, they are all shown again.
+fn apply_result_view(window: &AppWindow) {
+    let state = window_state(window);
+    let all = match state.result_view.borrow_mut().take() {
+        Some(view) => sync_result_view(&state, view),
+        None => state.results.take_rows(),
+    };
+    show_extension_facets(window, file_extensions(&all));
+    let filter = window.get_refine_text().to_lowercase();
+    let terms: Vec<&str> = filter.split_whitespace().collect();
+    let extension = window.get_extension_filter().to_lowercase();
+    if terms.is_empty() && extension.is_empty() {
+        show_rows(window, all);
+        return;
+    }
+
+    let shown: Vec<usize> = (0..all.len())
+        .filter(|&index| {
+            let row = &all[index];
+            let item = row.info();
+            refine_matches(row, &terms)
+                && (extension.is_empty()
+                    || (!item.is_dir && item.extension.to_lowercase() == extension))
+        })
+        .collect();
+    let count = shown.len();
+    let rows = shown.iter().map(|&index| all[index].clone()).collect();
+    show_rows(window, rows);
+    window.set_status_text(tr!("Filtered: {} of {} results", count, all.len()).into());
+    *state.result_view.borrow_mut() = Some(ResultView { all, shown });
+}
+
+// Extension chips of the whole list (not only the rows the filters leave).
+pub fn update_extension_facets(window: &AppWindow) {
+    let state = window_state(window);
+    let extensions = state
+        .result_view
+        .borrow()
+        .as_ref()
+        .map(|view| file_extensions(&view.all));
+    let extensions = extensions.unwrap_or_else(|| {
+        let mut extensions = Vec::new();
+        state.results.for_each(|_, row| {
+            if !row.is_dir {
+                extensions.push(row.extension.to_string());
+            }
+        });
+        extensions
+    });
+    show_extension_facets(window, extensions);
+}
+
+fn file_extensions(rows: &[Row]) -> Vec<String> {
+    rows.iter()
+        .map(Row::info)
+        .filter(|item| !item.is_dir)
+        .map(|item| item.extension.to_string())
+        .collect()
+}
+
+fn show_extension_facets(window: &AppWindow, extensions: Vec<String>) {
+    // Files without an extension get no chip.
+    let files = extensions.iter().filter(|extension| !extension.is_empty());
+    let facets: Vec<ExtensionFacet> = facets::extension_counts(files.map(String::as_str))
+        .into_iter()
+        .map(|(extension, count)| ExtensionFacet {
+            extension: extension.into(),
+            count: count as i32,
+        })
+        .collect();
+    window.set_extension_facets(Rc::new(VecModel::from(facets)).into());
+}
+
+// The full list with the displayed rows written back (they may have been
+// renamed, checked or expanded since); rows streamed in since are appended.
+fn sync_result_view(state: &WindowState, view: ResultView) -> Vec<Row> {
+    let ResultView { mut all, shown } = view;
+    let displayed = state.results.take_rows();
+    for (position, row) in displayed.into_iter().enumerate() {
+        match shown.get(position) {
+            Some(&index) => all[index] = row,
+            None => all.push(row),
+        }
+    }
+    all
+}
+
+fn refine_matches(row: &Row, terms: &[&str]) -> bool {
+    let item = row.info();
+    let mut text = format!("{}\n{}", item.file_name, item.relative_path);
+    for hit in row.lines() {
+        text.push('\n');
+        text.push_str(&hit.text);
+    }
+    let text = text.to_lowercase();
+    terms.iter().all(|term| text.contains(term))
+}
+
+// Removes a displayed row for good: from the list, the full list behind the
+// refine box and the cached search the time filters narrow.
+fn dismiss_result(window: &AppWindow, index: usize) -> Option<SearchResult> {
+    let state = window_state(window);
+    let item = state.results.remove(index)?;
+    if let Some(view) = state.result_view.borrow_mut().as_mut() {
+        if index < view.shown.len() {
+            let removed = view.shown.remove(index);
+            view.all.remove(removed);
+            for shown in view.shown.iter_mut().filter(|shown| **shown > removed) {
+                *shown -= 1;
+            }
+        }
+    }
+    if let Some(cached) = state.last_search.borrow_mut().as_mut() {
+        cached
+            .results
+            .retain(|result| result.file_path != item.file_path.as_str());
+    }
+    Some(item)
+}
+
+// Shows rows of the list (converted for the UI once displayed).
+fn show_rows(window: &AppWindow, rows: Vec<Row>) {
+    let total = rows.len();
+    window_state(window).results.set_rows(rows);
+    window.set_total_results(total as i32);
+    update_checked_count(window);
+}
+
+fn exported_results(window: &AppWindow) -> Vec<export::ExportedResult> {
+    all_rows(window, |rows| {
+        rows.iter()
+            .map(|row| {
+                let item = row.info();
+                export::ExportedResult {
+                    path: item.file_path.to_string(),
+                    name: item.file_name.to_string(),
+                    relative_path: item.relative_path.to_string(),
+                    extension: item.extension.to_string(),
+                    is_dir: item.is_dir,
+                    matches: row
+                        .lines()
+                        .into_iter()
+                        .map(|hit| export::ExportedMatch {
+                            line_number: hit.line_number,
+                            column: hit.column,
+                            text: hit.text,
+                        })
+                        .collect(),
+                }
+            })
+            .collect()
+    })
+}
+
+// Active search options as labelled in the search bar, for the HTML report.
+fn search_options(window: &AppWindow) -> Vec<String> {
+    let chips = [
+        (tr!("Content"), window.get_search_content()),
+        (tr!("Match case"), window.get_case_sensitive()),
+        (tr!("Whole word"), window.get_whole_word()),
+        (tr!("Full path"), window.get_match_path()),
+        (tr!("Ignore accents"), window.get_ignore_accents()),
+        (
+            tr!("Include ignored folders"),
+            window.get_walk_ignored_dirs(),
+        ),
+        (tr!("Git files"), window.get_tracked_only()),
+        ("Archives", window.get_search_archives()),
+        ("Regex", window.get_use_regex()),
+        (tr!("Fuzzy"), window.get_fuzzy()),
+    ];
+    let mut options: Vec<String> = chips
+        .iter()
+        .filter(|(_, active)| *active)
+        .map(|(label, _)| label.to_string())
+        .collect();
+    let language = window.get_language_filter();
+    if !language.trim().is_empty() {
+        options.push(tr!("Language: {}", language.trim()));
+    }
+    let size = window.get_size_filter();
+    if !size.trim().is_empty() {
+        options.push(tr!("Size: {}", size.trim()));
+    }
+    options
+}
+
+// Files behind every result; archive entries are left out.
+fn result_file_paths(window: &AppWindow) -> Vec<std::path::PathBuf> {
+    let mut paths: Vec<std::path::PathBuf> = Vec::new();
+    window_state(window).results.for_each(|_, row| {
+        if !row.is_dir && !archive::is_entry_path(row.file_path) {
+            paths.push(row.file_path.into());
+        }
+    });
+    // Keep the displayed order (rename counters follow it).
+    let mut seen = std::collections::HashSet::new();
+    paths.retain(|path| seen.insert(path.clone()));
+    paths
+}
+
+// Files of the batch rename: the checked results in selection mode (when any
+// is checked), every result otherwise.
+fn rename_targets(window: &AppWindow) -> Vec<std::path::PathBuf> {
+    let paths = result_file_paths(window);
+    if !window.get_select_mode() || window.get_checked_count() == 0 {
+        return paths;
+    }
+    let mut checked = std::collections::HashSet::new();
+    window_state(window).results.for_each(|_, row| {
+        if row.checked {
+            checked.insert(row.file_path.to_string());
+        }
+    });
+    paths
+        .into_iter()
+        .filter(|path| checked.contains(path.to_string_lossy().as_ref()))
+        .collect()
+}
+
+fn set_rename_preview(window: &AppWindow, plans: Vec<rename::RenamePlan>) {
+    let conflicts = plans.iter().filter(|plan| plan.conflict.is_some()).count();
+    let changes = plans.iter().filter(|plan| !plan.is_unchanged()).count();
+    window.set_rename_conflicts(conflicts as i32);
+    window.set_rename_changes(changes as i32);
+    window.set_rename_status(tr!("{} files renamed, {} conflicts", changes, conflicts).into());
+
+    let file_name = |path: &Path| path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let lines: Vec<RenamePreviewLine> = plans
+        .iter()
+        .map(|plan| RenamePreviewLine {
+            old_name: file_name(&plan.from).into(),
+            new_name: file_name(&plan.to).into(),
+            conflict: plan.conflict.clone().unwrap_or_default().into(),
+        })
+        .collect();
+    window.set_rename_preview(Rc::new(VecModel::from(lines)).into());
+    *window_state(window).rename_plans.borrow_mut() = plans;
+}
+
+// Points the renamed results at their new paths.
+fn update_renamed_results(
+    window: &AppWindow,
+    renamed: &[(std::path::PathBuf, std::path::PathBuf)],
+) {
+    if renamed.is_empty() {
+        return;
+    }
+    let targets: std::collections::HashMap<String, &std::path::PathBuf> = renamed
+        .iter()
+        .map(|(from, to)| (from.to_string_lossy().to_string(), to))
+        .collect();
+    let update = |item: &mut SearchResult| {
+        let Some(to) = targets.get(item.file_path.as_str()) else {
+            return false;
+        };
+        let name = to.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let extension = to.extension().unwrap_or_default().to_string_lossy().to_string();
+        item.relative_path = Path::new(item.relative_path.as_str())
+            .with_file_name(&name)
+            .to_string_lossy()
+            .to_string()
+            .into();
+        item.file_path = to.to_string_lossy().to_string().into();
+        item.icon_color = get_icon_color(&extension);
+        item.icon = file_icon(&extension, false);
+        item.name_segments = text_segments(&name, &[]);
+        item.file_name = name.into();
+        item.extension = extension.into();
+        true
+    };
+
+    let model = &window_state(window).results;
+    for index in model.positions(|row| targets.contains_key(row.file_path)) {
+        if let Some(mut item) = model.row_data(index) {
+            if update(&mut item) {
+                model.set_row_data(index, item);
+            }
+        }
+    }
+}
+
+fn set_replace_preview(window: &AppWindow, changes: Vec<replace::LineChange>) {
+    let files = changes
+        .iter()
+        .map(|c| &c.file_path)
+        .collect::<std::collections::HashSet<_>>()
+        .len();
+    window.set_replace_status(tr!("{} lines in {} files", changes.len(), files).into());
+
+    let lines: Vec<ReplacePreviewLine> = changes
+        .into_iter()
+        .map(|change| ReplacePreviewLine {
+            file_name: change
+                .file_path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string()
+                .into(),
+            file_path: change.file_path.to_string_lossy().to_string().into(),
+            line_number: change.line_number as i32,
+            before: change.before.into(),
+            after: change.after.into(),
+            checked: true,
+        })
+        .collect();
+    window_state(window).replace_model.set_vec(lines);
+    update_replace_checked_count(window);
+}
+
+fn update_replace_checked_count(window: &AppWindow) {
+    let count = window_state(window)
+        .replace_model
+        .iter()
+        .filter(|line| line.checked)
+        .count();
+    window.set_replace_checked_count(count as i32);
+}
+
+// Stores a finished folder comparison and shows it.
+pub fn set_compare_report(window: &AppWindow, entries: Vec<compare::DiffEntry>) {
+    *window_state(window).compare_report.borrow_mut() = entries;
+    show_compare_results(window);
+}
+
+// Comparison entries allowed by the filter chips.
+fn visible_compare_entries(window: &AppWindow) -> Vec<compare::DiffEntry> {
+    window_state(window)
+        .compare_report
+        .borrow()
+        .iter()
+        .filter(|entry| match entry.kind {
+            compare::DiffKind::OnlyInA => window.get_compare_show_only_a(),
+            compare::DiffKind::OnlyInB => window.get_compare_show_only_b(),
+            compare::DiffKind::Different => window.get_compare_show_different(),
+        })
+        .cloned()
+        .collect()
+}
+
+fn show_compare_results(window: &AppWindow) {
+    let results = visible_compare_entries(window)
+        .iter()
+        .map(compare::DiffEntry::to_search_result)
+        .collect();
+    show_results(window, results);
+}
+
+// Replaces the displayed results.
+pub fn show_results(window: &AppWindow, results: Vec<EngineSearchResult>) {
+    let total = results.len();
+    let state = window_state(window);
+    state.results.set_results(results);
+    window.set_total_results(total as i32);
+
+    // The new list is the one the refine box narrows.
+    state.result_view.borrow_mut().take();
+    let filtered =
+        !window.get_refine_text().trim().is_empty() || !window.get_extension_filter().is_empty();
+    if window.get_results_mode() == "search" && filtered {
+        apply_result_view(window);
+    } else {
+        update_extension_facets(window);
+    }
+}
+
+// Fills the panel of paths the scan could not read; `listed` may hold fewer
+// paths than `count`.
+pub fn show_skipped_paths(window: &AppWindow, count: usize, listed: Vec<String>) {
+    let paths: Vec<slint::SharedString> = listed.into_iter().map(Into::into).collect();
+    window.set_skipped_count(count as i32);
+    window.set_skipped_paths(slint::ModelRc::new(slint::VecModel::from(paths)));
+    window.set_skipped_expanded(false);
+}
+
+// Paths a batch action left untouched, with the reason, listed in the skipped
+// paths panel (the status text only counts them).
+fn show_failed_paths(window: &AppWindow, failed: &[(PathBuf, String)]) {
+    if failed.is_empty() {
+        return;
+    }
+    let listed = failed
+        .iter()
+        .map(|(path, reason)| format!("{}: {}", path.display(), reason))
+        .collect();
+    show_skipped_paths(window, failed.len(), listed);
+}
+
+// The file on disk behind a result: the archive of an archive entry.
+fn on_disk_path(file_path: &str) -> &str {
+    archive::split_entry_path(file_path).map_or(file_path, |(archive, _)| archive)
+}
+
+// Search defaults of the project in `root` (see `project`), set in the search
+// bar where they can still be changed.
+fn apply_project_config(window: &AppWindow, root: &Path) {
+    match project::load(root) {
+        Ok(Some(config)) => {
+            window.set_exclude_globs(config.exclude_globs.join(", ").into());
+            if let Some(search_content) = config.search_content {
+                window.set_search_content(search_content);
+            }
+            if let Some(language) = config.language {
+                window.set_language_filter(language.to_lowercase().into());
+            }
+            window.set_status_text(tr!("Settings loaded from {}", project::FILE_NAME).into());
+        }
+        Ok(None) => {}
+        Err(err) => {
+            window.set_status_text(tr!("Error: Invalid {}: {}", project::FILE_NAME, err).into())
+        }
+    }
+}
+
+// Cancels the running scan, if any, and returns the token of a new one.
+fn start_scan(window: &AppWindow) -> engine::SearchToken {
+    window.set_scan_paused(false);
+    window.set_checked_count(0);
+    show_skipped_paths(window, 0, Vec::new());
+    let state = window_state(window);
+    let mut scan = state.scan.borrow_mut();
+    scan.cancel();
+    *scan = engine::SearchToken::default();
+    scan.clone()
+}
+
+fn indexed_folder_count() -> i32 {
+    SETTINGS.with(|settings| settings.borrow().indexed_roots.len() as i32)
+}
+
+// Rebuilds the file-name index of a folder in the background.
+fn spawn_index_update(
+    window_weak: &slint::Weak<AppWindow>,
+    root: std::path::PathBuf,
+    respect_gitignore: bool,
+) {
+    let window_weak = window_weak.clone();
+    std::thread::spawn(move || {
+        let start_time = std::time::Instant::now();
+        let status = match index::update(&index::index_dir(), &root, respect_gitignore) {
+            Ok(count) => tr!(
+                "Index of {} updated: {} files in {}ms",
+                root.display(),
+                count,
+                start_time.elapsed().as_millis()
+            ),
+            Err(err) => tr!("Error: Unable to index {}: {}", root.display(), err),
+        };
+        let _ = window_weak.upgrade_in_event_loop(move |window| {
+            window.set_status_text(status.into());
+        });
+    });
+}
+
+// Keeps the indexes of a folder current from file system events (once per folder).
+fn watch_indexed_folder(window_weak: &slint::Weak<AppWindow>, root: PathBuf) {
+    WATCHERS.with(|watchers| {
+        let mut watchers = watchers.borrow_mut();
+        if watchers.contains_key(&root) {
+            return;
+        }
+        let updates = window_weak.clone();
+        let watched = watcher::watch_indexes(root.clone(), move |status| {
+            let _ = updates.upgrade_in_event_loop(move |window| {
+                window.set_index_status(status.into());
+            });
+        });
+        match watched {
+            Ok(watcher) => {
+                watchers.insert(root, watcher);
+            }
+            Err(err) => {
+                if let Some(window) = window_weak.upgrade() {
+                    window.set_index_status(
+                        tr!("Error: Unable to watch {}: {}", root.display(), err).into(),
+                    );
+                }
+            }
+        }
+    });
+}
+
+// Watches the folder of a live search (none when live mode is off) and re-runs
+// the search when its files change.
+fn watch_live_search(window: &AppWindow, root: &Path, live: bool) {
+    let state = window_state(window);
+    let mut live_watch = state.live_watch.borrow_mut();
+    if !live {
+        *live_watch = None;
+        return;
+    }
+    if live_watch.as_ref().is_some_and(|(watched, _)| watched == root) {
+        return;
+    }
+    let reruns = window.as_weak();
+    let watched = watcher::watch(root, watcher::LIVE_DEBOUNCE, move |_| {
+        let _ = reruns.upgrade_in_event_loop(|window| {
+            // Tools and folder comparisons show other results: no re-run over them.
+            if window.get_live() && window.get_results_mode() == "search" {
+                window_state(&window).live_refresh.set(true);
+                window.invoke_rerun_search();
+            }
+        });
+    });
+    *live_watch = match watched {
+        Ok(watcher) => Some((root.to_path_buf(), watcher)),
+        Err(err) => {
+            window
+                .set_status_text(tr!("Error: Unable to watch {}: {}", root.display(), err).into());
+            None
+        }
+    };
+}
+
+// Stops watching a folder that has no index left.
+fn unwatch_folder(root: &Path) {
+    if !index::exists(&index::index_dir(), root) && !indexer::exists(&indexer::index_dir(), root)
+    {
+        WATCHERS.with(|watchers| watchers.borrow_mut().remove(root));
+    }
+}
+
+// Updates the full-text index of a favorite folder in the background.
+fn spawn_content_index_update(window_weak: &slint::Weak<AppWindow>, root: std::path::PathBuf) {
+    let window_weak = window_weak.clone();
+    std::thread::spawn(move || {
+        let start_time = std::time::Instant::now();
+        let status = match indexer::update(&indexer::index_dir(), &root) {
+            Ok(report) => tr!(
+                "Content index of {} updated: {} files indexed, {} removed ({} in the index) in {}ms",
+                root.display(),
+                report.indexed,
+                report.removed,
+                report.files,
+                start_time.elapsed().as_millis()
+            ),
+            Err(err) => tr!("Error: Unable to index {}: {}", root.display(), err),
+        };
+        let _ = window_weak.upgrade_in_event_loop(move |window| {
+            window.set_status_text(status.into());
+        });
+    });
+}
+
+// Applies a change to the settings and saves them (errors go to the status bar).
+fn update_settings(window: &AppWindow, update: impl FnOnce(&mut settings::Settings)) {
+    let saved = SETTINGS.with(|settings| {
+        let mut settings = settings.borrow_mut();
+        update(&mut settings);
+        settings.save()
+    });
+    if let Err(err) = saved {
+        window.set_status_text(tr!("Error: Unable to save settings: {}", err).into());
+    }
+}
+
+// Appends to the audit log when the user enabled it. A failure stays shown in
+// the status bar until an entry is written again.
+fn record_audit(window: &AppWindow, event: audit::AuditEvent) {
+    if !SETTINGS.with(|settings| settings.borrow().audit_log_enabled) {
+        return;
+    }
+    match AUDIT_LOG.with(|log| log.borrow_mut().append(event)) {
+        Ok(_) => window.set_audit_error("".into()),
+        Err(err) => {
+            window.set_audit_error(tr!("Audit log not written: {}", err).into());
+        }
+    }
+}
+
+// Called by the engine at the end of a scan with the final result count.
+pub fn record_search_audit(window: &AppWindow, result_count: usize) {
+    let pending = window_state(window)
+        .pending_search_audit
+        .borrow_mut()
+        .take();
+    if let Some(mut event) = pending {
+        event.result_count = result_count;
+        record_audit(window, event);
+    }
+}
+
+// Fills the audit viewer (newest first) and reports whether the chain is intact.
+fn show_audit_log(window: &AppWindow) {
+    let path = audit::log_path();
+    let (content, head) = match audit::read_entries(&path).and_then(|content| {
+        let head = audit::read_head(&path)?;
+        Ok((content, head))
+    }) {
+        Ok(read) => read,
+        Err(err) => {
+            window.set_audit_log_rows(slint::ModelRc::default());
+            window.set_audit_log_status(tr!("Error: Unable to read the log: {}", err).into());
+            return;
+        }
+    };
+    let entries = &content.entries;
+
+    let status = if let Some(line) = content.bad_lines.first() {
+        tr!(
+            "Warning: line {} of the log is not a valid entry ({} such lines)",
+            line,
+            content.bad_lines.len()
+        )
+    } else if let Some(index) = audit::verify_chain(entries) {
+        tr!(
+            "Warning: the log was modified at entry {} of {}",
+            index + 1,
+            entries.len()
+        )
+    } else if let Some(head) = head.filter(|head| !content.matches_head(head)) {
+        tr!(
+            "Warning: the log holds {} entries, {} were written",
+            entries.len(),
+            head.count
+        )
+    } else {
+        tr!("{} entries, integrity verified", entries.len())
+    };
+
+    let rows: Vec<AuditLogRow> = entries
+        .iter()
+        .rev()
+        .map(|entry| AuditLogRow {
+            time: chrono::DateTime::from_timestamp(entry.timestamp as i64, 0)
+                .map(|time| {
+                    time.with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string()
+                })
+                .unwrap_or_default()
+                .into(),
+            user: entry.user.clone().into(),
+            action: entry.action.clone().into(),
+            scope: entry.scope.clone().into(),
+            details: entry.details.clone().into(),
+            count: entry.result_count as i32,
+        })
+        .collect();
+
+    window.set_audit_log_rows(slint::ModelRc::new(VecModel::from(rows)));
+    window.set_audit_log_status(status.into());
+}
+
+// Modification-time bounds of the search: the time chip and the period of the
+// options panel both apply, so the later start wins.
+fn modified_bounds(
+    window: &AppWindow,
+) -> Result<(Option<std::time::SystemTime>, Option<std::time::SystemTime>), String> {
+    let now = chrono::Local::now();
+    let range = match window.get_date_range_mode() {
+        1 => engine::DateRange::last_days(7, now),
+        2 => engine::DateRange::last_days(30, now),
+        3 => engine::DateRange::parse(&window.get_date_from(), &window.get_date_to())?,
+        _ => engine::DateRange::default(),
+    };
+    let chip = engine::TimeFilter::from_name(&window.get_time_filter()).since(now);
+    let since = match (chip, range.from) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    };
+    Ok((since, range.until))
+}
+
+pub fn cache_search_results(
+    window: &AppWindow,
+    modified_since: Option<std::time::SystemTime>,
+    modified_until: Option<std::time::SystemTime>,
+    results: Vec<EngineSearchResult>,
+) {
+    *window_state(window).last_search.borrow_mut() = Some(CachedSearch {
+        modified_since,
+        modified_until,
+        results,
+    });
+}
+
+// Converts an engine result to the Slint struct.
+pub(crate) fn to_ui_result(result: EngineSearchResult) -> SearchResult {
+    let color = if result.is_dir {
+        slint::Color::from_rgb_u8(220, 170, 60) // Folder
+    } else {
+        get_icon_color(&result.extension)
+    };
+    let icon = file_icon(&result.extension, result.is_dir);
+
+    SearchResult {
+        name_segments: text_segments(&result.file_name, &result.name_ranges),
+        file_name: result.file_name.into(),
         file_path: result.file_path.into(),
         relative_path: result.relative_path.into(),
         extension: result.extension.into(),
         line_match: result.line_match.into(),
+        matches: slint::ModelRc::new(VecModel::from(
+            result
+                .matches
+                .into_iter()
+                .map(|hit| LineHit {
+                    line_number: hit.line_number as i32,
+                    column: hit.column as i32,
+                    segments: text_segments(&hit.text, &hit.ranges),
+                })
+                .collect::<Vec<_>>(),
+        )),
+        expanded: false,
         icon_color: color,
-    };
+        icon,
+        is_dir: result.is_dir,
+        size: result
+            .size
+            .map(shell::short_size)
+            .unwrap_or_default()
+            .into(),
+        modified: result
+            .modified
+            .map(|time| {
+                chrono::DateTime::<chrono::Local>::from(time)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default()
+            .into(),
+        git_status: match result.git_status {
+            Some(git::FileStatus::Modified) => "modifié",
+            Some(git::FileStatus::Staged) => "indexé",
+            Some(git::FileStatus::Untracked) => "non suivi",
+            None => "",
+        }
+        .into(),
+        also_at: result.also_at.join(", ").into(),
+        thumbnail: slint::Image::default(),
+        checked: false,
+    }
+}
 
-    // Push into the UI-thread model.
-    RESULTS_MODEL.with(|model| {
-        model.borrow_mut().push(ui_result);
-    });
-}
-
-// Batch insert to reduce event-loop calls.
-pub fn add_results_batch_to_ui(_window: &AppWindow, results: Vec<EngineSearchResult>) {
-    RESULTS_MODEL.with(|model| {
-        let model_ref = model.borrow_mut();
-        for result in results {
-            let color = get_icon_color(&result.extension);
-            let ui_result = SearchResult {
-                file_name: result.file_name.into(),
-                file_path: result.file_path.into(),
-                relative_path: result.relative_path.into(),
-                extension: result.extension.into(),
-                line_match: result.line_match.into(),
-                icon_color: color,
+// Shows a larger thumbnail of an image in the detail pane.
+fn load_image_preview(window_weak: slint::Weak<AppWindow>, path: String) {
+    thumbnails::request(
+        PathBuf::from(&path),
+        thumbnails::PREVIEW_SIZE,
+        move |thumbnail| {
+            let Some(pixels) = thumbnail.as_deref().map(pixel_buffer) else {
+                return;
             };
-            model_ref.push(ui_result);
+            let _ = slint::invoke_from_event_loop(move || {
+                if let Some(window) = window_weak.upgrade() {
+                    if window.get_detail_item().file_path == path.as_str() {
+                        window.set_detail_image(slint::Image::from_rgba8(pixels));
+                    }
+                }
+            });
+        },
+    );
+}
+
+// Shows the start of a binary file in the detail pane (text files show none).
+fn load_hex_preview(window_weak: slint::Weak<AppWindow>, path: String) {
+    std::thread::spawn(move || {
+        let Ok(head) = hexdump::read_head(Path::new(&path), hexdump::PREVIEW_SIZE) else {
+            return;
+        };
+        if !hexdump::looks_binary(&head) {
+            return;
         }
+        let dump = hexdump::dump(&head);
+        let _ = slint::invoke_from_event_loop(move || {
+            if let Some(window) = window_weak.upgrade() {
+                // The pane may show another file by now.
+                if window.get_detail_item().file_path == path.as_str() {
+                    window.set_detail_hex(dump.into());
+                }
+            }
+        });
+    });
+}
+
+// Shows a Markdown file rendered (headings, lists, code blocks) in the detail
+// pane.
+fn load_markdown_preview(window_weak: slint::Weak<AppWindow>, path: String) {
+    std::thread::spawn(move || {
+        let Ok(blocks) = markdown::read_preview(Path::new(&path)) else {
+            return;
+        };
+        let _ = slint::invoke_from_event_loop(move || {
+            if let Some(window) = window_weak.upgrade() {
+                if window.get_detail_item().file_path == path.as_str() {
+                    window.set_detail_markdown(markdown_blocks(blocks));
+                }
+            }
+        });
     });
 }
 
-// Store remaining results for pagination ("Load more").
-pub fn set_remaining_results(results: Vec<EngineSearchResult>) {
-    REMAINING_RESULTS.with(|remaining| {
-        *remaining.borrow_mut() = results
+fn markdown_blocks(blocks: Vec<markdown::Block>) -> slint::ModelRc<MarkdownBlock> {
+    use markdown::BlockKind;
+
+    slint::ModelRc::new(VecModel::from(
+        blocks
             .into_iter()
-            .map(|r| {
-                let color = get_icon_color(&r.extension);
-                SearchResult {
-                    file_name: r.file_name.into(),
-                    file_path: r.file_path.into(),
-                    relative_path: r.relative_path.into(),
-                    extension: r.extension.into(),
-                    line_match: r.line_match.into(),
-                    icon_color: color,
+            .map(|block| {
+                let (kind, level) = match block.kind {
+                    BlockKind::Heading(level) => ("heading", level),
+                    BlockKind::Paragraph => ("paragraph", 0),
+                    BlockKind::ListItem(depth) => ("item", depth),
+                    BlockKind::Code => ("code", 0),
+                    BlockKind::Quote => ("quote", 0),
+                    BlockKind::Rule => ("rule", 0),
+                };
+                MarkdownBlock {
+                    kind: kind.into(),
+                    level: level as i32,
+                    text: block.text.into(),
                 }
             })
-            .collect();
-    });
+            .collect::<Vec<_>>(),
+    ))
+}
+
+// Text split into its matched and unmatched parts, for bold highlights.
+fn text_segments(text: &str, ranges: &[std::ops::Range<usize>]) -> slint::ModelRc<TextSegment> {
+    slint::ModelRc::new(VecModel::from(
+        engine::split_highlights(text, ranges)
+            .into_iter()
+            .map(|(text, matched)| TextSegment {
+                text: text.into(),
+                matched,
+            })
+            .collect::<Vec<_>>(),
+    ))
+}
+
+fn update_checked_count(window: &AppWindow) {
+    let count = window_state(window)
+        .results
+        .positions(|row| row.checked)
+        .len();
+    window.set_checked_count(count as i32);
+}
+
+// System icon of the files with this extension, or of folders; empty when
+// the system has none (the colored badge is shown instead).
+fn file_icon(extension: &str, is_dir: bool) -> slint::Image {
+    let key = if is_dir {
+        "/".to_string()
+    } else {
+        extension.to_lowercase()
+    };
+    FILE_ICONS.with(|icons| {
+        icons
+            .borrow_mut()
+            .entry(key)
+            .or_insert_with(|| {
+                file_icons::icon(extension, is_dir)
+                    .map(|icon| slint::Image::from_rgba8(pixel_buffer(&icon)))
+                    .unwrap_or_default()
+            })
+            .clone()
+    })
 }
 
+// Badge color of the results with this extension (settings `icon_colors`).
 fn get_icon_color(extension: &str) -> slint::Color {
-    match extension.to_lowercase().as_str() {
-        "rs" => slint::Color::from_rgb_u8(222, 165, 132), // Rust
-        "js" | "ts" | "jsx" | "tsx" => slint::Color::from_rgb_u8(241, 224, 90), // JS/TS
-        "html" | "css" | "scss" => slint::Color::from_rgb_u8(227, 76, 38), // Web
-        "json" | "toml" | "yaml" | "yml" => slint::Color::from_rgb_u8(133, 76, 199), // Config
-        "md" | "txt" => slint::Color::from_rgb_u8(0, 122, 204), // Docs
-        "pdf" => slint::Color::from_rgb_u8(180, 15, 15),  // PDF
-        "zip" | "tar" | "gz" => slint::Color::from_rgb_u8(255, 200, 0), // Archive
-        "png" | "jpg" | "jpeg" | "svg" => slint::Color::from_rgb_u8(100, 200, 100), // Images
-        "java" | "kt" => slint::Color::from_rgb_u8(180, 100, 50), // JVM
-        "py" => slint::Color::from_rgb_u8(53, 114, 165),  // Python
-        "c" | "cpp" | "h" => slint::Color::from_rgb_u8(85, 85, 85), // C/C++
-        "exe" | "dll" | "bat" | "ps1" => slint::Color::from_rgb_u8(0, 120, 212), // System
-        _ => slint::Color::from_rgb_u8(128, 128, 128),    // Default
-    }
+    let (r, g, b) =
+        SETTINGS.with(|settings| colors::icon_color(&settings.borrow().icon_colors, extension));
+    slint::Color::from_rgb_u8(r, g, b)
 }
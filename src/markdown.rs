@@ -0,0 +1,251 @@
+// Markdown rendering for the detail pane: the document as a list of styled
+// blocks (headings, paragraphs, list items, code blocks, quotes, rules).
+// Inline styles are flattened to plain text.
+
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+// Start of the file rendered (256 KB).
+const MAX_PREVIEW_SIZE: u64 = 256 * 1024;
+// Blocks rendered, at most.
+const MAX_BLOCKS: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockKind {
+    // Level 1 to 6.
+    Heading(usize),
+    Paragraph,
+    // Nesting depth of the list, from 1.
+    ListItem(usize),
+    Code,
+    Quote,
+    Rule,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    pub kind: BlockKind,
+    pub text: String,
+}
+
+pub fn is_markdown(extension: &str) -> bool {
+    matches!(
+        extension.to_lowercase().as_str(),
+        "md" | "markdown" | "mdown" | "mkd"
+    )
+}
+
+// Blocks of the start of a Markdown file.
+pub fn read_preview(path: &Path) -> io::Result<Vec<Block>> {
+    let mut bytes = Vec::new();
+    File::open(path)?
+        .take(MAX_PREVIEW_SIZE)
+        .read_to_end(&mut bytes)?;
+    Ok(blocks(&String::from_utf8_lossy(&bytes)))
+}
+
+pub fn blocks(source: &str) -> Vec<Block> {
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut current: Option<Block> = None;
+    // Next number of each open list (None: bullets).
+    let mut lists: Vec<Option<u64>> = Vec::new();
+    let mut quote_depth = 0;
+    // Whether the open list item has any text yet.
+    let mut item_has_text = false;
+
+    let flush = |blocks: &mut Vec<Block>, current: &mut Option<Block>| {
+        if let Some(mut block) = current.take() {
+            block.text = block.text.trim_end().to_string();
+            if !block.text.is_empty() {
+                blocks.push(block);
+            }
+        }
+    };
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    for event in Parser::new_ext(source, options) {
+        if blocks.len() >= MAX_BLOCKS {
+            break;
+        }
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                flush(&mut blocks, &mut current);
+                current = Some(Block {
+                    kind: BlockKind::Heading(level as usize),
+                    text: String::new(),
+                });
+            }
+            Event::Start(Tag::Paragraph) => {
+                // The first paragraph of a list item is the item itself.
+                let in_empty_item = !item_has_text
+                    && current
+                        .as_ref()
+                        .is_some_and(|block| matches!(block.kind, BlockKind::ListItem(_)));
+                if !in_empty_item {
+                    flush(&mut blocks, &mut current);
+                    current = Some(Block {
+                        kind: if quote_depth > 0 {
+                            BlockKind::Quote
+                        } else {
+                            BlockKind::Paragraph
+                        },
+                        text: String::new(),
+                    });
+                }
+            }
+            Event::Start(Tag::List(start)) => {
+                flush(&mut blocks, &mut current);
+                lists.push(start);
+            }
+            Event::End(TagEnd::List { .. }) => {
+                flush(&mut blocks, &mut current);
+                lists.pop();
+            }
+            Event::Start(Tag::Item) => {
+                flush(&mut blocks, &mut current);
+                let marker = match lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => "• ".to_string(),
+                };
+                item_has_text = false;
+                current = Some(Block {
+                    kind: BlockKind::ListItem(lists.len().max(1)),
+                    text: marker,
+                });
+            }
+            Event::Start(Tag::CodeBlock { .. }) => {
+                flush(&mut blocks, &mut current);
+                current = Some(Block {
+                    kind: BlockKind::Code,
+                    text: String::new(),
+                });
+            }
+            Event::Start(Tag::BlockQuote { .. }) => {
+                flush(&mut blocks, &mut current);
+                quote_depth += 1;
+            }
+            Event::End(TagEnd::BlockQuote { .. }) => {
+                flush(&mut blocks, &mut current);
+                quote_depth -= 1;
+            }
+            // A table row is one line, its cells separated by bars.
+            Event::Start(Tag::TableHead | Tag::TableRow) => {
+                flush(&mut blocks, &mut current);
+                current = Some(Block {
+                    kind: BlockKind::Paragraph,
+                    text: String::new(),
+                });
+            }
+            Event::End(TagEnd::TableCell) => {
+                if let Some(block) = current.as_mut() {
+                    block.text.push_str(" | ");
+                }
+            }
+            Event::End(TagEnd::TableHead | TagEnd::TableRow) => {
+                if let Some(block) = current.as_mut() {
+                    block.text = block.text.trim_end_matches(" | ").to_string();
+                }
+                flush(&mut blocks, &mut current);
+            }
+            Event::End(
+                TagEnd::Heading { .. } | TagEnd::Paragraph | TagEnd::Item | TagEnd::CodeBlock,
+            ) => flush(&mut blocks, &mut current),
+            Event::Text(text) | Event::Code(text) => {
+                item_has_text = true;
+                current
+                    .get_or_insert_with(|| Block {
+                        kind: BlockKind::Paragraph,
+                        text: String::new(),
+                    })
+                    .text
+                    .push_str(&text);
+            }
+            Event::TaskListMarker(checked) => {
+                if let Some(block) = current.as_mut() {
+                    block.text = if checked { "☑ " } else { "☐ " }.to_string();
+                }
+            }
+            Event::SoftBreak => {
+                if let Some(block) = current.as_mut() {
+                    block.text.push(' ');
+                }
+            }
+            Event::HardBreak => {
+                if let Some(block) = current.as_mut() {
+                    block.text.push('\n');
+                }
+            }
+            Event::Rule => {
+                flush(&mut blocks, &mut current);
+                blocks.push(Block {
+                    kind: BlockKind::Rule,
+                    text: String::new(),
+                });
+            }
+            _ => {}
+        }
+    }
+    flush(&mut blocks, &mut current);
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(kind: BlockKind, text: &str) -> Block {
+        Block {
+            kind,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_blocks() {
+        let source = "# Install\n\
+            \n\
+            Run the **installer** with `--quiet`\nthen reboot.\n\
+            \n\
+            - first\n\
+            - second\n  1. nested\n\
+            \n\
+            ```sh\ncargo build\n```\n\
+            \n\
+            > Note: slow on network shares\n\
+            \n\
+            ---\n";
+        assert_eq!(
+            blocks(source),
+            vec![
+                block(BlockKind::Heading(1), "Install"),
+                block(
+                    BlockKind::Paragraph,
+                    "Run the installer with --quiet then reboot."
+                ),
+                block(BlockKind::ListItem(1), "• first"),
+                block(BlockKind::ListItem(1), "• second"),
+                block(BlockKind::ListItem(2), "1. nested"),
+                block(BlockKind::Code, "cargo build"),
+                block(BlockKind::Quote, "Note: slow on network shares"),
+                block(BlockKind::Rule, ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_table_rows() {
+        let source = "| Key | Value |\n|-----|-------|\n| a | 1 |\n";
+        assert_eq!(
+            blocks(source),
+            vec![
+                block(BlockKind::Paragraph, "Key | Value"),
+                block(BlockKind::Paragraph, "a | 1"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,313 @@
+// Whole-volume listing of files and folders from the NTFS master file table,
+// read through the USN journal API (FSCTL_ENUM_USN_DATA): millions of names in
+// a few seconds, against minutes for a walk. Opening the volume needs administrator rights;
+// the search walks the disk whenever the listing is unavailable.
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf, Prefix};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MftRecord {
+    pub frn: u64,
+    pub parent: u64,
+    pub name: String,
+    pub attributes: u32,
+}
+
+// File reference number of the root folder of an NTFS volume.
+const ROOT_FRN: u64 = 5;
+// Reference numbers without their sequence number (upper 16 bits).
+const FRN_MASK: u64 = 0x0000_FFFF_FFFF_FFFF;
+const ATTRIBUTE_HIDDEN: u32 = 0x2;
+const ATTRIBUTE_DIRECTORY: u32 = 0x10;
+
+// "C:\" (a drive root), as opposed to one of its folders or to the root of a
+// share (`\\wsl$\Ubuntu\`).
+pub fn is_volume_root(path: &Path) -> bool {
+    let mut components = path.components();
+    match (components.next(), components.next(), components.next()) {
+        (Some(Component::Prefix(prefix)), Some(Component::RootDir), None) => {
+            matches!(prefix.kind(), Prefix::Disk(_) | Prefix::VerbatimDisk(_))
+        }
+        _ => false,
+    }
+}
+
+// Every file and folder of the volume (with whether it is a folder), hidden
+// ones excluded like the walker does; None when the master file table cannot
+// be read.
+#[cfg(windows)]
+pub fn list_entries(volume: &Path) -> Option<Vec<(PathBuf, bool)>> {
+    read_records(volume).map(|records| paths_from_records(&records, volume))
+}
+
+#[cfg(not(windows))]
+pub fn list_entries(_volume: &Path) -> Option<Vec<(PathBuf, bool)>> {
+    None
+}
+
+#[cfg(windows)]
+fn read_records(volume: &Path) -> Option<Vec<MftRecord>> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::{
+        CloseHandle, GetLastError, ERROR_HANDLE_EOF, GENERIC_READ, INVALID_HANDLE_VALUE,
+    };
+    use windows_sys::Win32::Storage::FileSystem::{
+        CreateFileW, GetVolumeInformationW, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
+    };
+    use windows_sys::Win32::System::Ioctl::{
+        FSCTL_ENUM_USN_DATA, FSCTL_QUERY_USN_JOURNAL, MFT_ENUM_DATA_V0, USN_JOURNAL_DATA_V0,
+    };
+    use windows_sys::Win32::System::IO::DeviceIoControl;
+
+    let wide = |text: &std::ffi::OsStr| -> Vec<u16> { text.encode_wide().chain(Some(0)).collect() };
+
+    // The USN API only exists on NTFS (and ReFS, whose 128-bit references it does not return).
+    let root = wide(volume.as_os_str());
+    let mut file_system = [0u16; 16];
+    // SAFETY: `root` is NUL-terminated and the name buffer length is passed along.
+    let ok = unsafe {
+        GetVolumeInformationW(
+            root.as_ptr(),
+            std::ptr::null_mut(),
+            0,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            file_system.as_mut_ptr(),
+            file_system.len() as u32,
+        )
+    };
+    let len = file_system.iter().position(|&c| c == 0).unwrap_or(0);
+    if ok == 0 || String::from_utf16_lossy(&file_system[..len]) != "NTFS" {
+        return None;
+    }
+
+    // "C:\" -> "\\.\C:"
+    let drive = volume.to_str()?.trim_end_matches('\\');
+    let device = wide(format!(r"\\.\{}", drive).as_ref());
+    // SAFETY: `device` is NUL-terminated; the handle is closed below.
+    let handle = unsafe {
+        CreateFileW(
+            device.as_ptr(),
+            GENERIC_READ,
+            FILE_SHARE_READ | FILE_SHARE_WRITE,
+            std::ptr::null(),
+            OPEN_EXISTING,
+            0,
+            std::ptr::null_mut(),
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        return None;
+    }
+
+    // SAFETY: every buffer outlives the calls that receive it, with its size passed along.
+    let records = unsafe {
+        let mut journal: USN_JOURNAL_DATA_V0 = std::mem::zeroed();
+        let mut returned = 0u32;
+        let high_usn = if DeviceIoControl(
+            handle,
+            FSCTL_QUERY_USN_JOURNAL,
+            std::ptr::null(),
+            0,
+            &mut journal as *mut USN_JOURNAL_DATA_V0 as *mut _,
+            std::mem::size_of::<USN_JOURNAL_DATA_V0>() as u32,
+            &mut returned,
+            std::ptr::null_mut(),
+        ) != 0
+        {
+            journal.NextUsn
+        } else {
+            i64::MAX
+        };
+
+        let mut enum_data = MFT_ENUM_DATA_V0 {
+            StartFileReferenceNumber: 0,
+            LowUsn: 0,
+            HighUsn: high_usn,
+        };
+        let mut buffer = vec![0u8; 1 << 16];
+        let mut records = Vec::new();
+        loop {
+            let ok = DeviceIoControl(
+                handle,
+                FSCTL_ENUM_USN_DATA,
+                &enum_data as *const MFT_ENUM_DATA_V0 as *const _,
+                std::mem::size_of::<MFT_ENUM_DATA_V0>() as u32,
+                buffer.as_mut_ptr() as *mut _,
+                buffer.len() as u32,
+                &mut returned,
+                std::ptr::null_mut(),
+            );
+            if ok == 0 {
+                // The end of the table is reported as an error.
+                break (GetLastError() == ERROR_HANDLE_EOF).then_some(records);
+            }
+            match parse_records(&buffer[..returned as usize], &mut records) {
+                Some(next) => enum_data.StartFileReferenceNumber = next,
+                None => break Some(records),
+            }
+        }
+    };
+
+    // SAFETY: `handle` was opened above and is not used afterwards.
+    unsafe { CloseHandle(handle) };
+    records
+}
+
+// Parses the output of FSCTL_ENUM_USN_DATA: the next start reference, then
+// USN_RECORD_V2 entries. Returns the next start, None past the last record.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_records(bytes: &[u8], records: &mut Vec<MftRecord>) -> Option<u64> {
+    let u16_at = |at: usize| Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?));
+    let u32_at = |at: usize| Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?));
+    let u64_at = |at: usize| Some(u64::from_le_bytes(bytes.get(at..at + 8)?.try_into().ok()?));
+
+    let next = u64_at(0)?;
+    let mut offset = 8;
+    if bytes.len() <= offset {
+        return None;
+    }
+    while let Some(length) = u32_at(offset).filter(|&length| length > 0) {
+        let name_length = u16_at(offset + 56)? as usize;
+        let name_start = offset + u16_at(offset + 58)? as usize;
+        let name: Vec<u16> = bytes
+            .get(name_start..name_start + name_length)?
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        records.push(MftRecord {
+            frn: u64_at(offset + 8)?,
+            parent: u64_at(offset + 16)?,
+            name: String::from_utf16_lossy(&name),
+            attributes: u32_at(offset + 52)?,
+        });
+        offset += length as usize;
+    }
+    Some(next)
+}
+
+// Full paths of the files and folders, rebuilt from the parent references.
+// Entries under a hidden folder, or whose folder chain is broken, are left out.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn paths_from_records(records: &[MftRecord], volume: &Path) -> Vec<(PathBuf, bool)> {
+    let by_frn: HashMap<u64, &MftRecord> = records
+        .iter()
+        .map(|record| (record.frn & FRN_MASK, record))
+        .collect();
+    let mut folders: HashMap<u64, Option<PathBuf>> = HashMap::new();
+    folders.insert(ROOT_FRN, Some(volume.to_path_buf()));
+
+    records
+        .iter()
+        .filter(|record| record.frn & FRN_MASK != ROOT_FRN && is_visible(record))
+        .filter_map(|record| {
+            let folder = folder_path(record.parent & FRN_MASK, &by_frn, &mut folders)?;
+            let is_dir = record.attributes & ATTRIBUTE_DIRECTORY != 0;
+            Some((folder.join(&record.name), is_dir))
+        })
+        .collect()
+}
+
+// Same rule as the walker: dot names and the hidden attribute.
+fn is_visible(record: &MftRecord) -> bool {
+    !record.name.starts_with('.') && record.attributes & ATTRIBUTE_HIDDEN == 0
+}
+
+fn folder_path(
+    frn: u64,
+    by_frn: &HashMap<u64, &MftRecord>,
+    folders: &mut HashMap<u64, Option<PathBuf>>,
+) -> Option<PathBuf> {
+    // Climbs to a known folder (the root at worst), then resolves the chain back.
+    let mut chain = Vec::new();
+    let mut current = frn;
+    let mut path = loop {
+        if let Some(known) = folders.get(&current) {
+            break known.clone();
+        }
+        match by_frn.get(&current) {
+            // The length check stops on a corrupted (cyclic) chain.
+            Some(record) if chain.len() < by_frn.len() => {
+                chain.push(current);
+                current = record.parent & FRN_MASK;
+            }
+            _ => break None,
+        }
+    };
+    for frn in chain.into_iter().rev() {
+        let record = by_frn[&frn];
+        path = path
+            .filter(|_| is_visible(record))
+            .map(|parent| parent.join(&record.name));
+        folders.insert(frn, path.clone());
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(frn: u64, parent: u64, name: &str, attributes: u32) -> MftRecord {
+        MftRecord {
+            frn,
+            parent,
+            name: name.to_string(),
+            attributes,
+        }
+    }
+
+    #[test]
+    fn test_paths_from_records() {
+        let sequence = 3 << 48;
+        let records = vec![
+            record(
+                40 | sequence,
+                ROOT_FRN | sequence,
+                "Users",
+                ATTRIBUTE_DIRECTORY,
+            ),
+            record(41, 40 | sequence, "bob", ATTRIBUTE_DIRECTORY),
+            record(42, 41, "notes.txt", 0x20),
+            record(43, ROOT_FRN, "$Secure", ATTRIBUTE_HIDDEN),
+            record(44, 41, ".cache", ATTRIBUTE_DIRECTORY),
+            record(45, 44, "blob.bin", 0),
+            record(46, 999, "orphan.txt", 0),
+        ];
+
+        let volume = Path::new("C:/");
+        let users = volume.join("Users");
+        assert_eq!(
+            paths_from_records(&records, volume),
+            vec![
+                (users.clone(), true),
+                (users.join("bob"), true),
+                (users.join("bob").join("notes.txt"), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_records() {
+        let name: Vec<u8> = "a.txt".encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let mut bytes = 77u64.to_le_bytes().to_vec();
+        let mut entry = vec![0u8; 60];
+        entry[0..4].copy_from_slice(&72u32.to_le_bytes());
+        entry[8..16].copy_from_slice(&42u64.to_le_bytes());
+        entry[16..24].copy_from_slice(&ROOT_FRN.to_le_bytes());
+        entry[52..56].copy_from_slice(&0x20u32.to_le_bytes());
+        entry[56..58].copy_from_slice(&(name.len() as u16).to_le_bytes());
+        entry[58..60].copy_from_slice(&60u16.to_le_bytes());
+        entry.extend(&name);
+        entry.resize(72, 0);
+        bytes.extend(&entry);
+
+        let mut records = Vec::new();
+        assert_eq!(parse_records(&bytes, &mut records), Some(77));
+        assert_eq!(records, vec![record(42, ROOT_FRN, "a.txt", 0x20)]);
+        // Nothing after the start reference: the table is exhausted.
+        assert_eq!(parse_records(&77u64.to_le_bytes(), &mut records), None);
+    }
+}
@@ -0,0 +1,327 @@
+// Network roots (`\\server\share`, mapped network drives). A share that stops
+// answering blocks a read forever instead of failing, so the walk runs on its
+// own thread and probes each entry on a small pool of helper threads, giving
+// up on the ones that do not answer in time. WSL distributions (`\\wsl$\Ubuntu`) are UNC
+// paths too, served locally.
+
+use std::io;
+use std::path::{Component, Path, PathBuf, Prefix};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// Longest wait for one entry of a network root before it is skipped.
+pub const IO_TIMEOUT: Duration = Duration::from_secs(5);
+// How often a probe checks whether the scan was cancelled.
+const CANCEL_CHECK: Duration = Duration::from_millis(100);
+// Most probe threads alive at once. Threads blocked on a stalled share stay
+// in the count: once all of them are, the next probes time out.
+const MAX_PROBE_THREADS: usize = 8;
+
+// Host names of the WSL distributions share (`\\wsl$\<distribution>`).
+const WSL_HOSTS: [&str; 2] = ["wsl$", "wsl.localhost"];
+
+// `\\server\share\...` (also written `\\?\UNC\server\share\...`), or a drive
+// letter mapped to a share. WSL paths are not: their files are local.
+pub fn is_network_path(path: &Path) -> bool {
+    if let Some(host) = unc_host(path) {
+        return !is_wsl_host(host);
+    }
+    match path.components().next() {
+        Some(Component::Prefix(prefix)) => match prefix.kind() {
+            Prefix::Disk(letter) | Prefix::VerbatimDisk(letter) => is_remote_drive(letter),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+// A file of a WSL distribution (`\\wsl$\Ubuntu\home\me`).
+pub fn is_wsl_path(path: &Path) -> bool {
+    unc_host(path).is_some_and(is_wsl_host)
+}
+
+fn is_wsl_host(host: &str) -> bool {
+    WSL_HOSTS.iter().any(|wsl| host.eq_ignore_ascii_case(wsl))
+}
+
+// Server of a UNC path, read from the text so that `//server/share` counts
+// too (prefixes are only parsed on Windows).
+fn unc_host(path: &Path) -> Option<&str> {
+    let path = path.to_str()?;
+    let rest = strip_ignore_case(path, r"\\?\UNC\").or_else(|| {
+        let rest = path
+            .strip_prefix(r"\\")
+            .or_else(|| path.strip_prefix("//"))?;
+        // `\\?\C:\` and `\\.\device` are local.
+        (!rest.starts_with(['?', '.'])).then_some(rest)
+    })?;
+    rest.split(['\\', '/'])
+        .next()
+        .filter(|host| !host.is_empty())
+}
+
+fn strip_ignore_case<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = text.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &text[prefix.len()..])
+}
+
+// The usual form of a UNC root: backslashes, no `\\?\UNC\` prefix, and a
+// trailing backslash after a bare share (`\\wsl$\Ubuntu\`), so that the paths
+// of the walk below it strip to relative paths. Other paths are unchanged.
+pub fn normalize_root(path: &Path) -> PathBuf {
+    let Some(text) = path.to_str().filter(|_| unc_host(path).is_some()) else {
+        return path.to_path_buf();
+    };
+    let text = text.replace('/', "\\");
+    let rest = strip_ignore_case(&text, r"\\?\UNC\")
+        .or_else(|| text.strip_prefix(r"\\"))
+        .unwrap_or(&text)
+        .trim_end_matches('\\');
+    let mut normalized = format!(r"\\{}", rest);
+    if rest.split('\\').count() <= 2 {
+        normalized.push('\\');
+    }
+    PathBuf::from(normalized)
+}
+
+#[cfg(windows)]
+fn is_remote_drive(letter: u8) -> bool {
+    use windows_sys::Win32::Storage::FileSystem::GetDriveTypeW;
+
+    const DRIVE_REMOTE: u32 = 4;
+    let root: Vec<u16> = format!("{}:\\", letter as char)
+        .encode_utf16()
+        .chain(Some(0))
+        .collect();
+    // SAFETY: `root` is NUL-terminated.
+    unsafe { GetDriveTypeW(root.as_ptr()) == DRIVE_REMOTE }
+}
+
+#[cfg(not(windows))]
+fn is_remote_drive(_letter: u8) -> bool {
+    false
+}
+
+// Reads the entry (lists a folder, opens a file when `open_files`, reads the
+// metadata otherwise) on a probe thread. A stalled share leaves that thread
+// blocked and fails with `TimedOut` after `timeout`, or with `Interrupted` as
+// soon as `cancelled` returns true.
+pub fn probe(
+    path: &Path,
+    open_files: bool,
+    timeout: Duration,
+    cancelled: impl Fn() -> bool,
+) -> io::Result<()> {
+    let (tx, rx) = mpsc::channel();
+    submit_probe(ProbeJob {
+        path: path.to_path_buf(),
+        open_files,
+        reply: tx,
+    });
+
+    let start = Instant::now();
+    loop {
+        match rx.recv_timeout(CANCEL_CHECK) {
+            Ok(result) => return result,
+            Err(RecvTimeoutError::Disconnected) => {
+                return Err(io::Error::other("the probe thread stopped"))
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+        if cancelled() {
+            return Err(io::ErrorKind::Interrupted.into());
+        }
+        if start.elapsed() >= timeout {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("no answer after {}s", timeout.as_secs()),
+            ));
+        }
+    }
+}
+
+struct ProbeJob {
+    path: PathBuf,
+    open_files: bool,
+    reply: Sender<io::Result<()>>,
+}
+
+impl ProbeJob {
+    fn run(self) {
+        let path = &self.path;
+        let result = std::fs::metadata(path).and_then(|metadata| {
+            if metadata.is_dir() {
+                std::fs::read_dir(path).map(drop)
+            } else if self.open_files {
+                std::fs::File::open(path).map(drop)
+            } else {
+                Ok(())
+            }
+        });
+        let _ = self.reply.send(result);
+    }
+}
+
+// Probe threads shared by every scan, started on demand up to
+// `MAX_PROBE_THREADS` and then reused.
+struct ProbePool {
+    jobs: Sender<ProbeJob>,
+    queue: Arc<Mutex<Receiver<ProbeJob>>>,
+    idle: Arc<AtomicUsize>,
+    threads: usize,
+}
+
+static PROBE_POOL: Mutex<Option<ProbePool>> = Mutex::new(None);
+
+fn submit_probe(job: ProbeJob) {
+    let mut pool = PROBE_POOL.lock().unwrap_or_else(|e| e.into_inner());
+    let pool = pool.get_or_insert_with(|| {
+        let (jobs, queue) = mpsc::channel();
+        ProbePool {
+            jobs,
+            queue: Arc::new(Mutex::new(queue)),
+            idle: Arc::new(AtomicUsize::new(0)),
+            threads: 0,
+        }
+    });
+    if pool.idle.load(Ordering::Relaxed) == 0 && pool.threads < MAX_PROBE_THREADS {
+        let queue = Arc::clone(&pool.queue);
+        let idle = Arc::clone(&pool.idle);
+        idle.fetch_add(1, Ordering::Relaxed);
+        std::thread::spawn(move || loop {
+            let job = queue.lock().unwrap_or_else(|e| e.into_inner()).recv();
+            idle.fetch_sub(1, Ordering::Relaxed);
+            match job {
+                Ok(job) => job.run(),
+                Err(_) => return,
+            }
+            idle.fetch_add(1, Ordering::Relaxed);
+        });
+        pool.threads += 1;
+    }
+    let _ = pool.jobs.send(job);
+}
+
+#[cfg(test)]
+fn probe_threads() -> usize {
+    PROBE_POOL
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map_or(0, |pool| pool.threads)
+}
+
+// Runs `entries` (a walk) on its own thread. The items stop as soon as
+// `cancelled` returns true, even when the walk is blocked on a stalled share
+// (its thread is then left behind until the read returns).
+pub fn detach<T: Send + 'static>(
+    entries: impl Iterator<Item = T> + Send + 'static,
+    cancelled: impl Fn() -> bool,
+) -> impl Iterator<Item = T> {
+    let (tx, rx) = mpsc::sync_channel(256);
+    std::thread::spawn(move || {
+        for entry in entries {
+            if tx.send(entry).is_err() {
+                break;
+            }
+        }
+    });
+    std::iter::from_fn(move || loop {
+        match rx.recv_timeout(CANCEL_CHECK) {
+            Ok(entry) => return Some(entry),
+            Err(RecvTimeoutError::Disconnected) => return None,
+            Err(RecvTimeoutError::Timeout) if cancelled() => return None,
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_is_network_path() {
+        assert!(is_network_path(Path::new(r"\\server\share\reports")));
+        assert!(is_network_path(Path::new(r"\\?\UNC\server\share")));
+        assert!(is_network_path(Path::new("//server/share")));
+        assert!(!is_network_path(Path::new(r"\\?\C:\Users")));
+        assert!(!is_network_path(Path::new(r"\\.\PhysicalDrive0")));
+        assert!(!is_network_path(Path::new(r"\\wsl$\Ubuntu\home\me")));
+        assert!(!is_network_path(&std::env::temp_dir()));
+    }
+
+    #[test]
+    fn test_is_wsl_path() {
+        assert!(is_wsl_path(Path::new(r"\\wsl$\Ubuntu\home\me")));
+        assert!(is_wsl_path(Path::new(r"\\WSL.localhost\Debian")));
+        assert!(is_wsl_path(Path::new(r"\\?\UNC\wsl.localhost\Ubuntu\etc")));
+        assert!(is_wsl_path(Path::new("//wsl$/Ubuntu/home")));
+        assert!(!is_wsl_path(Path::new(r"\\server\wsl$")));
+        assert!(!is_wsl_path(Path::new(r"C:\wsl$\Ubuntu")));
+    }
+
+    #[test]
+    fn test_normalize_root() {
+        let normalize = |path: &str| normalize_root(Path::new(path));
+        assert_eq!(
+            normalize(r"\\wsl$\Ubuntu"),
+            PathBuf::from(r"\\wsl$\Ubuntu\")
+        );
+        assert_eq!(
+            normalize("//wsl$/Ubuntu/home/me/"),
+            PathBuf::from(r"\\wsl$\Ubuntu\home\me")
+        );
+        assert_eq!(
+            normalize(r"\\?\UNC\wsl.localhost\Ubuntu\home"),
+            PathBuf::from(r"\\wsl.localhost\Ubuntu\home")
+        );
+        assert_eq!(
+            normalize(r"\\server\share\"),
+            PathBuf::from(r"\\server\share\")
+        );
+        assert_eq!(normalize(r"C:\Users"), PathBuf::from(r"C:\Users"));
+        assert_eq!(normalize("/home/me"), PathBuf::from("/home/me"));
+    }
+
+    #[test]
+    fn test_probe() {
+        let root = std::env::temp_dir().join("quick-findr-network-probe");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("report.txt"), "q3").unwrap();
+
+        assert!(probe(&root, true, IO_TIMEOUT, || false).is_ok());
+        assert!(probe(&root.join("report.txt"), true, IO_TIMEOUT, || false).is_ok());
+        let missing = probe(&root.join("missing.txt"), false, IO_TIMEOUT, || false);
+        assert_eq!(missing.unwrap_err().kind(), io::ErrorKind::NotFound);
+
+        // Probes of every entry of a folder share a few threads.
+        for index in 0..100 {
+            let file = root.join(format!("{}.txt", index));
+            fs::write(&file, "q3").unwrap();
+            assert!(probe(&file, true, IO_TIMEOUT, || false).is_ok());
+        }
+        assert!(probe_threads() <= MAX_PROBE_THREADS);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_detach() {
+        let entries: Vec<u32> = detach(0..5, || false).collect();
+        assert_eq!(entries, vec![0, 1, 2, 3, 4]);
+
+        // A walk that never yields stops once cancelled.
+        let stalled = std::iter::from_fn(|| {
+            std::thread::sleep(Duration::from_secs(60));
+            Some(0)
+        });
+        let start = Instant::now();
+        assert_eq!(detach(stalled, || true).next(), None);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
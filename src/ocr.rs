@@ -0,0 +1,132 @@
+// Text of images and scanned PDFs, recognised by the tesseract command line
+// (PDF pages are rendered by pdftoppm first); both tools must be on the PATH.
+// Built with the `ocr` cargo feature and enabled in the settings. Recognition
+// takes seconds per page, so the text is cached by file digest: a file is only
+// read again once its content changes.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const AVAILABLE: bool = cfg!(feature = "ocr");
+
+pub fn is_supported(extension: &str) -> bool {
+    matches!(
+        extension.to_lowercase().as_str(),
+        "png" | "jpg" | "jpeg" | "tif" | "tiff" | "bmp" | "gif" | "webp" | "pdf"
+    )
+}
+
+#[cfg(feature = "ocr")]
+pub fn text(path: &Path) -> io::Result<String> {
+    use crate::hashing::{hash_file, HashAlgorithm};
+
+    let digest = hash_file(path, HashAlgorithm::Sha256)?;
+    cached_text(&cache_dir(), &digest, || {
+        let extension = path.extension().unwrap_or_default();
+        if extension.eq_ignore_ascii_case("pdf") {
+            recognize_pdf(path)
+        } else {
+            recognize_image(path)
+        }
+    })
+}
+
+#[cfg(not(feature = "ocr"))]
+pub fn text(_path: &Path) -> io::Result<String> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "built without the ocr feature",
+    ))
+}
+
+#[cfg_attr(not(feature = "ocr"), allow(dead_code))]
+fn cache_dir() -> PathBuf {
+    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("quick-findr");
+    path.push("ocr-cache");
+    path
+}
+
+// Cached text of the file with this digest, recognised (and cached) on a miss.
+#[cfg_attr(not(feature = "ocr"), allow(dead_code))]
+fn cached_text(
+    dir: &Path,
+    digest: &str,
+    recognize: impl FnOnce() -> io::Result<String>,
+) -> io::Result<String> {
+    let cached = dir.join(format!("{}.txt", digest));
+    if let Ok(text) = fs::read_to_string(&cached) {
+        return Ok(text);
+    }
+    let text = recognize()?;
+    fs::create_dir_all(dir)?;
+    fs::write(&cached, &text)?;
+    Ok(text)
+}
+
+#[cfg(feature = "ocr")]
+fn recognize_image(path: &Path) -> io::Result<String> {
+    let output = std::process::Command::new("tesseract")
+        .arg(path)
+        .arg("stdout")
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// Every page rendered to PNG in a temporary folder, then recognised in order.
+#[cfg(feature = "ocr")]
+fn recognize_pdf(path: &Path) -> io::Result<String> {
+    let pages_dir = std::env::temp_dir().join(format!("quick-findr-ocr-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&pages_dir);
+    fs::create_dir_all(&pages_dir)?;
+
+    let rendered = std::process::Command::new("pdftoppm")
+        .args(["-r", "300", "-png"])
+        .arg(path)
+        .arg(pages_dir.join("page"))
+        .status();
+    let text = match rendered {
+        Ok(status) if status.success() => {
+            let mut pages: Vec<PathBuf> = fs::read_dir(&pages_dir)?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .collect();
+            // page-01.png, page-02.png... (zero-padded, so the names sort).
+            pages.sort();
+            pages
+                .iter()
+                .map(|page| recognize_image(page))
+                .collect::<io::Result<Vec<_>>>()
+                .map(|pages| pages.join("\n"))
+        }
+        Ok(status) => Err(io::Error::other(format!("pdftoppm failed ({})", status))),
+        Err(err) => Err(err),
+    };
+    let _ = fs::remove_dir_all(&pages_dir);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_text_recognizes_once() {
+        let dir = std::env::temp_dir().join("quick-findr-ocr-cache-test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let text = cached_text(&dir, "abc", || Ok("Invoice 42".to_string())).unwrap();
+        assert_eq!(text, "Invoice 42");
+        let text = cached_text(&dir, "abc", || panic!("recognised twice")).unwrap();
+        assert_eq!(text, "Invoice 42");
+        // Failures are not cached.
+        assert!(cached_text(&dir, "def", || Err(io::Error::other("no tesseract"))).is_err());
+        assert!(!dir.join("def.txt").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
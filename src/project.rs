@@ -0,0 +1,65 @@
+// Search defaults checked into a project: a `.quickfindr.toml` at the root of
+// the searched folder, applied when the folder is selected. Every key is
+// optional:
+//
+//   exclude_globs = ["**/build/**", "dist/"]
+//   search_content = true
+//   language = "rust"
+
+use crate::engine::language_extensions;
+use serde::Deserialize;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub const FILE_NAME: &str = ".quickfindr.toml";
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    pub exclude_globs: Vec<String>,
+    pub search_content: Option<bool>,
+    // A language quick filter ("rust", "python"...).
+    pub language: Option<String>,
+}
+
+impl ProjectConfig {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let config: Self = toml::from_str(text).map_err(|err| err.message().to_string())?;
+        if let Some(language) = &config.language {
+            if language_extensions(language).is_none() {
+                return Err(format!("unknown language \"{}\"", language));
+            }
+        }
+        Ok(config)
+    }
+}
+
+// The configuration of `root`; None when it has no file.
+pub fn load(root: &Path) -> Result<Option<ProjectConfig>, String> {
+    match fs::read_to_string(root.join(FILE_NAME)) {
+        Ok(text) => ProjectConfig::parse(&text).map(Some),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_project_config() {
+        let config = ProjectConfig::parse(
+            "exclude_globs = [\"**/build/**\", \"dist/\"]\nsearch_content = true\nlanguage = \"rust\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.exclude_globs, ["**/build/**", "dist/"]);
+        assert_eq!(config.search_content, Some(true));
+        assert_eq!(config.language.as_deref(), Some("rust"));
+
+        assert_eq!(ProjectConfig::parse("").unwrap(), ProjectConfig::default());
+        assert!(ProjectConfig::parse("language = \"cobol\"").is_err());
+        assert!(ProjectConfig::parse("exclude = [\"dist/\"]").is_err());
+    }
+}
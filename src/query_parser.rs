@@ -0,0 +1,378 @@
+use crate::engine::{DateRange, SizeFilter, TimeFilter};
+use crate::filetype::FileKind;
+use chrono::{DateTime, Local};
+use std::time::SystemTime;
+
+// Filters typed in the query box next to the search text:
+// "invoice ext:pdf modified:<30d path:Documents".
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Qualifiers {
+    // `type:image` (repeatable): a file must be one of these kinds.
+    pub kinds: Vec<FileKind>,
+    // `signed:yes` / `signed:no`: only executables with / without a valid signature.
+    pub signed: Option<bool>,
+    // `ext:pdf,docx`: lowercase extensions without the dot.
+    pub extensions: Vec<String>,
+    // `size:>10MB` (repeatable, as in the size box).
+    pub size: SizeFilter,
+    // `path:Documents`: lowercase substrings the relative path must all contain.
+    pub paths: Vec<String>,
+    // `modified:<30d`, `modified:week`, `modified:2024-03-01`...
+    pub modified: DateRange,
+}
+
+impl Qualifiers {
+    // The relative path contains every `path:` fragment (case-insensitive, either separator).
+    pub fn path_allowed(&self, relative_path: &str) -> bool {
+        if self.paths.is_empty() {
+            return true;
+        }
+        let relative_path = relative_path.to_lowercase().replace('\\', "/");
+        self.paths
+            .iter()
+            .all(|p| relative_path.contains(p.as_str()))
+    }
+}
+
+// Splits the qualifiers off the query. Unknown keys and invalid values are
+// left in the search text as typed.
+pub fn parse(query: &str, now: DateTime<Local>) -> (String, Qualifiers) {
+    let mut qualifiers = Qualifiers::default();
+    let mut rest = Vec::new();
+    let mut found = false;
+
+    for token in query.split(' ') {
+        let applied = token
+            .split_once(':')
+            .is_some_and(|(key, value)| apply(&mut qualifiers, key, value, now));
+        if applied {
+            found = true;
+        } else {
+            rest.push(token);
+        }
+    }
+
+    if found {
+        (rest.join(" ").trim().to_string(), qualifiers)
+    } else {
+        (query.to_string(), qualifiers)
+    }
+}
+
+// Records one `key:value` token; false when it is not a valid qualifier.
+fn apply(qualifiers: &mut Qualifiers, key: &str, value: &str, now: DateTime<Local>) -> bool {
+    match key.to_lowercase().as_str() {
+        "type" => match FileKind::from_name(value) {
+            Some(kind) => qualifiers.kinds.push(kind),
+            None => return false,
+        },
+        "signed" => match value.to_lowercase().as_str() {
+            "yes" => qualifiers.signed = Some(true),
+            "no" => qualifiers.signed = Some(false),
+            _ => return false,
+        },
+        "ext" => {
+            let extensions: Vec<String> = value
+                .split(',')
+                .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+                .filter(|e| !e.is_empty())
+                .collect();
+            if extensions.is_empty() {
+                return false;
+            }
+            qualifiers.extensions.extend(extensions);
+        }
+        "size" => match SizeFilter::parse(value) {
+            Ok(filter) if !filter.is_empty() => {
+                qualifiers.size.min = filter.min.or(qualifiers.size.min);
+                qualifiers.size.max = filter.max.or(qualifiers.size.max);
+            }
+            _ => return false,
+        },
+        "path" if !value.is_empty() => {
+            qualifiers
+                .paths
+                .push(value.to_lowercase().replace('\\', "/"));
+        }
+        "modified" => match parse_modified(value, now) {
+            Some(range) => {
+                let current = &mut qualifiers.modified;
+                current.from = latest(current.from, range.from);
+                current.until = match (current.until, range.until) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+            }
+            None => return false,
+        },
+        _ => return false,
+    }
+    true
+}
+
+fn latest(a: Option<SystemTime>, b: Option<SystemTime>) -> Option<SystemTime> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+// Boolean query: "foo AND bar", "foo OR baz", "-excluded" / "NOT excluded".
+// Adjacent terms are ANDed and AND binds tighter than OR; operators are uppercase.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Term(String),
+    Not(Box<Expr>),
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+}
+
+impl Expr {
+    // Evaluates the tree, `matches` deciding each term.
+    pub fn eval(&self, matches: &impl Fn(&str) -> bool) -> bool {
+        match self {
+            Self::Term(term) => matches(term),
+            Self::Not(inner) => !inner.eval(matches),
+            Self::And(items) => items.iter().all(|item| item.eval(matches)),
+            Self::Or(items) => items.iter().any(|item| item.eval(matches)),
+        }
+    }
+
+    // Terms that are searched for rather than excluded (highlighted in the results).
+    pub fn positive_terms(&self) -> Vec<&str> {
+        match self {
+            Self::Term(term) => vec![term.as_str()],
+            Self::Not(_) => Vec::new(),
+            Self::And(items) | Self::Or(items) => {
+                items.iter().flat_map(Self::positive_terms).collect()
+            }
+        }
+    }
+}
+
+// None when the text has no operator: it is then searched as a single phrase.
+pub fn parse_expression(text: &str) -> Option<Expr> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let has_operator = tokens.iter().any(|token| {
+        matches!(*token, "AND" | "OR" | "NOT") || (token.len() > 1 && token.starts_with('-'))
+    });
+    if !has_operator {
+        return None;
+    }
+
+    let mut alternatives = Vec::new();
+    for group in tokens.split(|token| *token == "OR") {
+        let mut terms = Vec::new();
+        let mut negate = false;
+        for token in group {
+            match *token {
+                "AND" => continue,
+                "NOT" => {
+                    negate = !negate;
+                    continue;
+                }
+                _ => {}
+            }
+            let (term, excluded) = match token.strip_prefix('-') {
+                Some(rest) if !rest.is_empty() => (rest, true),
+                _ => (*token, false),
+            };
+            let term = Expr::Term(term.to_string());
+            terms.push(if negate != excluded {
+                Expr::Not(Box::new(term))
+            } else {
+                term
+            });
+            negate = false;
+        }
+        if !terms.is_empty() {
+            alternatives.push(collapse(terms, Expr::And));
+        }
+    }
+
+    if alternatives.is_empty() {
+        return None;
+    }
+    Some(collapse(alternatives, Expr::Or))
+}
+
+fn collapse(mut items: Vec<Expr>, combine: fn(Vec<Expr>) -> Expr) -> Expr {
+    if items.len() == 1 {
+        items.remove(0)
+    } else {
+        combine(items)
+    }
+}
+
+// Ages ("<30d" newer than 30 days, ">1y" older than a year; units h, d, w, m, y),
+// periods ("today", "week", "month"), days ("2024-03-01"), bounds
+// (">=2024-01-01", "<2024-06-01") and ranges ("2024-01-01..2024-03-31").
+fn parse_modified(value: &str, now: DateTime<Local>) -> Option<DateRange> {
+    let filter = TimeFilter::from_name(value);
+    if filter != TimeFilter::Any {
+        return Some(DateRange {
+            from: filter.since(now),
+            until: None,
+        });
+    }
+
+    if let Some((from, to)) = value.split_once("..") {
+        return DateRange::parse(from, to).ok();
+    }
+
+    let (operator, operand) = ["<=", ">=", "<", ">"]
+        .iter()
+        .find_map(|op| value.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("", value));
+
+    if let Some(age) = parse_age(operand) {
+        let cutoff: SystemTime = (now - age).into();
+        return match operator {
+            "<" | "<=" => Some(DateRange {
+                from: Some(cutoff),
+                until: None,
+            }),
+            ">" | ">=" => Some(DateRange {
+                from: None,
+                until: Some(cutoff),
+            }),
+            _ => None,
+        };
+    }
+
+    let day = DateRange::parse(operand, operand).ok()?;
+    Some(match operator {
+        ">=" => DateRange { until: None, ..day },
+        ">" => DateRange {
+            from: day.until,
+            until: None,
+        },
+        "<" => DateRange {
+            from: None,
+            until: day.from,
+        },
+        "<=" => DateRange { from: None, ..day },
+        _ => day,
+    })
+}
+
+// "30d", "12h", "2w", "6m" (30-day months), "1y" (365 days).
+fn parse_age(text: &str) -> Option<chrono::Duration> {
+    let split = text.find(|c: char| !c.is_ascii_digit())?;
+    let (number, unit) = text.split_at(split);
+    let number: i64 = number.parse().ok()?;
+    let hours = match unit.to_lowercase().as_str() {
+        "h" => 1,
+        "d" => 24,
+        "w" => 24 * 7,
+        "m" => 24 * 30,
+        "y" => 24 * 365,
+        _ => return None,
+    };
+    chrono::Duration::try_hours(number.checked_mul(hours)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 6, 15, 12, 0, 0).unwrap()
+    }
+
+    fn midnight(y: i32, m: u32, d: u32) -> SystemTime {
+        Local.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap().into()
+    }
+
+    #[test]
+    fn test_parse_qualifiers() {
+        let (text, q) = parse("invoice ext:pdf,.DOCX modified:<30d path:Documents", now());
+        assert_eq!(text, "invoice");
+        assert_eq!(q.extensions, vec!["pdf", "docx"]);
+        assert_eq!(q.paths, vec!["documents"]);
+        let thirty_days_ago: SystemTime = (now() - chrono::Duration::days(30)).into();
+        assert_eq!(q.modified.from, Some(thirty_days_ago));
+        assert_eq!(q.modified.until, None);
+
+        let (text, q) = parse(
+            "report type:doc TYPE:IMAGE SIGNED:no size:>1MB size:<=2MB",
+            now(),
+        );
+        assert_eq!(text, "report");
+        assert_eq!(q.kinds, vec![FileKind::Document, FileKind::Image]);
+        assert_eq!(q.signed, Some(false));
+        assert_eq!(q.size, SizeFilter::parse(">1MB <=2MB").unwrap());
+    }
+
+    #[test]
+    fn test_invalid_qualifiers_stay_in_the_query() {
+        let (text, q) = parse("type:unknown notes signed:maybe size:big", now());
+        assert_eq!(text, "type:unknown notes signed:maybe size:big");
+        assert_eq!(q, Qualifiers::default());
+
+        // Not a qualifier at all.
+        assert_eq!(parse("C:\\Users", now()).0, "C:\\Users");
+    }
+
+    #[test]
+    fn test_parse_modified() {
+        let range = |value| parse_modified(value, now()).unwrap();
+
+        let year_ago: SystemTime = (now() - chrono::Duration::days(365)).into();
+        assert_eq!(range(">1y").until, Some(year_ago));
+        assert_eq!(range("today").from, Some(midnight(2024, 6, 15)));
+
+        assert_eq!(
+            range("2024-03-01"),
+            DateRange {
+                from: Some(midnight(2024, 3, 1)),
+                until: Some(midnight(2024, 3, 2)),
+            }
+        );
+        assert_eq!(range(">2024-03-01").from, Some(midnight(2024, 3, 2)));
+        assert_eq!(range("<2024-03-01").until, Some(midnight(2024, 3, 1)));
+        assert_eq!(
+            range("2024-01-01..2024-01-31").until,
+            Some(midnight(2024, 2, 1))
+        );
+        assert!(parse_modified("soon", now()).is_none());
+    }
+
+    #[test]
+    fn test_parse_expression() {
+        let term = |t: &str| Expr::Term(t.to_string());
+        let not = |t: &str| Expr::Not(Box::new(term(t)));
+
+        assert_eq!(parse_expression("foo bar"), None);
+        assert_eq!(parse_expression("my-file"), None);
+        assert_eq!(
+            parse_expression("foo AND bar OR baz -qux"),
+            Some(Expr::Or(vec![
+                Expr::And(vec![term("foo"), term("bar")]),
+                Expr::And(vec![term("baz"), not("qux")]),
+            ]))
+        );
+        assert_eq!(parse_expression("NOT draft"), Some(not("draft")));
+        assert_eq!(parse_expression("OR"), None);
+    }
+
+    #[test]
+    fn test_expression_eval() {
+        let expression = parse_expression("report OR invoice -draft").unwrap();
+        let eval = |text: &str| expression.eval(&|term| text.contains(term));
+
+        assert!(eval("report.pdf"));
+        assert!(eval("invoice.pdf"));
+        assert!(!eval("invoice_draft.pdf"));
+        assert!(!eval("notes.txt"));
+        assert_eq!(expression.positive_terms(), vec!["report", "invoice"]);
+    }
+
+    #[test]
+    fn test_path_allowed() {
+        let (_, q) = parse("path:docs/2024", now());
+        assert!(q.path_allowed("Docs\\2024\\invoice.pdf"));
+        assert!(!q.path_allowed("docs/2023/invoice.pdf"));
+    }
+}
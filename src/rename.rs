@@ -0,0 +1,410 @@
+use regex::{NoExpand, Regex};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseTransform {
+    Keep,
+    Lower,
+    Upper,
+    // First letter of every word in upper case.
+    Title,
+}
+
+impl CaseTransform {
+    // Index of the entry in the UI combo box.
+    pub fn from_index(index: i32) -> Self {
+        match index {
+            1 => Self::Lower,
+            2 => Self::Upper,
+            3 => Self::Title,
+            _ => Self::Keep,
+        }
+    }
+
+    fn apply(self, text: &str) -> String {
+        match self {
+            Self::Keep => text.to_string(),
+            Self::Lower => text.to_lowercase(),
+            Self::Upper => text.to_uppercase(),
+            Self::Title => {
+                let mut result = String::with_capacity(text.len());
+                let mut word_start = true;
+                for c in text.chars() {
+                    if word_start {
+                        result.extend(c.to_uppercase());
+                    } else {
+                        result.extend(c.to_lowercase());
+                    }
+                    word_start = !c.is_alphanumeric();
+                }
+                result
+            }
+        }
+    }
+}
+
+// How each new name is built:
+// 1. `find` is replaced by `replace` in the original name (without extension);
+// 2. the result fills the template: `{name}`, `{ext}` (".txt" or empty),
+//    `{n}` (counter) or `{n:3}` (zero-padded counter);
+// 3. the case transform is applied to the whole name.
+#[derive(Debug, Clone)]
+pub struct RenameRule {
+    pub template: String,
+    pub find: String,
+    pub replace: String,
+    pub use_regex: bool,
+    pub case: CaseTransform,
+    pub counter_start: u64,
+}
+
+impl Default for RenameRule {
+    fn default() -> Self {
+        Self {
+            template: "{name}{ext}".to_string(),
+            find: String::new(),
+            replace: String::new(),
+            use_regex: false,
+            case: CaseTransform::Keep,
+            counter_start: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenamePlan {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    // Why this rename cannot be done; the whole batch is blocked while any is set.
+    pub conflict: Option<String>,
+}
+
+impl RenamePlan {
+    pub fn is_unchanged(&self) -> bool {
+        self.from == self.to
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RenameReport {
+    pub renamed: Vec<(PathBuf, PathBuf)>,
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+// Widest `{n:<width>}` padding; a wider one is kept as typed (a width typed
+// by mistake would otherwise build a name of gigabytes).
+const MAX_COUNTER_WIDTH: usize = 32;
+
+fn expand_template(template: &str, name: &str, ext: &str, counter: u64) -> String {
+    let mut result = String::new();
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        result.push_str(&rest[..open]);
+        let Some(close) = rest[open..].find('}') else {
+            break;
+        };
+        let token = &rest[open + 1..open + close];
+        match token {
+            "name" => result.push_str(name),
+            "ext" => result.push_str(ext),
+            "n" => result.push_str(&counter.to_string()),
+            _ => match token
+                .strip_prefix("n:")
+                .and_then(|w| w.parse::<usize>().ok())
+                .filter(|width| *width <= MAX_COUNTER_WIDTH)
+            {
+                Some(width) => result.push_str(&format!("{:0width$}", counter, width = width)),
+                // Unknown token: kept as typed.
+                None => result.push_str(&rest[open..=open + close]),
+            },
+        }
+        rest = &rest[open + close + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+fn new_name(rule: &RenameRule, find: Option<&Regex>, path: &Path, counter: u64) -> String {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+
+    let name = match find {
+        Some(regex) if rule.use_regex => regex.replace_all(&stem, rule.replace.as_str()),
+        Some(regex) => regex.replace_all(&stem, NoExpand(&rule.replace)),
+        None => stem.clone(),
+    };
+
+    let template = if rule.template.trim().is_empty() {
+        "{name}{ext}"
+    } else {
+        rule.template.as_str()
+    };
+    rule.case
+        .apply(&expand_template(template, &name, &ext, counter))
+}
+
+// Characters Windows refuses in file names.
+fn invalid_name_reason(name: &str) -> Option<&'static str> {
+    if name.trim().is_empty() || name == "." || name == ".." {
+        Some("empty name")
+    } else if name.contains(['/', '\\', ':', '*', '?', '"', '<', '>', '|']) {
+        Some("invalid character in name")
+    } else if name.ends_with([' ', '.']) {
+        Some("name ends with a space or a dot")
+    } else {
+        None
+    }
+}
+
+// Computes the new name of every file (counter in the given order) and flags
+// invalid names, duplicate targets and targets that already exist.
+// Err is returned for an invalid `find` regex.
+pub fn plan_renames(paths: &[PathBuf], rule: &RenameRule) -> Result<Vec<RenamePlan>, String> {
+    let find = if rule.find.is_empty() {
+        None
+    } else {
+        let pattern = if rule.use_regex {
+            rule.find.clone()
+        } else {
+            regex::escape(&rule.find)
+        };
+        Some(Regex::new(&pattern).map_err(|err| err.to_string())?)
+    };
+
+    let mut plans: Vec<RenamePlan> = paths
+        .iter()
+        .enumerate()
+        .map(|(i, path)| {
+            let name = new_name(rule, find.as_ref(), path, rule.counter_start + i as u64);
+            RenamePlan {
+                from: path.clone(),
+                to: path.with_file_name(&name),
+                conflict: invalid_name_reason(&name).map(String::from),
+            }
+        })
+        .collect();
+
+    // Names are compared case-insensitively, like the Windows file system does.
+    let key = |path: &Path| path.to_string_lossy().to_lowercase();
+    let sources: HashSet<String> = plans.iter().map(|p| key(&p.from)).collect();
+    let mut targets: HashMap<String, usize> = HashMap::new();
+    for plan in &plans {
+        *targets.entry(key(&plan.to)).or_default() += 1;
+    }
+
+    for plan in plans.iter_mut().filter(|p| p.conflict.is_none()) {
+        let target = key(&plan.to);
+        if targets[&target] > 1 {
+            plan.conflict = Some("several files get this name".to_string());
+        } else if !plan.is_unchanged() && plan.to.exists() && !sources.contains(&target) {
+            plan.conflict = Some("a file with this name already exists".to_string());
+        }
+    }
+
+    Ok(plans)
+}
+
+// Renames one file or folder in place (inline rename of a result), with the
+// checks of a batch. Only the case of the name may change too.
+pub fn rename_one(from: &Path, name: &str) -> Result<PathBuf, String> {
+    if let Some(reason) = invalid_name_reason(name) {
+        return Err(reason.to_string());
+    }
+    let to = from.with_file_name(name);
+    let same_name = to.to_string_lossy().to_lowercase() == from.to_string_lossy().to_lowercase();
+    if same_name && to == from {
+        return Ok(to);
+    }
+    if !same_name && fs::symlink_metadata(&to).is_ok() {
+        return Err("a file with this name already exists".to_string());
+    }
+    fs::rename(from, &to).map_err(|err| match err.kind() {
+        std::io::ErrorKind::PermissionDenied => {
+            "access denied (read-only, protected or in use)".to_string()
+        }
+        _ => err.to_string(),
+    })?;
+    Ok(to)
+}
+
+// Runs the renames in two passes through temporary names, so swaps and chains
+// (a -> b, b -> c) work. Plans with a conflict or no change are left out.
+pub fn execute_renames(plans: &[RenamePlan]) -> RenameReport {
+    let mut report = RenameReport::default();
+    let pending: Vec<&RenamePlan> = plans
+        .iter()
+        .filter(|p| p.conflict.is_none() && !p.is_unchanged())
+        .collect();
+
+    let mut moved: Vec<(&RenamePlan, PathBuf)> = Vec::new();
+    for (index, plan) in pending.into_iter().enumerate() {
+        let temp = plan.from.with_file_name(format!(
+            ".quickfindr-rename-{}-{}",
+            std::process::id(),
+            index
+        ));
+        match fs::rename(&plan.from, &temp) {
+            Ok(()) => moved.push((plan, temp)),
+            Err(err) => report.failed.push((plan.from.clone(), err.to_string())),
+        }
+    }
+
+    for (plan, temp) in moved {
+        // A target freed by a failed first pass may still be there: never overwrite it.
+        let result = if plan.to.exists() {
+            Err("a file with this name already exists".to_string())
+        } else {
+            fs::rename(&temp, &plan.to).map_err(|err| err.to_string())
+        };
+        match result {
+            Ok(()) => report.renamed.push((plan.from.clone(), plan.to.clone())),
+            Err(reason) => {
+                let _ = fs::rename(&temp, &plan.from);
+                report.failed.push((plan.from.clone(), reason));
+            }
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_root;
+
+    fn names(plans: &[RenamePlan]) -> Vec<String> {
+        plans
+            .iter()
+            .map(|p| p.to.file_name().unwrap().to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_template_with_padded_counter() {
+        let rule = RenameRule {
+            template: "photo_{n:3}{ext}".to_string(),
+            counter_start: 9,
+            ..Default::default()
+        };
+        let paths = vec![PathBuf::from("/x/IMG_1.JPG"), PathBuf::from("/x/IMG_2.JPG")];
+        let plans = plan_renames(&paths, &rule).unwrap();
+
+        assert_eq!(names(&plans), vec!["photo_009.JPG", "photo_010.JPG"]);
+        assert_eq!(
+            expand_template("{n:999999999999}", "a", "", 1),
+            "{n:999999999999}"
+        );
+        assert_eq!(
+            expand_template("{n:33}_{n:32}", "a", "", 7),
+            format!("{{n:33}}_{:032}", 7)
+        );
+    }
+
+    #[test]
+    fn test_regex_captures_and_case() {
+        let rule = RenameRule {
+            find: r"(\d+)-(\w+)".to_string(),
+            replace: "$2 $1".to_string(),
+            use_regex: true,
+            case: CaseTransform::Title,
+            ..Default::default()
+        };
+        let plans = plan_renames(&[PathBuf::from("/x/2024-holiday_pics.png")], &rule).unwrap();
+
+        assert_eq!(names(&plans), vec!["Holiday_Pics 2024.Png"]);
+
+        let invalid = RenameRule {
+            find: "(".to_string(),
+            use_regex: true,
+            ..Default::default()
+        };
+        assert!(plan_renames(&[], &invalid).is_err());
+    }
+
+    #[test]
+    fn test_conflicts_are_detected() {
+        let root = temp_root("rename-conflicts");
+        fs::write(root.join("a.txt"), "").unwrap();
+        fs::write(root.join("b.txt"), "").unwrap();
+        fs::write(root.join("taken.txt"), "").unwrap();
+
+        let rule = RenameRule {
+            template: "same{ext}".to_string(),
+            ..Default::default()
+        };
+        let plans = plan_renames(&[root.join("a.txt"), root.join("b.txt")], &rule).unwrap();
+        assert!(plans.iter().all(|p| p.conflict.is_some()));
+
+        let rule = RenameRule {
+            template: "taken{ext}".to_string(),
+            ..Default::default()
+        };
+        let plans = plan_renames(&[root.join("a.txt")], &rule).unwrap();
+        assert!(plans[0].conflict.is_some());
+
+        let rule = RenameRule {
+            template: "bad:name".to_string(),
+            ..Default::default()
+        };
+        assert!(plan_renames(&[root.join("a.txt")], &rule).unwrap()[0]
+            .conflict
+            .is_some());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_rename_one() {
+        let root = temp_root("rename-one");
+        fs::write(root.join("draft.txt"), "A").unwrap();
+        fs::write(root.join("taken.txt"), "B").unwrap();
+
+        assert_eq!(
+            rename_one(&root.join("draft.txt"), "final.txt"),
+            Ok(root.join("final.txt"))
+        );
+        assert_eq!(fs::read_to_string(root.join("final.txt")).unwrap(), "A");
+        assert!(rename_one(&root.join("final.txt"), "taken.txt").is_err());
+        assert!(rename_one(&root.join("final.txt"), "bad:name.txt").is_err());
+        assert!(rename_one(&root.join("missing.txt"), "other.txt").is_err());
+        assert_eq!(
+            rename_one(&root.join("final.txt"), "Final.txt"),
+            Ok(root.join("Final.txt"))
+        );
+        assert!(root.join("Final.txt").exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_execute_swaps_names() {
+        let root = temp_root("rename-swap");
+        fs::write(root.join("a.txt"), "A").unwrap();
+        fs::write(root.join("b.txt"), "B").unwrap();
+
+        let plans = vec![
+            RenamePlan {
+                from: root.join("a.txt"),
+                to: root.join("b.txt"),
+                conflict: None,
+            },
+            RenamePlan {
+                from: root.join("b.txt"),
+                to: root.join("a.txt"),
+                conflict: None,
+            },
+        ];
+        let report = execute_renames(&plans);
+
+        assert_eq!(report.renamed.len(), 2);
+        assert!(report.failed.is_empty());
+        assert_eq!(fs::read_to_string(root.join("a.txt")).unwrap(), "B");
+        assert_eq!(fs::read_to_string(root.join("b.txt")).unwrap(), "A");
+        let _ = fs::remove_dir_all(&root);
+    }
+}
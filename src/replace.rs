@@ -0,0 +1,561 @@
+use encoding_rs::Encoding;
+use rayon::prelude::*;
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+// Pattern built from the current search options.
+pub struct ReplacePattern {
+    regex: Regex,
+    replacement: String,
+    // Literal mode: `$1` in the replacement is kept as typed.
+    expand_captures: bool,
+}
+
+impl ReplacePattern {
+    // Regex mode supports capture groups (`$1`, `${name}`); otherwise both sides are literal.
+    pub fn new(
+        query: &str,
+        replacement: &str,
+        use_regex: bool,
+        case_sensitive: bool,
+    ) -> Option<Self> {
+        if query.is_empty() {
+            return None;
+        }
+        let pattern = if use_regex {
+            query.to_string()
+        } else {
+            regex::escape(query)
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(!case_sensitive)
+            .build()
+            .ok()?;
+
+        Some(Self {
+            regex,
+            replacement: replacement.to_string(),
+            expand_captures: use_regex,
+        })
+    }
+
+    // Returns the replaced line, or None when the line does not match.
+    pub fn apply(&self, line: &str) -> Option<String> {
+        if !self.regex.is_match(line) {
+            return None;
+        }
+        let replaced = if self.expand_captures {
+            self.regex.replace_all(line, self.replacement.as_str())
+        } else {
+            self.regex.replace_all(line, NoExpand(&self.replacement))
+        };
+        Some(replaced.into_owned())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineChange {
+    pub file_path: PathBuf,
+    pub line_number: usize, // 1-based
+    pub before: String,
+    pub after: String,
+}
+
+// Splits a line into its content and its ending ("\r\n", "\n" or "").
+fn split_ending(segment: &str) -> (&str, &str) {
+    if let Some(content) = segment.strip_suffix("\r\n") {
+        (content, "\r\n")
+    } else if let Some(content) = segment.strip_suffix('\n') {
+        (content, "\n")
+    } else {
+        (segment, "")
+    }
+}
+
+// A text file decoded the way the content search reads it: by its BOM
+// (UTF-8, UTF-16), as UTF-8, or else as Windows-1252. It is written back in
+// the same encoding, BOM included.
+struct TextFile {
+    content: String,
+    encoding: &'static Encoding,
+    bom: bool,
+}
+
+impl TextFile {
+    fn read(path: &Path) -> io::Result<Self> {
+        let bytes = fs::read(path)?;
+        if let Some((encoding, bom_length)) = Encoding::for_bom(&bytes) {
+            let (content, _) = encoding.decode_without_bom_handling(&bytes[bom_length..]);
+            return Ok(Self {
+                content: content.into_owned(),
+                encoding,
+                bom: true,
+            });
+        }
+        // Without a BOM, a NUL byte means a binary file, never rewritten.
+        if bytes.contains(&0) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "binary file"));
+        }
+        let (content, encoding) = match String::from_utf8(bytes) {
+            Ok(content) => (content, encoding_rs::UTF_8),
+            Err(err) => {
+                let (content, _) =
+                    encoding_rs::WINDOWS_1252.decode_without_bom_handling(err.as_bytes());
+                (content.into_owned(), encoding_rs::WINDOWS_1252)
+            }
+        };
+        Ok(Self {
+            content,
+            encoding,
+            bom: false,
+        })
+    }
+
+    // `content` in the encoding of the file; None when a character of the
+    // replacement has no code in it (Windows-1252).
+    fn encode(&self, content: &str) -> Option<Vec<u8>> {
+        let mut bytes = Vec::new();
+        if self.encoding == encoding_rs::UTF_16LE || self.encoding == encoding_rs::UTF_16BE {
+            let little_endian = self.encoding == encoding_rs::UTF_16LE;
+            let units = self
+                .bom
+                .then_some(0xfeff)
+                .into_iter()
+                .chain(content.encode_utf16());
+            for unit in units {
+                bytes.extend(if little_endian {
+                    unit.to_le_bytes()
+                } else {
+                    unit.to_be_bytes()
+                });
+            }
+            return Some(bytes);
+        }
+        if self.bom {
+            bytes.extend_from_slice(b"\xef\xbb\xbf");
+        }
+        let (encoded, _, unmappable) = self.encoding.encode(content);
+        if unmappable {
+            return None;
+        }
+        bytes.extend_from_slice(&encoded);
+        Some(bytes)
+    }
+}
+
+// Lists the substitutions the pattern would make in a text file.
+pub fn preview_file(path: &Path, pattern: &ReplacePattern) -> io::Result<Vec<LineChange>> {
+    let content = TextFile::read(path)?.content;
+
+    Ok(content
+        .split_inclusive('\n')
+        .enumerate()
+        .filter_map(|(i, segment)| {
+            let (line, _) = split_ending(segment);
+            let after = pattern.apply(line)?;
+            (after != line).then(|| LineChange {
+                file_path: path.to_path_buf(),
+                line_number: i + 1,
+                before: line.to_string(),
+                after,
+            })
+        })
+        .collect())
+}
+
+// Preview over several files; unreadable and binary files are skipped.
+pub fn preview(paths: &[PathBuf], pattern: &ReplacePattern) -> Vec<LineChange> {
+    paths
+        .par_iter()
+        .flat_map(|path| preview_file(path, pattern).unwrap_or_default())
+        .collect()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupEntry {
+    original: PathBuf,
+    backup: PathBuf,
+    // Modification time of the file the replace wrote: a file edited since
+    // then is not overwritten by the undo (None in older manifests).
+    #[serde(default)]
+    written: Option<SystemTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupManifest {
+    files: Vec<BackupEntry>,
+}
+
+#[derive(Debug, Default)]
+pub struct ApplyReport {
+    pub files_changed: usize,
+    pub lines_changed: usize,
+    // Files left untouched, with the reason.
+    pub skipped: Vec<(PathBuf, String)>,
+}
+
+pub fn backups_dir() -> PathBuf {
+    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("quick-findr");
+    path.push("backups");
+    path
+}
+
+// Writes the selected changes. Every modified file is first copied into a new
+// session folder under `backup_root`, which `undo_last` restores.
+// A file whose lines no longer match the preview, or that `write_refusal`
+// rejects, is skipped.
+pub fn apply_changes(changes: &[LineChange], backup_root: &Path) -> io::Result<ApplyReport> {
+    let mut by_file: BTreeMap<&Path, Vec<&LineChange>> = BTreeMap::new();
+    for change in changes {
+        by_file.entry(&change.file_path).or_default().push(change);
+    }
+
+    let session = backup_root.join(session_name());
+    fs::create_dir_all(&session)?;
+
+    let mut report = ApplyReport::default();
+    let mut manifest = BackupManifest { files: Vec::new() };
+
+    for (index, (path, file_changes)) in by_file.into_iter().enumerate() {
+        if let Some(reason) = write_refusal(path, backup_root) {
+            report
+                .skipped
+                .push((path.to_path_buf(), reason.to_string()));
+            continue;
+        }
+        let file = match TextFile::read(path) {
+            Ok(file) => file,
+            Err(err) => {
+                report.skipped.push((path.to_path_buf(), err.to_string()));
+                continue;
+            }
+        };
+
+        let mut segments: Vec<String> = file
+            .content
+            .split_inclusive('\n')
+            .map(String::from)
+            .collect();
+        let mut stale = false;
+        for change in &file_changes {
+            match segments.get_mut(change.line_number - 1) {
+                Some(segment) if split_ending(segment).0 == change.before => {
+                    let ending = split_ending(segment).1.to_string();
+                    *segment = format!("{}{}", change.after, ending);
+                }
+                _ => stale = true,
+            }
+        }
+        if stale {
+            report
+                .skipped
+                .push((path.to_path_buf(), "changed since the preview".to_string()));
+            continue;
+        }
+        let Some(bytes) = file.encode(&segments.concat()) else {
+            report.skipped.push((
+                path.to_path_buf(),
+                format!(
+                    "the replacement does not fit the {} encoding",
+                    file.encoding.name()
+                ),
+            ));
+            continue;
+        };
+
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let backup = session.join(format!("{}_{}", index, file_name));
+        if let Err(err) = fs::copy(path, &backup) {
+            report.skipped.push((path.to_path_buf(), err.to_string()));
+            continue;
+        }
+        if let Err(err) = write_atomically(path, &bytes) {
+            let _ = fs::remove_file(&backup);
+            report.skipped.push((path.to_path_buf(), err.to_string()));
+            continue;
+        }
+        manifest.files.push(BackupEntry {
+            original: path.to_path_buf(),
+            backup,
+            written: modified_time(path),
+        });
+        report.files_changed += 1;
+        report.lines_changed += file_changes.len();
+    }
+
+    if manifest.files.is_empty() {
+        let _ = fs::remove_dir_all(&session);
+    } else {
+        let json = serde_json::to_string_pretty(&manifest)?;
+        fs::write(session.join("manifest.json"), json)?;
+    }
+
+    Ok(report)
+}
+
+// Files the replace never writes: the rename in `write_atomically` would turn
+// a symbolic link into a plain file, and read-only files are left as the user
+// marked them.
+fn write_refusal(path: &Path, backup_root: &Path) -> Option<&'static str> {
+    if path.starts_with(backup_root) {
+        return Some("inside the backup folder");
+    }
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return None, // Reported by the read that follows.
+    };
+    if metadata.file_type().is_symlink() {
+        Some("symbolic link")
+    } else if !metadata.is_file() {
+        Some("not a regular file")
+    } else if metadata.permissions().readonly() {
+        Some("read-only")
+    } else {
+        None
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+// Writes next to the target, then renames over it.
+fn write_atomically(path: &Path, content: &[u8]) -> io::Result<()> {
+    let tmp = path.with_extension("quickfindr-tmp");
+    fs::write(&tmp, content)?;
+    let permissions = fs::metadata(path)?.permissions();
+    fs::set_permissions(&tmp, permissions)?;
+    fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
+// Sortable, unique-enough folder name for a backup session.
+fn session_name() -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    format!("{:013}-{:09}", now.as_millis(), now.subsec_nanos())
+}
+
+#[derive(Debug, Default)]
+pub struct UndoReport {
+    pub restored: usize,
+    // Files edited since the replace, left as they are; their backup copies
+    // stay in the backup folder.
+    pub skipped: Vec<PathBuf>,
+}
+
+// Restores the files of the most recent replace operation, then drops its
+// backup. Nothing to undo gives an empty report.
+pub fn undo_last(backup_root: &Path) -> io::Result<UndoReport> {
+    let latest = match fs::read_dir(backup_root) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.join("manifest.json").is_file())
+            .max(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => return Err(err),
+    };
+    let Some(session) = latest else {
+        return Ok(UndoReport::default());
+    };
+
+    let manifest: BackupManifest =
+        serde_json::from_str(&fs::read_to_string(session.join("manifest.json"))?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    let mut report = UndoReport::default();
+    for entry in &manifest.files {
+        if entry
+            .written
+            .is_some_and(|written| modified_time(&entry.original) != Some(written))
+        {
+            report.skipped.push(entry.original.clone());
+            continue;
+        }
+        fs::copy(&entry.backup, &entry.original)?;
+        report.restored += 1;
+    }
+    if report.skipped.is_empty() {
+        fs::remove_dir_all(&session)?;
+    } else {
+        // The session is no longer the last one to undo.
+        fs::remove_file(session.join("manifest.json"))?;
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_root;
+
+    #[test]
+    fn test_literal_pattern_keeps_dollar_signs() {
+        let pattern = ReplacePattern::new("price", "$1", false, false).unwrap();
+        assert_eq!(pattern.apply("Price: 3").unwrap(), "$1: 3");
+        assert!(pattern.apply("nothing here").is_none());
+    }
+
+    #[test]
+    fn test_regex_pattern_expands_captures() {
+        let pattern = ReplacePattern::new(r"(\w+)@old\.com", "$1@new.com", true, true).unwrap();
+        assert_eq!(
+            pattern.apply("mail bob@old.com and amy@old.com").unwrap(),
+            "mail bob@new.com and amy@new.com"
+        );
+    }
+
+    #[test]
+    fn test_invalid_or_empty_pattern() {
+        assert!(ReplacePattern::new("[oops", "x", true, false).is_none());
+        assert!(ReplacePattern::new("", "x", false, false).is_none());
+    }
+
+    #[test]
+    fn test_preview_file_lists_changed_lines() {
+        let root = temp_root("replace-preview");
+        let file = root.join("a.txt");
+        fs::write(&file, "foo\r\nbar\nfoo bar\n").unwrap();
+
+        let pattern = ReplacePattern::new("foo", "baz", false, true).unwrap();
+        let changes = preview_file(&file, &pattern).unwrap();
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].line_number, 1);
+        assert_eq!(changes[0].before, "foo");
+        assert_eq!(changes[0].after, "baz");
+        assert_eq!(changes[1].line_number, 3);
+        assert_eq!(changes[1].after, "baz bar");
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_apply_selected_lines_and_undo() {
+        let root = temp_root("replace-apply");
+        let backups = root.join("backups");
+        let file = root.join("a.txt");
+        fs::write(&file, "foo\r\nbar\nfoo\n").unwrap();
+
+        let pattern = ReplacePattern::new("foo", "baz", false, true).unwrap();
+        let changes = preview_file(&file, &pattern).unwrap();
+
+        // Only the first occurrence is ticked; line endings are preserved.
+        let report = apply_changes(&changes[..1], &backups).unwrap();
+        assert_eq!(report.files_changed, 1);
+        assert_eq!(report.lines_changed, 1);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "baz\r\nbar\nfoo\n");
+
+        assert_eq!(undo_last(&backups).unwrap().restored, 1);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "foo\r\nbar\nfoo\n");
+        assert_eq!(undo_last(&backups).unwrap().restored, 0);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_apply_keeps_encoding() {
+        let root = temp_root("replace-encoding");
+        let backups = root.join("backups");
+        let utf16 = root.join("utf16.txt");
+        let mut bytes = vec![0xff, 0xfe];
+        bytes.extend("foo\r\n".encode_utf16().flat_map(u16::to_le_bytes));
+        fs::write(&utf16, &bytes).unwrap();
+        let latin = root.join("latin.txt");
+        fs::write(&latin, b"caf\xe9 foo\n").unwrap();
+        let binary = root.join("data.bin");
+        fs::write(&binary, b"foo\0").unwrap();
+
+        let pattern = ReplacePattern::new("foo", "bar", false, true).unwrap();
+        let changes = preview(&[utf16.clone(), latin.clone(), binary], &pattern);
+        assert_eq!(changes.len(), 2);
+        let report = apply_changes(&changes, &backups).unwrap();
+        assert_eq!(report.files_changed, 2);
+
+        let mut expected = vec![0xff, 0xfe];
+        expected.extend("bar\r\n".encode_utf16().flat_map(u16::to_le_bytes));
+        assert_eq!(fs::read(&utf16).unwrap(), expected);
+        assert_eq!(fs::read(&latin).unwrap(), b"caf\xe9 bar\n");
+
+        // "€" exists in Windows-1252, "→" does not.
+        let pattern = ReplacePattern::new("bar", "→", false, true).unwrap();
+        let report = apply_changes(&preview_file(&latin, &pattern).unwrap(), &backups).unwrap();
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(fs::read(&latin).unwrap(), b"caf\xe9 bar\n");
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_undo_skips_files_edited_since() {
+        let root = temp_root("replace-undo-edited");
+        let backups = root.join("backups");
+        let file = root.join("a.txt");
+        fs::write(&file, "foo\n").unwrap();
+
+        let pattern = ReplacePattern::new("foo", "baz", false, true).unwrap();
+        apply_changes(&preview_file(&file, &pattern).unwrap(), &backups).unwrap();
+        let edited = fs::File::options().write(true).open(&file).unwrap();
+        edited
+            .set_modified(SystemTime::now() + std::time::Duration::from_secs(60))
+            .unwrap();
+
+        let report = undo_last(&backups).unwrap();
+        assert_eq!(report.restored, 0);
+        assert_eq!(report.skipped, vec![file.clone()]);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "baz\n");
+        assert_eq!(undo_last(&backups).unwrap().restored, 0);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_apply_skips_file_changed_since_preview() {
+        let root = temp_root("replace-stale");
+        let file = root.join("a.txt");
+        fs::write(&file, "foo\n").unwrap();
+
+        let pattern = ReplacePattern::new("foo", "baz", false, true).unwrap();
+        let changes = preview_file(&file, &pattern).unwrap();
+        fs::write(&file, "edited meanwhile\n").unwrap();
+
+        let report = apply_changes(&changes, &root.join("backups")).unwrap();
+        assert_eq!(report.files_changed, 0);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "edited meanwhile\n");
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_apply_refuses_read_only_files_and_backups() {
+        let root = temp_root("replace-refused");
+        let backups = root.join("backups");
+        let locked = root.join("locked.txt");
+        let backup = backups.join("old").join("a.txt");
+        fs::create_dir_all(backup.parent().unwrap()).unwrap();
+        fs::write(&locked, "foo\n").unwrap();
+        fs::write(&backup, "foo\n").unwrap();
+        let mut permissions = fs::metadata(&locked).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&locked, permissions.clone()).unwrap();
+
+        let pattern = ReplacePattern::new("foo", "baz", false, true).unwrap();
+        let changes = preview(&[locked.clone(), backup.clone()], &pattern);
+        let report = apply_changes(&changes, &backups).unwrap();
+
+        assert_eq!(report.files_changed, 0);
+        assert_eq!(report.skipped.len(), 2);
+        assert_eq!(fs::read_to_string(&locked).unwrap(), "foo\n");
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "foo\n");
+
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        fs::set_permissions(&locked, permissions).unwrap();
+        let _ = fs::remove_dir_all(&root);
+    }
+}
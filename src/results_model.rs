@@ -0,0 +1,204 @@
+// The results list as a Slint model that owns every result of the search and
+// converts a row for the UI only when it is first read (scrolled into view), so
+// a search with hundreds of thousands of results needs no "Load more" paging.
+
+use crate::engine::SearchResult as EngineSearchResult;
+use crate::SearchResult;
+use slint::{Model, ModelNotify, ModelTracker};
+use std::cell::RefCell;
+
+#[derive(Clone)]
+pub enum Row {
+    // Not displayed yet: kept as the engine found it.
+    Pending(EngineSearchResult),
+    Ready(SearchResult),
+}
+
+// What a row tells without being converted.
+pub struct RowInfo<'a> {
+    pub file_name: &'a str,
+    pub file_path: &'a str,
+    pub relative_path: &'a str,
+    pub extension: &'a str,
+    pub is_dir: bool,
+    // Only displayed rows can have been checked.
+    pub checked: bool,
+}
+
+// A matched line of a row.
+pub struct RowLine {
+    pub line_number: usize,
+    pub column: usize,
+    pub text: String,
+}
+
+impl Row {
+    pub fn info(&self) -> RowInfo<'_> {
+        match self {
+            Row::Pending(result) => RowInfo {
+                file_name: &result.file_name,
+                file_path: &result.file_path,
+                relative_path: &result.relative_path,
+                extension: &result.extension,
+                is_dir: result.is_dir,
+                checked: false,
+            },
+            Row::Ready(item) => RowInfo {
+                file_name: &item.file_name,
+                file_path: &item.file_path,
+                relative_path: &item.relative_path,
+                extension: &item.extension,
+                is_dir: item.is_dir,
+                checked: item.checked,
+            },
+        }
+    }
+
+    pub fn lines(&self) -> Vec<RowLine> {
+        match self {
+            Row::Pending(result) => result
+                .matches
+                .iter()
+                .map(|hit| RowLine {
+                    line_number: hit.line_number,
+                    column: hit.column,
+                    text: hit.text.clone(),
+                })
+                .collect(),
+            Row::Ready(item) => item
+                .matches
+                .iter()
+                .map(|hit| RowLine {
+                    line_number: hit.line_number.max(0) as usize,
+                    column: hit.column.max(0) as usize,
+                    text: hit
+                        .segments
+                        .iter()
+                        .map(|segment| segment.text.to_string())
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    // Rows never displayed get their thumbnail once they are.
+    pub fn set_thumbnail(&mut self, thumbnail: &slint::Image) {
+        if let Row::Ready(item) = self {
+            item.thumbnail = thumbnail.clone();
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ResultsModel {
+    rows: RefCell<Vec<Row>>,
+    notify: ModelNotify,
+}
+
+impl ResultsModel {
+    pub fn clear(&self) {
+        self.rows.borrow_mut().clear();
+        self.notify.reset();
+    }
+
+    // Replaces the list with results straight from the engine.
+    pub fn set_results(&self, results: Vec<EngineSearchResult>) {
+        *self.rows.borrow_mut() = results.into_iter().map(Row::Pending).collect();
+        self.notify.reset();
+    }
+
+    // Replaces the list with rows taken from it before (see `take_rows`).
+    pub fn set_rows(&self, rows: Vec<Row>) {
+        *self.rows.borrow_mut() = rows;
+        self.notify.reset();
+    }
+
+    // Empties the list, returning its rows as they are.
+    pub fn take_rows(&self) -> Vec<Row> {
+        let rows = std::mem::take(&mut *self.rows.borrow_mut());
+        self.notify.reset();
+        rows
+    }
+
+    pub fn extend(&self, results: Vec<EngineSearchResult>) {
+        if results.is_empty() {
+            return;
+        }
+        let start = self.row_count();
+        let count = results.len();
+        self.rows
+            .borrow_mut()
+            .extend(results.into_iter().map(Row::Pending));
+        self.notify.row_added(start, count);
+    }
+
+    pub fn remove(&self, index: usize) -> Option<SearchResult> {
+        let item = self.row_data(index)?;
+        self.rows.borrow_mut().remove(index);
+        self.notify.row_removed(index, 1);
+        Some(item)
+    }
+
+    pub fn retain(&self, mut keep: impl FnMut(&RowInfo) -> bool) {
+        self.rows.borrow_mut().retain(|row| keep(&row.info()));
+        self.notify.reset();
+    }
+
+    // Visits every row without converting it; `visit` must not touch the model.
+    pub fn for_each(&self, mut visit: impl FnMut(usize, &RowInfo)) {
+        for (index, row) in self.rows.borrow().iter().enumerate() {
+            visit(index, &row.info());
+        }
+    }
+
+    // Indexes of the rows `matches` accepts.
+    pub fn positions(&self, mut matches: impl FnMut(&RowInfo) -> bool) -> Vec<usize> {
+        let mut positions = Vec::new();
+        self.for_each(|index, row| {
+            if matches(row) {
+                positions.push(index);
+            }
+        });
+        positions
+    }
+
+    // Reads every row without converting it; `read` must not touch the model.
+    pub fn with_rows<T>(&self, read: impl FnOnce(&[Row]) -> T) -> T {
+        read(&self.rows.borrow())
+    }
+}
+
+impl Model for ResultsModel {
+    type Data = SearchResult;
+
+    fn row_count(&self) -> usize {
+        self.rows.borrow().len()
+    }
+
+    fn row_data(&self, row: usize) -> Option<SearchResult> {
+        let mut rows = self.rows.borrow_mut();
+        let slot = rows.get_mut(row)?;
+        let item = match std::mem::replace(slot, Row::Ready(SearchResult::default())) {
+            Row::Pending(result) => {
+                let item = crate::to_ui_result(result);
+                crate::request_thumbnail(&item);
+                item
+            }
+            Row::Ready(item) => item,
+        };
+        *slot = Row::Ready(item.clone());
+        Some(item)
+    }
+
+    fn set_row_data(&self, row: usize, data: SearchResult) {
+        match self.rows.borrow_mut().get_mut(row) {
+            Some(slot) => *slot = Row::Ready(data),
+            None => return,
+        }
+        self.notify.row_changed(row);
+    }
+
+    fn model_tracker(&self) -> &dyn ModelTracker {
+        &self.notify
+    }
+}
@@ -0,0 +1,168 @@
+// The search the app was closed on (folder, query, search bar options) and
+// where its window was, restored at the next launch.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub folder: Option<PathBuf>,
+    pub query: String,
+    pub search_content: bool,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    pub match_path: bool,
+    pub ignore_accents: bool,
+    pub walk_ignored_dirs: bool,
+    pub tracked_only: bool,
+    pub low_priority: bool,
+    pub search_archives: bool,
+    pub use_regex: bool,
+    pub fuzzy: bool,
+    pub sort_recent: bool,
+    pub sort_shallow: bool,
+    pub include_hidden: bool,
+    pub follow_links: bool,
+    #[serde(default = "default_true")]
+    pub respect_gitignore: bool,
+    // Excluded-by-default categories the user let back in.
+    pub include_logs: bool,
+    pub include_binaries: bool,
+    pub include_temp: bool,
+    pub include_build: bool,
+    pub include_archives: bool,
+    pub language_filter: String,
+    pub include_globs: String,
+    pub exclude_globs: String,
+    pub size_filter: String,
+    pub max_depth: String,
+    pub window: Option<WindowGeometry>,
+}
+
+// Outer position and inner size, in physical pixels, of the window when it
+// is neither maximized nor minimized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+impl WindowGeometry {
+    // Whether the title bar would be on a connected monitor: a window saved on
+    // a screen that has since been unplugged opens at the default position.
+    pub fn is_on_screen(&self) -> bool {
+        let (x, y) = self.title_bar_point();
+        point_on_monitor(x, y)
+    }
+
+    // Middle of the top of the window, where it is grabbed to be moved.
+    fn title_bar_point(&self) -> (i32, i32) {
+        (
+            self.x.saturating_add((self.width / 2) as i32),
+            self.y.saturating_add(16),
+        )
+    }
+}
+
+#[cfg(windows)]
+fn point_on_monitor(x: i32, y: i32) -> bool {
+    use windows_sys::Win32::Foundation::POINT;
+    use windows_sys::Win32::Graphics::Gdi::{MonitorFromPoint, MONITOR_DEFAULTTONULL};
+
+    // SAFETY: plain call on a point; no handle is kept.
+    let monitor = unsafe { MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONULL) };
+    !monitor.is_null()
+}
+
+// Window managers elsewhere keep new windows on screen themselves (Wayland
+// ignores the position altogether).
+#[cfg(not(windows))]
+fn point_on_monitor(_x: i32, _y: i32) -> bool {
+    true
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Session {
+    // None on the first launch (or when the file cannot be read).
+    pub fn load() -> Option<Self> {
+        let content = fs::read_to_string(Self::get_config_path()).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let config_path = Self::get_config_path();
+
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(&config_path, json)?;
+
+        Ok(())
+    }
+
+    fn get_config_path() -> PathBuf {
+        let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+        path.push("quick-findr");
+        path.push("session.json");
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_round_trip() {
+        let session = Session {
+            folder: Some(PathBuf::from(r"C:\src")),
+            query: "*.rs".to_string(),
+            search_content: true,
+            respect_gitignore: false,
+            exclude_globs: "target/".to_string(),
+            window: Some(WindowGeometry {
+                x: -1200,
+                y: 80,
+                width: 1100,
+                height: 700,
+                maximized: true,
+            }),
+            ..Session::default()
+        };
+        let json = serde_json::to_string(&session).unwrap();
+        assert_eq!(serde_json::from_str::<Session>(&json).unwrap(), session);
+
+        // Keys missing from an older file keep the search bar defaults.
+        let partial: Session = serde_json::from_str(r#"{"query":"todo"}"#).unwrap();
+        assert_eq!(partial.query, "todo");
+        assert!(partial.respect_gitignore);
+        assert!(!partial.search_content);
+        assert_eq!(partial.window, None);
+        let normal: Session =
+            serde_json::from_str(r#"{"window":{"x":10,"y":20,"width":800,"height":600}}"#).unwrap();
+        assert_eq!(normal.window.map(|window| window.maximized), Some(false));
+    }
+
+    #[test]
+    fn test_title_bar_point() {
+        let geometry = WindowGeometry {
+            x: -1920,
+            y: 100,
+            width: 1000,
+            height: 600,
+            maximized: false,
+        };
+        assert_eq!(geometry.title_bar_point(), (-1420, 116));
+    }
+}
@@ -0,0 +1,279 @@
+use crate::colors::CustomColors;
+use crate::engine::DEFAULT_IGNORED_DIRS;
+use crate::i18n::Language;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+// User preferences persisted across sessions (missing keys take their default).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // Opt-in: record searches and file actions in the audit log.
+    pub audit_log_enabled: bool,
+    // The walk stops once this many results are found (0: no limit).
+    pub max_results: usize,
+    // Content search reads at most this many lines per file (0: no limit).
+    pub max_content_lines: usize,
+    // Worker threads of a search (0: one per CPU).
+    pub worker_threads: usize,
+    // Folders with a file-name index, refreshed in the background at startup.
+    pub indexed_roots: Vec<PathBuf>,
+    // Folders never entered by searches and indexes ("target", "node_modules"...).
+    pub ignored_dirs: Vec<String>,
+    // Extra ignore file applied by searches and indexes that honor ignore files.
+    pub ignore_file: Option<PathBuf>,
+    // Content search reads images and scanned PDFs through OCR (builds with
+    // the `ocr` feature only).
+    pub ocr_enabled: bool,
+    // Content search reads cloud files that are not on disk (OneDrive
+    // placeholders), downloading them.
+    pub read_cloud_files: bool,
+    // "Open in editor" command: {file}, {line} and {column} are filled in.
+    pub editor_command: String,
+    // Global hotkey that brings the window to the front ("Alt+Space"; empty: none).
+    pub global_hotkey: String,
+    pub language: Language,
+    pub theme: Theme,
+    pub density: Density,
+    // Text size of the UI, in percent of the default size.
+    pub font_size_percent: u32,
+    // Accent and background colors replacing the theme ones ([colors] table).
+    pub colors: CustomColors,
+    // Badge color of the results by extension ([icon_colors] table, "rs" = "#dea584").
+    pub icon_colors: BTreeMap<String, String>,
+}
+
+// Light or dark UI; `System` follows the system setting, even when it
+// changes while the app is open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    System,
+    Dark,
+    Light,
+}
+
+impl Theme {
+    // Name used by the UI chips ("system", "dark", "light").
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "dark" => Self::Dark,
+            "light" => Self::Light,
+            _ => Self::System,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::Dark => "dark",
+            Self::Light => "light",
+        }
+    }
+}
+
+// Spacing around the results and the search bar: compact fits more rows on
+// a laptop screen, comfortable leaves room for touch screens.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Density {
+    Compact,
+    #[default]
+    Normal,
+    Comfortable,
+}
+
+impl Density {
+    // Name used by the UI chips ("compact", "normal", "comfortable").
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "compact" => Self::Compact,
+            "comfortable" => Self::Comfortable,
+            _ => Self::Normal,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Compact => "compact",
+            Self::Normal => "normal",
+            Self::Comfortable => "comfortable",
+        }
+    }
+
+    // Factor applied to the paddings of the UI.
+    pub fn spacing_scale(self) -> f32 {
+        match self {
+            Self::Compact => 0.5,
+            Self::Normal => 1.0,
+            Self::Comfortable => 1.5,
+        }
+    }
+}
+
+// Range of the text size slider; a hand-edited value is clamped to it.
+pub const MIN_FONT_SIZE_PERCENT: u32 = 80;
+pub const MAX_FONT_SIZE_PERCENT: u32 = 150;
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            audit_log_enabled: false,
+            max_results: 100_000,
+            max_content_lines: 5000,
+            worker_threads: 0,
+            indexed_roots: Vec::new(),
+            ignored_dirs: DEFAULT_IGNORED_DIRS
+                .iter()
+                .map(|dir| dir.to_string())
+                .collect(),
+            ignore_file: None,
+            ocr_enabled: false,
+            read_cloud_files: false,
+            editor_command: crate::editor::DEFAULT_COMMAND.to_string(),
+            global_hotkey: String::new(),
+            language: Language::System,
+            theme: Theme::System,
+            density: Density::Normal,
+            font_size_percent: 100,
+            colors: CustomColors::default(),
+            icon_colors: crate::colors::default_icon_colors(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        if let Ok(content) = fs::read_to_string(Self::get_config_path()) {
+            if let Ok(settings) = toml::from_str(&content) {
+                return settings;
+            }
+        }
+
+        // Earlier versions saved JSON; the next save writes TOML.
+        if let Ok(content) = fs::read_to_string(Self::config_file("settings.json")) {
+            if let Ok(settings) = serde_json::from_str(&content) {
+                return settings;
+            }
+        }
+
+        Self::default()
+    }
+
+    // Factor applied to every font size of the UI.
+    pub fn font_scale(&self) -> f32 {
+        self.font_size_percent
+            .clamp(MIN_FONT_SIZE_PERCENT, MAX_FONT_SIZE_PERCENT) as f32
+            / 100.0
+    }
+
+    // Worker threads of a search: the setting, or one per CPU.
+    pub fn search_threads(&self) -> usize {
+        if self.worker_threads == 0 {
+            num_cpus::get()
+        } else {
+            self.worker_threads
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let config_path = Self::get_config_path();
+
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let text = toml::to_string_pretty(self)?;
+        fs::write(&config_path, text)?;
+
+        Ok(())
+    }
+
+    fn get_config_path() -> PathBuf {
+        Self::config_file("settings.toml")
+    }
+
+    fn config_file(name: &str) -> PathBuf {
+        let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+        path.push("quick-findr");
+        path.push(name);
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_keys_use_defaults() {
+        let settings: Settings = serde_json::from_str("{}").unwrap();
+        assert!(!settings.audit_log_enabled);
+        assert!(!settings.read_cloud_files);
+        assert_eq!(settings.editor_command, crate::editor::DEFAULT_COMMAND);
+        assert_eq!(settings.max_results, Settings::default().max_results);
+        assert_eq!(settings.search_threads(), num_cpus::get());
+        assert!(settings
+            .ignored_dirs
+            .iter()
+            .any(|dir| dir == "node_modules"));
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let mut settings = Settings {
+            max_results: 500,
+            ignore_file: Some(PathBuf::from("/home/me/.ignore")),
+            ..Settings::default()
+        };
+        settings.ignored_dirs.push("build".to_string());
+
+        let text = toml::to_string_pretty(&settings).unwrap();
+        assert!(text.contains("max_results = 500"));
+        assert!(text.contains("theme = \"system\""));
+        let loaded: Settings = toml::from_str(&text).unwrap();
+        assert_eq!(loaded.max_results, 500);
+        assert_eq!(loaded.ignore_file, settings.ignore_file);
+        assert_eq!(loaded.ignored_dirs, settings.ignored_dirs);
+
+        let partial: Settings = toml::from_str(
+            "worker_threads = 2\nlanguage = \"fr\"\ntheme = \"light\"\n\n[colors]\naccent = \"#e81123\"\n",
+        )
+        .unwrap();
+        assert_eq!(partial.colors.accent, "#e81123");
+        assert_eq!(partial.colors.background, "");
+        assert_eq!(partial.icon_colors, crate::colors::default_icon_colors());
+        assert_eq!(partial.language, Language::French);
+        assert_eq!(partial.theme, Theme::Light);
+        assert_eq!(Theme::from_name(partial.theme.name()), Theme::Light);
+        assert_eq!(partial.search_threads(), 2);
+        assert_eq!(partial.density, Density::Normal);
+        assert_eq!(partial.font_scale(), 1.0);
+        assert_eq!(
+            partial.max_content_lines,
+            Settings::default().max_content_lines
+        );
+    }
+
+    #[test]
+    fn test_density_and_font_size() {
+        let settings: Settings =
+            toml::from_str("density = \"compact\"\nfont_size_percent = 400\n").unwrap();
+        assert_eq!(settings.density, Density::Compact);
+        assert_eq!(
+            Density::from_name(settings.density.name()),
+            Density::Compact
+        );
+        assert_eq!(Density::from_name("unknown"), Density::Normal);
+        assert_eq!(settings.font_scale(), 1.5);
+
+        let small = Settings {
+            font_size_percent: 90,
+            ..Settings::default()
+        };
+        assert_eq!(small.font_scale(), 0.9);
+    }
+}
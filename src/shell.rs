@@ -0,0 +1,203 @@
+// Windows shell dialogs for a result: the "Open with" application picker
+// (modal: it blocks the calling thread until closed, so it is shown from a
+// thread of its own) and the properties sheet. Elsewhere the properties are
+// listed by the app itself.
+
+use crate::i18n::tr;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[cfg(windows)]
+fn wide(path: &Path) -> Vec<u16> {
+    use std::os::windows::ffi::OsStrExt;
+
+    path.as_os_str().encode_wide().chain(Some(0)).collect()
+}
+
+// Lets the user pick the application that opens the file, then opens it.
+#[cfg(windows)]
+pub fn open_with(path: &Path) -> Result<(), String> {
+    use windows_sys::Win32::System::Com::{
+        CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED,
+    };
+    use windows_sys::Win32::UI::Shell::{
+        SHOpenWithDialog, OAIF_ALLOW_REGISTRATION, OAIF_EXEC, OAIF_REGISTER_EXT, OPENASINFO,
+    };
+
+    // HRESULT of a dialog closed without a choice.
+    const CANCELLED: i32 = 0x800704C7_u32 as i32;
+
+    let file = wide(path);
+    let info = OPENASINFO {
+        pcszFile: file.as_ptr(),
+        pcszClass: std::ptr::null(),
+        oaifInFlags: OAIF_ALLOW_REGISTRATION | OAIF_REGISTER_EXT | OAIF_EXEC,
+    };
+    // SAFETY: `info` and the NUL-terminated `file` outlive the call; COM is
+    // initialized for this thread around it.
+    let result = unsafe {
+        let com = CoInitializeEx(std::ptr::null(), COINIT_APARTMENTTHREADED as u32);
+        let result = SHOpenWithDialog(std::ptr::null_mut(), &info);
+        if com >= 0 {
+            CoUninitialize();
+        }
+        result
+    };
+    if result >= 0 || result == CANCELLED {
+        Ok(())
+    } else {
+        Err(format!(
+            "the \"Open with\" dialog failed (0x{:08x})",
+            result
+        ))
+    }
+}
+
+#[cfg(not(windows))]
+pub fn open_with(_path: &Path) -> Result<(), String> {
+    Err("\"Open with\" is only available on Windows".to_string())
+}
+
+// Opens the Windows properties sheet of the file (it runs by itself, the call
+// returns at once); false when it cannot be shown.
+#[cfg(windows)]
+pub fn show_native_properties(path: &Path) -> bool {
+    use windows_sys::Win32::UI::Shell::{
+        ShellExecuteExW, SEE_MASK_INVOKEIDLIST, SHELLEXECUTEINFOW,
+    };
+
+    const SW_SHOW: i32 = 5;
+
+    let file = wide(path);
+    let verb: Vec<u16> = "properties".encode_utf16().chain(Some(0)).collect();
+    // SAFETY: the structure is zeroed (null is valid for every pointer field),
+    // and `file` and `verb` are NUL-terminated and outlive the call.
+    unsafe {
+        let mut info: SHELLEXECUTEINFOW = std::mem::zeroed();
+        info.cbSize = std::mem::size_of::<SHELLEXECUTEINFOW>() as u32;
+        info.fMask = SEE_MASK_INVOKEIDLIST;
+        info.lpVerb = verb.as_ptr();
+        info.lpFile = file.as_ptr();
+        info.nShow = SW_SHOW;
+        ShellExecuteExW(&mut info) != 0
+    }
+}
+
+#[cfg(not(windows))]
+pub fn show_native_properties(_path: &Path) -> bool {
+    false
+}
+
+// Label and value of each property listed when there is no native sheet.
+pub fn file_properties(path: &Path) -> io::Result<Vec<(&'static str, String)>> {
+    let metadata = fs::metadata(path)?;
+    let mut properties = vec![(
+        "Type",
+        if metadata.is_dir() {
+            tr!("Folder")
+        } else {
+            tr!("File")
+        }
+        .to_string(),
+    )];
+    if metadata.is_file() {
+        properties.push((tr!("Size"), format_size(metadata.len())));
+    }
+    let times = [
+        (tr!("Created"), metadata.created()),
+        (tr!("Modified"), metadata.modified()),
+        (tr!("Last accessed"), metadata.accessed()),
+    ];
+    for (label, time) in times {
+        if let Ok(time) = time {
+            let time: chrono::DateTime<chrono::Local> = time.into();
+            properties.push((label, time.format("%Y-%m-%d %H:%M:%S").to_string()));
+        }
+    }
+    let read_only = metadata.permissions().readonly();
+    properties.push((
+        tr!("Read-only"),
+        if read_only { tr!("yes") } else { tr!("no") }.to_string(),
+    ));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        properties.push(("Permissions", format_mode(metadata.permissions().mode())));
+    }
+    Ok(properties)
+}
+
+// "2.0 KB (2048 bytes)".
+fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        return tr!("{} bytes", bytes);
+    }
+    tr!("{} ({} bytes)", short_size(bytes), bytes)
+}
+
+// "512 B", "2.0 KB", "1.5 GB"; 1024-based (also the size column of the results).
+pub fn short_size(bytes: u64) -> String {
+    let units = [tr!("KB"), tr!("MB"), tr!("GB"), tr!("TB")];
+    if bytes < 1024 {
+        return tr!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < units.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, units[unit])
+}
+
+// "rwxr-xr-x (755)".
+#[cfg(unix)]
+fn format_mode(mode: u32) -> String {
+    let bits: String = (0..9)
+        .map(|i| {
+            let set = mode & (1 << (8 - i)) != 0;
+            match (set, i % 3) {
+                (false, _) => '-',
+                (true, 0) => 'r',
+                (true, 1) => 'w',
+                (true, _) => 'x',
+            }
+        })
+        .collect();
+    format!("{} ({:o})", bits, mode & 0o777)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 bytes");
+        assert_eq!(format_size(2048), "2.0 KB (2048 bytes)");
+        assert_eq!(format_size(3 << 30), "3.0 GB (3221225472 bytes)");
+        assert_eq!(short_size(1000), "1000 B");
+        assert_eq!(short_size(1536 << 20), "1.5 GB");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_format_mode() {
+        assert_eq!(format_mode(0o100755), "rwxr-xr-x (755)");
+        assert_eq!(format_mode(0o640), "rw-r----- (640)");
+    }
+
+    #[test]
+    fn test_file_properties() {
+        let path = std::env::temp_dir().join("quick-findr-shell-properties.txt");
+        fs::write(&path, "q3").unwrap();
+
+        let properties = file_properties(&path).unwrap();
+        assert!(properties.contains(&("Type", "File".to_string())));
+        assert!(properties.contains(&("Size", "2 bytes".to_string())));
+        assert!(properties.iter().any(|(label, _)| *label == "Modified"));
+        assert!(file_properties(&path.with_extension("missing")).is_err());
+        let _ = fs::remove_file(&path);
+    }
+}
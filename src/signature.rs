@@ -0,0 +1,158 @@
+use std::path::Path;
+
+// Authenticode state of an executable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub enum SignatureStatus {
+    // Valid signature chaining to a trusted root.
+    Signed { signer: String },
+    Unsigned,
+    // A signature is present but does not verify (reason included).
+    Invalid(String),
+    // Not checkable on this platform.
+    Unsupported,
+}
+
+impl SignatureStatus {
+    pub fn is_trusted(&self) -> bool {
+        matches!(self, Self::Signed { .. })
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::Signed { signer } if signer.is_empty() => "Signed".to_string(),
+            Self::Signed { signer } => format!("Signed by {}", signer),
+            Self::Unsigned => "Not signed".to_string(),
+            Self::Invalid(reason) => format!("Invalid signature: {}", reason),
+            Self::Unsupported => "Signature check unavailable".to_string(),
+        }
+    }
+}
+
+// Extensions whose embedded Authenticode signature is checked.
+pub fn is_signable(extension: &str) -> bool {
+    matches!(
+        extension.to_lowercase().as_str(),
+        "exe" | "dll" | "msi" | "sys"
+    )
+}
+
+// Verifies the embedded signature with WinVerifyTrust. Revocation is not checked
+// (no network access); files signed only through a catalog report as unsigned.
+#[cfg(windows)]
+pub fn verify(path: &Path) -> SignatureStatus {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::{
+        CERT_E_EXPIRED, CERT_E_UNTRUSTEDROOT, INVALID_HANDLE_VALUE, TRUST_E_BAD_DIGEST,
+        TRUST_E_EXPLICIT_DISTRUST, TRUST_E_NOSIGNATURE, TRUST_E_SUBJECT_FORM_UNKNOWN,
+    };
+    use windows_sys::Win32::Security::WinTrust::{
+        WinVerifyTrust, WINTRUST_ACTION_GENERIC_VERIFY_V2, WINTRUST_DATA, WINTRUST_FILE_INFO,
+        WTD_CACHE_ONLY_URL_RETRIEVAL, WTD_CHOICE_FILE, WTD_REVOKE_NONE, WTD_STATEACTION_CLOSE,
+        WTD_STATEACTION_VERIFY, WTD_UI_NONE,
+    };
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut file_info = WINTRUST_FILE_INFO {
+        cbStruct: std::mem::size_of::<WINTRUST_FILE_INFO>() as u32,
+        pcwszFilePath: wide.as_ptr(),
+        hFile: std::ptr::null_mut(),
+        pgKnownSubject: std::ptr::null_mut(),
+    };
+
+    // SAFETY: all-zero is a valid WINTRUST_DATA (null pointers, zero flags).
+    let mut data: WINTRUST_DATA = unsafe { std::mem::zeroed() };
+    data.cbStruct = std::mem::size_of::<WINTRUST_DATA>() as u32;
+    data.dwUIChoice = WTD_UI_NONE;
+    data.fdwRevocationChecks = WTD_REVOKE_NONE;
+    data.dwUnionChoice = WTD_CHOICE_FILE;
+    data.Anonymous.pFile = &mut file_info;
+    data.dwStateAction = WTD_STATEACTION_VERIFY;
+    data.dwProvFlags = WTD_CACHE_ONLY_URL_RETRIEVAL;
+    let mut action = WINTRUST_ACTION_GENERIC_VERIFY_V2;
+
+    // SAFETY: `data` and the buffers it points to outlive both calls; the state
+    // opened by the VERIFY call is released by the CLOSE call.
+    let (result, signer) = unsafe {
+        let result = WinVerifyTrust(
+            INVALID_HANDLE_VALUE,
+            &mut action,
+            &mut data as *mut WINTRUST_DATA as *mut _,
+        );
+        let signer = signer_name(data.hWVTStateData);
+        data.dwStateAction = WTD_STATEACTION_CLOSE;
+        WinVerifyTrust(
+            INVALID_HANDLE_VALUE,
+            &mut action,
+            &mut data as *mut WINTRUST_DATA as *mut _,
+        );
+        (result, signer)
+    };
+
+    match result {
+        0 => SignatureStatus::Signed {
+            signer: signer.unwrap_or_default(),
+        },
+        TRUST_E_NOSIGNATURE | TRUST_E_SUBJECT_FORM_UNKNOWN => SignatureStatus::Unsigned,
+        TRUST_E_BAD_DIGEST => SignatureStatus::Invalid("file modified after signing".to_string()),
+        TRUST_E_EXPLICIT_DISTRUST => {
+            SignatureStatus::Invalid("signer explicitly distrusted".to_string())
+        }
+        CERT_E_UNTRUSTEDROOT => SignatureStatus::Invalid("untrusted root".to_string()),
+        CERT_E_EXPIRED => SignatureStatus::Invalid("certificate expired".to_string()),
+        code => SignatureStatus::Invalid(format!("error 0x{:08X}", code as u32)),
+    }
+}
+
+// Display name of the leaf certificate of the first signer.
+#[cfg(windows)]
+unsafe fn signer_name(state: windows_sys::Win32::Foundation::HANDLE) -> Option<String> {
+    use windows_sys::Win32::Security::Cryptography::{
+        CertGetNameStringW, CERT_NAME_SIMPLE_DISPLAY_TYPE,
+    };
+    use windows_sys::Win32::Security::WinTrust::{
+        WTHelperGetProvCertFromChain, WTHelperGetProvSignerFromChain, WTHelperProvDataFromStateData,
+    };
+
+    let provider = WTHelperProvDataFromStateData(state);
+    if provider.is_null() {
+        return None;
+    }
+    let signer = WTHelperGetProvSignerFromChain(provider, 0, 0, 0);
+    if signer.is_null() {
+        return None;
+    }
+    let cert = WTHelperGetProvCertFromChain(signer, 0);
+    if cert.is_null() || (*cert).pCert.is_null() {
+        return None;
+    }
+
+    let mut buffer = [0u16; 256];
+    let len = CertGetNameStringW(
+        (*cert).pCert,
+        CERT_NAME_SIMPLE_DISPLAY_TYPE,
+        0,
+        std::ptr::null(),
+        buffer.as_mut_ptr(),
+        buffer.len() as u32,
+    ) as usize;
+    // `len` counts the terminating NUL.
+    (len > 1).then(|| String::from_utf16_lossy(&buffer[..len - 1]))
+}
+
+#[cfg(not(windows))]
+pub fn verify(_path: &Path) -> SignatureStatus {
+    SignatureStatus::Unsupported
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_signable() {
+        assert!(is_signable("EXE"));
+        assert!(is_signable("msi"));
+        assert!(!is_signable("txt"));
+    }
+}
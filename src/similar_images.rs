@@ -0,0 +1,181 @@
+use crate::engine::{publish_results, update_ui, SearchResult, SearchToken};
+use crate::i18n::tr;
+use ignore::WalkBuilder;
+use image::imageops::FilterType;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+// Extensions decoded by the enabled `image` features.
+pub fn is_image_extension(ext: &str) -> bool {
+    matches!(
+        ext.to_lowercase().as_str(),
+        "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" | "tif" | "tiff"
+    )
+}
+
+// Perceptual "difference hash": 64 bits comparing neighbour pixels of a 9x8
+// grayscale thumbnail. Resized or recompressed copies keep (almost) the same bits.
+pub fn dhash(path: &Path) -> Option<u64> {
+    let image = image::ImageReader::open(path)
+        .ok()?
+        .with_guessed_format()
+        .ok()?
+        .decode()
+        .ok()?;
+    let thumbnail = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let left = thumbnail.get_pixel(x, y)[0];
+            let right = thumbnail.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | u64::from(left < right);
+        }
+    }
+    Some(hash)
+}
+
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+// Lists images under `root` within `max_distance` bits of `reference`, closest first.
+pub fn find_similar_images(
+    root: &Path,
+    reference: u64,
+    max_distance: u32,
+    token: &SearchToken,
+) -> Vec<(PathBuf, u32)> {
+    let mut builder = WalkBuilder::new(root);
+    builder.hidden(true).threads(num_cpus::get());
+
+    let mut matches: Vec<(PathBuf, u32)> = builder
+        .build()
+        .par_bridge()
+        .filter_map(|entry| {
+            if token.is_cancelled() {
+                return None;
+            }
+
+            let dir_entry = entry.ok()?;
+            let path = dir_entry.path();
+            let extension = path.extension()?.to_string_lossy();
+            if !path.is_file() || !is_image_extension(&extension) {
+                return None;
+            }
+
+            let distance = hamming_distance(reference, dhash(path)?);
+            (distance <= max_distance).then(|| (dir_entry.into_path(), distance))
+        })
+        .collect();
+
+    matches.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+    matches
+}
+
+// Spawns the similar-image scan in a separate thread.
+pub fn spawn_similar_search(
+    reference_path: PathBuf,
+    max_distance: u32,
+    root_path: PathBuf,
+    sender: slint::Weak<crate::AppWindow>,
+    token: SearchToken,
+) {
+    std::thread::spawn(move || {
+        let start_time = Instant::now();
+
+        let reference = match dhash(&reference_path) {
+            Some(hash) => hash,
+            None => {
+                update_ui(&token, &sender, move |window| {
+                    window
+                        .set_status_text(tr!("Error: Unable to decode the reference image").into());
+                    window.set_active_threads(0);
+                });
+                return;
+            }
+        };
+
+        let results: Vec<SearchResult> =
+            find_similar_images(&root_path, reference, max_distance, &token)
+                .into_iter()
+                .map(|(path, distance)| SearchResult {
+                    line_match: tr!("Distance: {}", distance),
+                    size: std::fs::metadata(&path).map(|m| m.len()).ok(),
+                    ..SearchResult::for_path(&path, &root_path)
+                })
+                .collect();
+
+        let total_results_count = publish_results(&token, &sender, results);
+
+        let duration = start_time.elapsed().as_millis() as u64;
+        update_ui(&token, &sender, move |window| {
+            window.set_status_text(
+                tr!(
+                    "Completed: {} similar images in {}ms",
+                    total_results_count,
+                    duration
+                )
+                .into(),
+            );
+            window.set_active_threads(0);
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_root;
+    use image::{GrayImage, Luma};
+    use std::fs;
+
+    // Horizontal gradient, optionally mirrored.
+    fn gradient(width: u32, height: u32, mirrored: bool) -> GrayImage {
+        GrayImage::from_fn(width, height, |x, _| {
+            let value = (x * 255 / (width - 1)) as u8;
+            Luma([if mirrored { 255 - value } else { value }])
+        })
+    }
+
+    #[test]
+    fn test_hamming_distance() {
+        assert_eq!(hamming_distance(0, 0), 0);
+        assert_eq!(hamming_distance(0b1011, 0b0001), 2);
+        assert_eq!(hamming_distance(u64::MAX, 0), 64);
+    }
+
+    #[test]
+    fn test_is_image_extension() {
+        assert!(is_image_extension("PNG"));
+        assert!(is_image_extension("jpeg"));
+        assert!(!is_image_extension("svg"));
+    }
+
+    #[test]
+    fn test_resized_copy_is_similar() {
+        let root = temp_root("similar-resized");
+        gradient(64, 64, false)
+            .save(root.join("original.png"))
+            .unwrap();
+        gradient(200, 120, false)
+            .save(root.join("resized.png"))
+            .unwrap();
+        gradient(64, 64, true)
+            .save(root.join("mirrored.png"))
+            .unwrap();
+        fs::write(root.join("notes.txt"), "not an image").unwrap();
+
+        let reference = dhash(&root.join("original.png")).unwrap();
+        let found = find_similar_images(&root, reference, 10, &SearchToken::default());
+        let names: Vec<_> = found
+            .iter()
+            .map(|(path, _)| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+
+        assert_eq!(names, vec!["original.png", "resized.png"]);
+        assert_eq!(found[0].1, 0);
+        let _ = fs::remove_dir_all(&root);
+    }
+}
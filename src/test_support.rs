@@ -0,0 +1,13 @@
+// Helpers shared by the unit tests.
+
+use std::fs;
+use std::path::PathBuf;
+
+// Empty folder "quick-findr-<name>" in the temporary directory, wiped first;
+// names start with the module ("hash-sizes") so that tests never share one.
+pub fn temp_root(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("quick-findr-{}", name));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    root
+}
@@ -0,0 +1,231 @@
+// Thumbnails of image results (png, jpg, gif, bmp, webp, svg). One loader
+// thread decodes them in request order, so the UI thread never decodes an
+// image; the last ones decoded are kept in a small LRU cache keyed by path,
+// modification time and size.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+// Longest side of the thumbnail in a result row (38px icon, doubled for
+// high-DPI screens) and of the preview in the detail pane.
+pub const ICON_SIZE: u32 = 76;
+pub const PREVIEW_SIZE: u32 = 288;
+// Thumbnails kept in memory.
+const CACHE_CAPACITY: usize = 256;
+// Larger files are not decoded.
+const MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+
+// Decoded pixels, RGBA8 (straight alpha), row by row.
+#[derive(Debug)]
+pub struct Thumbnail {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+pub fn is_supported(extension: &str) -> bool {
+    matches!(
+        extension.to_lowercase().as_str(),
+        "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" | "svg"
+    )
+}
+
+// The image scaled to fit `max_side` (SVG drawn at that size); None when it
+// cannot be read or decoded.
+pub fn decode(path: &Path, max_side: u32) -> Option<Thumbnail> {
+    if std::fs::metadata(path).ok()?.len() > MAX_FILE_SIZE {
+        return None;
+    }
+    let is_svg = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("svg"));
+    if is_svg {
+        return decode_svg(path, max_side);
+    }
+    let image = image::ImageReader::open(path)
+        .ok()?
+        .with_guessed_format()
+        .ok()?
+        .decode()
+        .ok()?
+        .thumbnail(max_side, max_side)
+        .to_rgba8();
+    Some(Thumbnail {
+        width: image.width(),
+        height: image.height(),
+        rgba: image.into_raw(),
+    })
+}
+
+fn decode_svg(path: &Path, max_side: u32) -> Option<Thumbnail> {
+    use resvg::tiny_skia::{Pixmap, Transform};
+    use resvg::usvg::{Options, Tree};
+
+    let data = std::fs::read(path).ok()?;
+    let tree = Tree::from_data(&data, &Options::default()).ok()?;
+    let size = tree.size();
+    let scale = max_side as f32 / size.width().max(size.height());
+    let width = ((size.width() * scale).round() as u32).max(1);
+    let height = ((size.height() * scale).round() as u32).max(1);
+    let mut pixmap = Pixmap::new(width, height)?;
+    resvg::render(
+        &tree,
+        Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+    // The pixmap is premultiplied.
+    let rgba = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+    Some(Thumbnail {
+        width,
+        height,
+        rgba,
+    })
+}
+
+type CacheKey = (PathBuf, Option<SystemTime>, u32);
+
+// Least recently used thumbnails are dropped first.
+struct Cache {
+    entries: HashMap<CacheKey, Arc<Thumbnail>>,
+    // Oldest use first.
+    order: VecDeque<CacheKey>,
+    capacity: usize,
+}
+
+impl Cache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    fn get(&mut self, key: &CacheKey) -> Option<Arc<Thumbnail>> {
+        let thumbnail = self.entries.get(key)?.clone();
+        self.touch(key);
+        Some(thumbnail)
+    }
+
+    fn insert(&mut self, key: CacheKey, thumbnail: Arc<Thumbnail>) {
+        if self.entries.insert(key.clone(), thumbnail).is_some() {
+            self.touch(&key);
+            return;
+        }
+        self.order.push_back(key);
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    fn touch(&mut self, key: &CacheKey) {
+        if let Some(position) = self.order.iter().position(|k| k == key) {
+            if let Some(key) = self.order.remove(position) {
+                self.order.push_back(key);
+            }
+        }
+    }
+}
+
+type Done = Box<dyn FnOnce(Option<Arc<Thumbnail>>) + Send>;
+
+// Queue of the loader thread, started by the first request.
+static LOADER: Mutex<Option<Sender<(PathBuf, u32, Done)>>> = Mutex::new(None);
+
+// Decodes a thumbnail on the loader thread (or takes it from the cache) and
+// hands it to `done`, on that thread.
+pub fn request(
+    path: PathBuf,
+    max_side: u32,
+    done: impl FnOnce(Option<Arc<Thumbnail>>) + Send + 'static,
+) {
+    let mut loader = LOADER.lock().unwrap_or_else(|e| e.into_inner());
+    let sender = loader.get_or_insert_with(spawn_loader);
+    if let Err(mpsc::SendError(job)) = sender.send((path, max_side, Box::new(done))) {
+        // The loader stopped (a decoder panicked): start another one.
+        *loader = Some(spawn_loader());
+        if let Some(sender) = loader.as_ref() {
+            let _ = sender.send(job);
+        }
+    }
+}
+
+fn spawn_loader() -> Sender<(PathBuf, u32, Done)> {
+    let (tx, rx) = mpsc::channel::<(PathBuf, u32, Done)>();
+    std::thread::spawn(move || {
+        let mut cache = Cache::new(CACHE_CAPACITY);
+        for (path, max_side, done) in rx {
+            let modified = std::fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok();
+            let key = (path, modified, max_side);
+            let thumbnail = cache.get(&key).or_else(|| {
+                let thumbnail = Arc::new(decode(&key.0, max_side)?);
+                cache.insert(key.clone(), thumbnail.clone());
+                Some(thumbnail)
+            });
+            done(thumbnail);
+        }
+    });
+    tx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(name: &str) -> CacheKey {
+        (PathBuf::from(name), None, ICON_SIZE)
+    }
+
+    fn pixel() -> Arc<Thumbnail> {
+        Arc::new(Thumbnail {
+            width: 1,
+            height: 1,
+            rgba: vec![0, 0, 0, 255],
+        })
+    }
+
+    #[test]
+    fn test_cache_drops_least_recently_used() {
+        let mut cache = Cache::new(2);
+        cache.insert(key("a.png"), pixel());
+        cache.insert(key("b.png"), pixel());
+        assert!(cache.get(&key("a.png")).is_some());
+        cache.insert(key("c.png"), pixel());
+
+        assert!(cache.get(&key("a.png")).is_some());
+        assert!(cache.get(&key("b.png")).is_none());
+        assert!(cache.get(&key("c.png")).is_some());
+    }
+
+    #[test]
+    fn test_decode_scales_to_fit() {
+        let root = std::env::temp_dir().join("quick-findr-thumbnails");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        image::RgbaImage::from_pixel(200, 100, image::Rgba([255, 0, 0, 255]))
+            .save(root.join("wide.png"))
+            .unwrap();
+        std::fs::write(root.join("broken.png"), "not an image").unwrap();
+
+        let thumbnail = decode(&root.join("wide.png"), ICON_SIZE).unwrap();
+        assert_eq!((thumbnail.width, thumbnail.height), (76, 38));
+        assert_eq!(&thumbnail.rgba[..4], &[255, 0, 0, 255]);
+        assert!(decode(&root.join("broken.png"), ICON_SIZE).is_none());
+        assert!(is_supported("JPG") && !is_supported("txt"));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,117 @@
+// Watches a folder: file system events are collected until the folder has
+// been quiet for a moment, then handed over in one batch. Keeps the indexes of
+// a folder current and re-runs a live search.
+
+use crate::i18n::tr;
+use crate::{index, indexer};
+use chrono::Local;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+pub struct Debounce {
+    // Changes are handed over once no event came for this long...
+    pub quiet_period: Duration,
+    // ...or at the latest this long after the first one (a log written nonstop).
+    pub max_delay: Duration,
+}
+
+pub const INDEX_DEBOUNCE: Debounce = Debounce {
+    quiet_period: Duration::from_secs(2),
+    max_delay: Duration::from_secs(30),
+};
+
+// A live search shows changes sooner.
+pub const LIVE_DEBOUNCE: Debounce = Debounce {
+    quiet_period: Duration::from_secs(1),
+    max_delay: Duration::from_secs(5),
+};
+
+// Watches while alive; the worker thread stops once it is dropped.
+pub struct FolderWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+// `on_changes` receives each batch of changed paths (created, modified,
+// deleted or renamed).
+pub fn watch(
+    root: &Path,
+    debounce: Debounce,
+    on_changes: impl Fn(Vec<PathBuf>) + Send + 'static,
+) -> notify::Result<FolderWatcher> {
+    // Writes to the indexes themselves must not feed back into the watcher.
+    let own_files = [index::index_dir(), indexer::index_dir()];
+    let (event_tx, event_rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event {
+            if !matches!(event.kind, EventKind::Access(_)) {
+                let _ = event_tx.send(event.paths);
+            }
+        }
+    })?;
+    watcher.watch(root, RecursiveMode::Recursive)?;
+
+    std::thread::spawn(move || {
+        let mut pending: HashSet<PathBuf> = HashSet::new();
+        let mut first_event = Instant::now();
+        loop {
+            let quiet = match event_rx.recv_timeout(debounce.quiet_period) {
+                Ok(paths) => {
+                    if pending.is_empty() {
+                        first_event = Instant::now();
+                    }
+                    pending.extend(
+                        paths
+                            .into_iter()
+                            .filter(|path| !own_files.iter().any(|own| path.starts_with(own))),
+                    );
+                    false
+                }
+                Err(RecvTimeoutError::Timeout) => true,
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+            if !pending.is_empty() && (quiet || first_event.elapsed() >= debounce.max_delay) {
+                on_changes(pending.drain().collect());
+            }
+        }
+    });
+
+    Ok(FolderWatcher { _watcher: watcher })
+}
+
+// Applies the changes to the indexes of `root`, whichever exist; `on_update`
+// receives the status line of each applied batch.
+pub fn watch_indexes(
+    root: PathBuf,
+    on_update: impl Fn(String) + Send + 'static,
+) -> notify::Result<FolderWatcher> {
+    watch(&root.clone(), INDEX_DEBOUNCE, move |changed| {
+        if let Some(status) = apply(&root, &changed) {
+            on_update(status);
+        }
+    })
+}
+
+// Status line of the update; None when the folder has no index (yet).
+fn apply(root: &Path, changed: &[PathBuf]) -> Option<String> {
+    let mut updated = Vec::new();
+    match index::apply_changes(&index::index_dir(), root, changed) {
+        Ok(Some(files)) => updated.push(tr!("{} file names", files)),
+        Ok(None) => {}
+        Err(err) => return Some(tr!("Error: Unable to update the index: {}", err)),
+    }
+    match indexer::update_files(&indexer::index_dir(), root, changed) {
+        Ok(Some(report)) => updated.push(tr!("{} files with content", report.files)),
+        Ok(None) => {}
+        Err(err) => return Some(tr!("Error: Unable to update the content index: {}", err)),
+    }
+    (!updated.is_empty()).then(|| {
+        tr!(
+            "Index updated {} ({})",
+            Local::now().format("%H:%M:%S"),
+            updated.join(", ")
+        )
+    })
+}